@@ -2,7 +2,8 @@ use anyhow::Result;
 use async_trait::async_trait;
 use clawden_core::{
     AgentConfig, AgentHandle, AgentMessage, AgentMetrics, AgentResponse, ClawAdapter, ClawRuntime,
-    EventStream, HealthStatus, InstallConfig, RuntimeConfig, RuntimeMetadata, Skill, SkillManifest,
+    EventStream, ExecutionMode, HealthStatus, HealthThresholds, InstallConfig, ProcessManager,
+    RuntimeConfig, RuntimeMetadata, Skill, SkillManifest,
 };
 
 pub struct PicoClawAdapter;
@@ -38,16 +39,21 @@ impl ClawAdapter for PicoClawAdapter {
         Ok(())
     }
 
-    async fn health(&self, _handle: &AgentHandle) -> Result<HealthStatus> {
-        Ok(HealthStatus::Unknown)
+    async fn health(&self, handle: &AgentHandle) -> Result<HealthStatus> {
+        let process_manager = ProcessManager::new(ExecutionMode::Auto)?;
+        process_manager.sample_health(handle.runtime.as_slug(), &HealthThresholds::default())
     }
 
-    async fn metrics(&self, _handle: &AgentHandle) -> Result<AgentMetrics> {
-        Ok(AgentMetrics {
-            cpu_percent: 0.0,
-            memory_mb: 0.0,
-            queue_depth: 0,
-        })
+    async fn metrics(&self, handle: &AgentHandle) -> Result<AgentMetrics> {
+        let process_manager = ProcessManager::new(ExecutionMode::Auto)?;
+        match process_manager.sample_metrics(handle.runtime.as_slug()) {
+            Ok(metrics) => Ok(metrics),
+            Err(_) => Ok(AgentMetrics {
+                cpu_percent: 0.0,
+                memory_mb: 0.0,
+                queue_depth: 0,
+            }),
+        }
     }
 
     async fn send(&self, _handle: &AgentHandle, message: &AgentMessage) -> Result<AgentResponse> {
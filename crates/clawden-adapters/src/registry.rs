@@ -45,4 +45,48 @@ impl AdapterRegistry {
             }
         })
     }
+
+    /// Every adapter whose capabilities cover all of `required` (case
+    /// insensitive), ranked highest-scoring first so callers can route to
+    /// the best candidate instead of the first one `HashMap` iteration
+    /// happens to yield. Score is the number of `required` entries matched
+    /// (equal for every returned adapter, since all of them satisfy the
+    /// full set); runtime name breaks ties so the ordering is stable across
+    /// calls and process restarts.
+    pub fn find_runtimes(&self, required: &[&str]) -> Vec<ClawRuntime> {
+        let mut scored: Vec<(ClawRuntime, usize)> = self
+            .adapters
+            .iter()
+            .filter_map(|(runtime, adapter)| {
+                let capabilities = adapter.metadata().capabilities;
+                let matched = required
+                    .iter()
+                    .filter(|req| {
+                        capabilities
+                            .iter()
+                            .any(|candidate| candidate.eq_ignore_ascii_case(req))
+                    })
+                    .count();
+                if matched == required.len() {
+                    Some((runtime.clone(), matched))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        scored.sort_by(|(a_runtime, a_score), (b_runtime, b_score)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| format!("{a_runtime:?}").cmp(&format!("{b_runtime:?}")))
+        });
+
+        scored.into_iter().map(|(runtime, _)| runtime).collect()
+    }
+
+    /// The top-ranked [`find_runtimes`] candidate, if any adapter satisfies
+    /// `required`.
+    pub fn best_runtime(&self, required: &[&str]) -> Option<ClawRuntime> {
+        self.find_runtimes(required).into_iter().next()
+    }
 }
@@ -0,0 +1,80 @@
+//! Backgrounding and foreground-attach support for `clawden up`.
+//!
+//! `clawden up` normally stays in the foreground, waiting on Ctrl-C and
+//! forwarding the shutdown to every runtime it started. `--detach` instead
+//! double-forks the supervising process into the background the usual Unix
+//! daemon way (fork, `setsid`, fork again, redirect stdio to `/dev/null`) so
+//! the terminal is freed immediately. `clawden attach <runtime>` goes the
+//! other direction: it `execve`s straight into that runtime's binary,
+//! replacing the CLI process so the user gets the runtime's raw stdio and a
+//! Ctrl-C that only affects the one runtime.
+
+use std::fs::File;
+use std::io;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+
+/// Double-forks the current process into the background, writing the final
+/// daemon's pid to `pidfile`. Returns in the grandchild only; the original
+/// process and the intermediate child both exit before this returns.
+pub fn daemonize(pidfile: &Path) -> Result<()> {
+    // First fork: the parent exits so the shell regains its prompt immediately.
+    match unsafe { libc::fork() } {
+        -1 => anyhow::bail!("fork() failed: {}", io::Error::last_os_error()),
+        0 => {}
+        _ => std::process::exit(0),
+    }
+
+    if unsafe { libc::setsid() } == -1 {
+        anyhow::bail!("setsid() failed: {}", io::Error::last_os_error());
+    }
+
+    // Second fork so the daemon can never re-acquire a controlling terminal.
+    match unsafe { libc::fork() } {
+        -1 => anyhow::bail!("fork() failed: {}", io::Error::last_os_error()),
+        0 => {}
+        _ => std::process::exit(0),
+    }
+
+    redirect_stdio_to_dev_null()?;
+
+    if let Some(parent) = pidfile.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating {}", parent.display()))?;
+    }
+    std::fs::write(pidfile, std::process::id().to_string())
+        .with_context(|| format!("writing {}", pidfile.display()))?;
+
+    Ok(())
+}
+
+fn redirect_stdio_to_dev_null() -> Result<()> {
+    let dev_null = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+        .context("opening /dev/null")?;
+    let fd = dev_null.as_raw_fd();
+    for target in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+        if unsafe { libc::dup2(fd, target) } == -1 {
+            anyhow::bail!("dup2 failed redirecting fd {target}: {}", io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Pidfile path for the `up --detach` supervisor.
+pub fn supervisor_pidfile(clawden_home: &Path) -> PathBuf {
+    clawden_home.join("up.pid")
+}
+
+/// Replaces the current process image with `executable`, handing it the
+/// terminal's stdio directly. Never returns on success.
+pub fn exec_replace(executable: &Path, args: &[String]) -> Result<()> {
+    let err = Command::new(executable).args(args).exec();
+    Err(err).with_context(|| format!("exec failed for {}", executable.display()))
+}
@@ -1,11 +1,17 @@
+mod daemon;
+mod tunnel;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use clawden_config::FeatureToggle;
+use clawden_core::audit::{AuditEvent, AuditLog, AuditQuery, AuditSelector};
 use clawden_core::{
     ClawRuntime, ExecutionMode, LifecycleManager, ProcessManager, RuntimeInstaller,
+    RuntimeLaunchSpec,
 };
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -21,7 +27,17 @@ struct Cli {
 
 #[derive(Debug, Subcommand)]
 enum Commands {
-    Init,
+    /// Scaffold or update a project's clawden.yaml from feature toggles.
+    Init {
+        /// Directory to scaffold into (defaults to the current directory).
+        project: Option<String>,
+        /// Print the diff that would be applied without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// Feature toggles, e.g. `--redis=on --telegram=off`.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        toggles: Vec<String>,
+    },
     /// Install runtimes for direct execution mode.
     Install {
         runtime: Option<String>,
@@ -30,14 +46,44 @@ enum Commands {
         #[arg(long)]
         list: bool,
     },
-    /// Remove a directly installed runtime.
+    /// Remove a directly installed runtime, or a single version of it with
+    /// `runtime@version`.
     Uninstall {
         runtime: String,
     },
+    /// List installed versions of a runtime.
+    Versions {
+        runtime: String,
+        /// Query the upstream GitHub releases API instead of local installs.
+        #[arg(long)]
+        remote: bool,
+    },
+    /// Repoint `current` at the previously-active version without
+    /// re-downloading it.
+    Rollback {
+        runtime: String,
+    },
+    /// Repoint a runtime's `current` symlink at an already-installed
+    /// version, e.g. `clawden default zeroclaw@1.2.3`.
+    Default {
+        runtime_spec: String,
+    },
+    /// Write PATH-able wrapper scripts for every installed runtime.
+    Shims {
+        /// Directory to write shims into (defaults to ~/.clawden/bin).
+        bin_dir: Option<String>,
+    },
     /// Start all runtimes from clawden.yaml
     Up {
         /// Specific runtimes to start (starts all if empty)
         runtimes: Vec<String>,
+        /// Double-fork the supervisor into the background and free the terminal.
+        #[arg(long)]
+        detach: bool,
+    },
+    /// Hand the terminal directly to a single running runtime's stdio.
+    Attach {
+        runtime: String,
     },
     /// Run a single runtime
     Run {
@@ -51,6 +97,16 @@ enum Commands {
         /// Restart on failure policy.
         #[arg(long)]
         restart: Option<String>,
+        /// Watch these paths (the executable and config/skill directories)
+        /// and restart the runtime whenever they change, instead of
+        /// returning once it starts.
+        #[arg(long)]
+        watch: Vec<PathBuf>,
+        /// Perform a zero-downtime graceful restart instead of a plain
+        /// start, binding (or reusing) a listening socket at this address
+        /// and handing it to the runtime via systemd socket activation.
+        #[arg(long)]
+        graceful: Option<String>,
     },
     /// Show running runtimes
     Ps,
@@ -70,13 +126,75 @@ enum Commands {
         #[arg(long, default_value_t = 8080)]
         port: u16,
     },
-    /// Check local direct-install prerequisites.
-    Doctor,
+    /// Check local direct-install prerequisites and runtime health.
+    Doctor {
+        /// Print the report as JSON instead of a human table.
+        #[arg(long)]
+        json: bool,
+    },
     /// Channel management
     Channels {
         #[command(subcommand)]
         command: Option<ChannelCommand>,
     },
+    /// Query the local audit trail (~/.clawden/logs/audit.log).
+    Audit {
+        /// Max events to return.
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        /// Only events strictly before this unix-ms timestamp.
+        #[arg(long, conflicts_with_all = ["after", "since", "until"])]
+        before: Option<u64>,
+        /// Only events strictly after this unix-ms timestamp.
+        #[arg(long, conflicts_with_all = ["before", "since", "until"])]
+        after: Option<u64>,
+        /// Start of an inclusive time range (pair with --until).
+        #[arg(long, requires = "until", conflicts_with_all = ["before", "after"])]
+        since: Option<u64>,
+        /// End of an inclusive time range (pair with --since).
+        #[arg(long, requires = "since", conflicts_with_all = ["before", "after"])]
+        until: Option<u64>,
+        /// Filter by the session/actor tag (e.g. "local" or a tunnel session id).
+        #[arg(long)]
+        actor: Option<String>,
+        #[arg(long)]
+        action: Option<String>,
+        /// Filter by the runtime/target the event was recorded against.
+        #[arg(long)]
+        target: Option<String>,
+    },
+    /// Drive this host's runtimes from another machine through a relay.
+    Tunnel {
+        #[command(subcommand)]
+        command: Option<TunnelCommand>,
+        /// Host id to register under (defaults to the local hostname).
+        #[arg(long)]
+        host_id: Option<String>,
+    },
+    /// Internal: runs the Rust-native restart supervisor for a single
+    /// runtime. `ProcessManager::start_direct` spawns this as a detached
+    /// child in place of the old generated shell script when
+    /// `--restart=on-failure` is set; it is not meant to be invoked by hand.
+    #[command(hide = true)]
+    Supervise {
+        runtime: String,
+        exec_path: String,
+        log_path: String,
+        audit_path: String,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TunnelCommand {
+    /// Connect to a tunnel host registered with the relay.
+    Connect {
+        host_id: String,
+        /// Access token printed by the host at `clawden tunnel` startup.
+        #[arg(long)]
+        token: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -97,7 +215,43 @@ async fn main() -> Result<()> {
     let mut manager = LifecycleManager::new(registry.adapters_map());
 
     match cli.command {
-        Commands::Init => println!("clawden init scaffold is not implemented yet"),
+        Commands::Init {
+            project,
+            dry_run,
+            toggles,
+        } => {
+            let project_dir = PathBuf::from(project.unwrap_or_else(|| ".".to_string()));
+            let parsed: Vec<FeatureToggle> = toggles
+                .iter()
+                .filter_map(|arg| {
+                    let toggle = FeatureToggle::parse(arg);
+                    if toggle.is_none() {
+                        eprintln!("ignoring unrecognized toggle: {arg}");
+                    }
+                    toggle
+                })
+                .collect();
+
+            let changes = if parsed.is_empty() {
+                Vec::new()
+            } else {
+                clawden_config::scaffold_project(&project_dir, &parsed, dry_run)
+                    .map_err(|e| anyhow::anyhow!(e))?
+            };
+
+            if changes.is_empty() {
+                println!("No toggles given; clawden.yaml left untouched");
+            } else {
+                if dry_run {
+                    println!("Dry run — would apply:");
+                } else {
+                    println!("Applied to {}:", project_dir.join("clawden.yaml").display());
+                }
+                for change in changes {
+                    println!("  {change}");
+                }
+            }
+        }
         Commands::Install { runtime, all, list } => {
             if list {
                 let installed = installer.list_installed()?;
@@ -143,10 +297,57 @@ async fn main() -> Result<()> {
             );
         }
         Commands::Uninstall { runtime } => {
-            installer.uninstall_runtime(&runtime)?;
-            println!("Uninstalled {runtime}");
+            let (runtime_name, version) = parse_runtime_version(&runtime);
+            match version {
+                Some(version) => {
+                    installer.uninstall_version(&runtime_name, &version)?;
+                    println!("Uninstalled {runtime_name}@{version}");
+                }
+                None => {
+                    installer.uninstall_runtime(&runtime_name)?;
+                    println!("Uninstalled {runtime_name}");
+                }
+            }
         }
-        Commands::Up { runtimes } => {
+        Commands::Versions { runtime, remote } => {
+            let versions = if remote {
+                installer.available_versions(&runtime)?
+            } else {
+                installer.installed_versions(&runtime)?
+            };
+            if versions.is_empty() {
+                println!("No versions of {runtime} found");
+            } else {
+                for version in versions {
+                    println!("{version}");
+                }
+            }
+        }
+        Commands::Rollback { runtime } => {
+            let version = installer.rollback(&runtime)?;
+            println!("{runtime} rolled back to {version}");
+        }
+        Commands::Default { runtime_spec } => {
+            let (runtime_name, version) = parse_runtime_version(&runtime_spec);
+            let Some(version) = version else {
+                anyhow::bail!("specify a version (e.g. clawden default zeroclaw@1.2.3)");
+            };
+            installer.set_default(&runtime_name, &version)?;
+            println!("{runtime_name} default is now {version}");
+        }
+        Commands::Shims { bin_dir } => {
+            let bin_dir = bin_dir
+                .map(PathBuf::from)
+                .unwrap_or_else(|| installer.root_dir().join("bin"));
+            installer.generate_shims(&bin_dir)?;
+            println!("Wrote runtime shims to {}", bin_dir.display());
+        }
+        Commands::Up { runtimes, detach } => {
+            if detach {
+                let pidfile = daemon::supervisor_pidfile(installer.root_dir());
+                daemon::daemonize(&pidfile)?;
+            }
+
             let mode = process_manager.resolve_mode(cli.no_docker || env_no_docker_enabled());
             let target_runtimes = if runtimes.is_empty() {
                 installer
@@ -163,32 +364,98 @@ async fn main() -> Result<()> {
                 return Ok(());
             }
 
-            for runtime in target_runtimes {
+            for runtime in &target_runtimes {
                 match mode {
                     ExecutionMode::Docker => {
-                        println!("Docker mode is available; direct processes are not started for {runtime}");
+                        let info = process_manager.start_docker(
+                            runtime,
+                            &default_docker_image(runtime),
+                            &RuntimeLaunchSpec::new(),
+                            &clawden_core::ResourceLimits {
+                                cpu_cores: None,
+                                memory_mb: None,
+                            },
+                        )?;
+                        append_audit_file("runtime.start", runtime, "ok")?;
+                        println!("Started {runtime} in a Docker container (pid {})", info.pid);
+                    }
+                    ExecutionMode::Oci => {
+                        let executable =
+                            installer.runtime_executable(runtime).ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "Runtime '{}' not installed. Run 'clawden install {}' first.",
+                                    runtime,
+                                    runtime
+                                )
+                            })?;
+                        let info = process_manager.start_oci(
+                            runtime,
+                            &executable,
+                            &[],
+                            &clawden_core::ResourceLimits {
+                                cpu_cores: None,
+                                memory_mb: None,
+                            },
+                        )?;
+                        append_audit_file("runtime.start", runtime, "ok")?;
+                        println!("Started {runtime} in an OCI container (pid {})", info.pid);
                     }
                     ExecutionMode::Direct | ExecutionMode::Auto => {
                         let executable =
-                            installer.runtime_executable(&runtime).ok_or_else(|| {
+                            installer.runtime_executable(runtime).ok_or_else(|| {
                                 anyhow::anyhow!(
                                     "Runtime '{}' not installed. Run 'clawden install {}' first.",
                                     runtime,
                                     runtime
                                 )
                             })?;
-                        let info = process_manager.start_direct(&runtime, &executable, &[])?;
-                        append_audit_file("runtime.start", &runtime, "ok")?;
+                        let info = process_manager.start_direct(
+                            runtime,
+                            &executable,
+                            &RuntimeLaunchSpec::new(),
+                        )?;
+                        append_audit_file("runtime.start", runtime, "ok")?;
                         println!("Started {runtime} (pid {})", info.pid);
                     }
                 }
             }
+
+            println!("All runtimes started. Waiting for Ctrl-C to stop them...");
+            tokio::signal::ctrl_c().await?;
+            println!("Received interrupt, stopping runtimes...");
+            for runtime in &target_runtimes {
+                if let Err(e) = process_manager.stop(runtime) {
+                    eprintln!("failed to stop {runtime}: {e}");
+                    continue;
+                }
+                append_audit_file("runtime.stop", runtime, "ok")?;
+            }
+            if detach {
+                let pidfile = daemon::supervisor_pidfile(installer.root_dir());
+                let _ = std::fs::remove_file(pidfile);
+            }
+        }
+        Commands::Attach { runtime } => {
+            let executable = installer.runtime_executable(&runtime).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Runtime '{}' not installed. Run 'clawden install {}' first.",
+                    runtime,
+                    runtime
+                )
+            })?;
+            // Two processes can't own the same pid-file slot and log file, so
+            // stop any existing tracked instance before taking it over.
+            let _ = process_manager.stop(&runtime);
+            append_audit_file("runtime.attach", &runtime, "ok")?;
+            daemon::exec_replace(&executable, &[])?;
         }
         Commands::Run {
             runtime,
             channel,
             tools,
             restart,
+            watch,
+            graceful,
         } => {
             let rt = runtime.unwrap_or_else(|| "zeroclaw".to_string());
             let tools_list = tools
@@ -221,6 +488,32 @@ async fn main() -> Result<()> {
                         rt
                     );
                 }
+                ExecutionMode::Oci => {
+                    let executable = installer.runtime_executable(&rt).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Runtime '{}' not installed. Run 'clawden install {}' to install it.",
+                            rt,
+                            rt
+                        )
+                    })?;
+
+                    let info = process_manager.start_oci(
+                        &rt,
+                        &executable,
+                        &[],
+                        &clawden_core::ResourceLimits {
+                            cpu_cores: None,
+                            memory_mb: None,
+                        },
+                    )?;
+                    append_audit_file("runtime.start", &rt, "ok")?;
+                    println!(
+                        "Started {} in an OCI container (pid {}, logs: {})",
+                        rt,
+                        info.pid,
+                        info.log_path.display()
+                    );
+                }
                 ExecutionMode::Direct | ExecutionMode::Auto => {
                     let executable = installer.runtime_executable(&rt).ok_or_else(|| {
                         anyhow::anyhow!(
@@ -234,18 +527,37 @@ async fn main() -> Result<()> {
                     if !channel.is_empty() {
                         args.push(format!("--channels={}", channel.join(",")));
                     }
-                    if let Some(policy) = restart {
+                    if let Some(policy) = &restart {
                         args.push(format!("--restart={policy}"));
                     }
+                    let spec = RuntimeLaunchSpec::new().with_args(args);
 
-                    let info = process_manager.start_direct(&rt, &executable, &args)?;
-                    append_audit_file("runtime.start", &rt, "ok")?;
-                    println!(
-                        "Started {} in direct mode (pid {}, logs: {})",
-                        rt,
-                        info.pid,
-                        info.log_path.display()
-                    );
+                    if let Some(bind_addr) = &graceful {
+                        let info =
+                            process_manager.graceful_restart(&rt, &executable, &spec, bind_addr)?;
+                        append_audit_file("runtime.graceful_restart", &rt, "ok")?;
+                        println!(
+                            "Gracefully restarted {} on {} (pid {}, logs: {})",
+                            rt,
+                            bind_addr,
+                            info.pid,
+                            info.log_path.display()
+                        );
+                    } else {
+                        let info = process_manager.start_direct(&rt, &executable, &spec)?;
+                        append_audit_file("runtime.start", &rt, "ok")?;
+                        println!(
+                            "Started {} in direct mode (pid {}, logs: {})",
+                            rt,
+                            info.pid,
+                            info.log_path.display()
+                        );
+                    }
+
+                    if !watch.is_empty() {
+                        println!("Watching {watch:?} for changes; restarting {rt} on edits...");
+                        process_manager.watch(&rt, &executable, &spec, &watch)?;
+                    }
                 }
             }
         }
@@ -255,12 +567,12 @@ async fn main() -> Result<()> {
                 println!("No running runtimes");
             } else {
                 println!(
-                    "{:<14} {:<8} {:<10} {:<10} {:<10} LOG",
-                    "RUNTIME", "PID", "MODE", "STATE", "HEALTH"
+                    "{:<14} {:<8} {:<10} {:<10} {:<10} {:<10} LOG",
+                    "RUNTIME", "PID", "MODE", "STATE", "HEALTH", "CONTAINER"
                 );
                 for status in statuses {
                     println!(
-                        "{:<14} {:<8} {:<10} {:<10} {:<10} {}",
+                        "{:<14} {:<8} {:<10} {:<10} {:<10} {:<10} {}",
                         status.runtime,
                         status
                             .pid
@@ -269,6 +581,7 @@ async fn main() -> Result<()> {
                         format!("{:?}", status.mode),
                         if status.running { "running" } else { "stopped" },
                         status.health,
+                        status.container_state.as_deref().unwrap_or("-"),
                         status.log_path.display(),
                     );
                 }
@@ -321,18 +634,19 @@ async fn main() -> Result<()> {
                 anyhow::bail!("clawden-server exited with status {status}");
             }
         }
-        Commands::Doctor => {
-            println!("docker_available={}", ProcessManager::docker_available());
-            println!("node_available={}", command_exists("node"));
-            println!("npm_available={}", command_exists("npm"));
-            println!("git_available={}", command_exists("git"));
-            println!(
-                "curl_available={}",
-                command_exists("curl") || command_exists("wget")
-            );
-            println!("clawden_home={}", installer.root_dir().display());
-            for row in installer.list_installed()? {
-                println!("installed={}@{}", row.runtime, row.version);
+        Commands::Doctor { json } => {
+            let diagnostics = installer.diagnose()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+            } else {
+                println!("docker_available={}", ProcessManager::docker_available());
+                println!(
+                    "oci_runtime={}",
+                    ProcessManager::oci_runtime_available().unwrap_or("none")
+                );
+                println!("clawden_home={}", installer.root_dir().display());
+                println!();
+                print!("{diagnostics}");
             }
         }
         Commands::Channels { command } => match command {
@@ -355,11 +669,88 @@ async fn main() -> Result<()> {
                 }
             }
         },
+        Commands::Audit {
+            limit,
+            before,
+            after,
+            since,
+            until,
+            actor,
+            action,
+            target,
+        } => {
+            let selector = match (before, after, since, until) {
+                (Some(ts), None, None, None) => AuditSelector::Before { ts, limit },
+                (None, Some(ts), None, None) => AuditSelector::After { ts, limit },
+                (None, None, Some(start), Some(end)) => AuditSelector::Between { start, end, limit },
+                _ => AuditSelector::Latest { limit },
+            };
+            let query = AuditQuery {
+                selector,
+                actor,
+                action,
+                target,
+                correlation_id: None,
+            };
+            let log = load_local_audit_log()?;
+            let page = log.query(&query);
+            if page.events.is_empty() {
+                println!("No matching audit events");
+            } else {
+                println!("{:<16} {:<24} {:<14} SESSION", "TIMESTAMP_MS", "ACTION", "TARGET");
+                for event in &page.events {
+                    println!(
+                        "{:<16} {:<24} {:<14} {}",
+                        event.timestamp_unix_ms, event.action, event.target, event.actor
+                    );
+                }
+                if let Some(cursor) = page.next_cursor {
+                    println!(
+                        "-- page on with --before {} for older events",
+                        cursor.timestamp_unix_ms
+                    );
+                }
+            }
+        }
+        Commands::Tunnel { command, host_id } => match command {
+            None => {
+                let host_id = host_id.unwrap_or_else(local_hostname);
+                tunnel::run_host(&host_id).await?;
+            }
+            Some(TunnelCommand::Connect { host_id, token }) => {
+                tunnel::connect_client(&host_id, &token).await?;
+            }
+        },
+        Commands::Supervise {
+            runtime,
+            exec_path,
+            log_path,
+            audit_path,
+            args,
+        } => {
+            clawden_core::supervisor::run(
+                &runtime,
+                Path::new(&exec_path),
+                &args,
+                Path::new(&log_path),
+                Path::new(&audit_path),
+            )?;
+        }
     }
 
     Ok(())
 }
 
+fn local_hostname() -> String {
+    Command::new("hostname")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "clawden-host".to_string())
+}
+
 fn parse_runtime(value: &str) -> Result<ClawRuntime> {
     ClawRuntime::from_str_loose(value).ok_or_else(|| anyhow::anyhow!("unknown runtime: {value}"))
 }
@@ -382,6 +773,12 @@ fn command_exists(command: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Default image reference for a runtime's Docker container when no
+/// `InstallConfig::image` override is plumbed through: `clawden/<runtime>:latest`.
+fn default_docker_image(runtime: &str) -> String {
+    format!("clawden/{runtime}:latest")
+}
+
 fn env_no_docker_enabled() -> bool {
     std::env::var("CLAWDEN_NO_DOCKER")
         .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
@@ -389,6 +786,18 @@ fn env_no_docker_enabled() -> bool {
 }
 
 fn append_audit_file(action: &str, runtime: &str, outcome: &str) -> Result<()> {
+    append_audit_file_for_session(action, runtime, outcome, "local")
+}
+
+/// Same as [`append_audit_file`], tagged with a session id so remote tunnel
+/// actions (session id = the client's tunnel session) can be told apart
+/// from local CLI invocations (session id `"local"`) in the audit trail.
+pub(crate) fn append_audit_file_for_session(
+    action: &str,
+    runtime: &str,
+    outcome: &str,
+    session_id: &str,
+) -> Result<()> {
     let home = std::env::var("HOME")?;
     let log_dir = PathBuf::from(home).join(".clawden").join("logs");
     std::fs::create_dir_all(&log_dir)?;
@@ -397,7 +806,7 @@ fn append_audit_file(action: &str, runtime: &str, outcome: &str) -> Result<()> {
         .duration_since(UNIX_EPOCH)
         .expect("system clock before UNIX_EPOCH")
         .as_millis();
-    let line = format!("{now}\t{action}\t{runtime}\t{outcome}\n");
+    let line = format!("{now}\t{action}\t{runtime}\t{outcome}\t{session_id}\n");
 
     let mut file = OpenOptions::new()
         .create(true)
@@ -406,3 +815,36 @@ fn append_audit_file(action: &str, runtime: &str, outcome: &str) -> Result<()> {
     file.write_all(line.as_bytes())?;
     Ok(())
 }
+
+/// Parses `~/.clawden/logs/audit.log` (`ts\taction\ttarget\toutcome\tsession_id`,
+/// see [`append_audit_file_for_session`]) into an in-memory [`AuditLog`] so
+/// `clawden audit` can reuse the same `Before`/`After`/`Between` query engine
+/// the server exposes over `/audit`. The outcome is folded into `action` as
+/// `action:outcome` since `AuditEvent` has no dedicated outcome field.
+fn load_local_audit_log() -> Result<AuditLog> {
+    let home = std::env::var("HOME")?;
+    let log_path = PathBuf::from(home).join(".clawden").join("logs").join("audit.log");
+    let log = AuditLog::default();
+    let Ok(content) = std::fs::read_to_string(&log_path) else {
+        return Ok(log);
+    };
+
+    for line in content.lines() {
+        let columns: Vec<&str> = line.split('\t').collect();
+        if columns.len() < 4 {
+            continue;
+        }
+        let Ok(timestamp_unix_ms) = columns[0].parse::<u64>() else {
+            continue;
+        };
+        let session_id = columns.get(4).copied().unwrap_or("local");
+        log.append(AuditEvent {
+            actor: session_id.to_string(),
+            action: format!("{}:{}", columns[1], columns[3]),
+            target: columns[2].to_string(),
+            timestamp_unix_ms,
+            correlation_id: None,
+        });
+    }
+    Ok(log)
+}
@@ -0,0 +1,199 @@
+//! `clawden tunnel`: drive this host's runtimes from another machine.
+//!
+//! The host dials out to a relay endpoint and registers under a host id; a
+//! client elsewhere dials the same relay with `clawden tunnel connect
+//! <host-id>` and is routed to the host's side of a websocket. Every request
+//! on that channel is one of the operations the local CLI already exposes
+//! (`ps`, `logs`, `start`, `stop`, `send`), authenticated with the
+//! pre-shared access token printed once at `tunnel` startup. Every action
+//! the host takes on behalf of a remote session is recorded through
+//! [`crate::append_audit_file_for_session`] tagged with that session's id.
+
+use anyhow::{anyhow, Context, Result};
+use clawden_core::tunnel::{
+    generate_token, hash_token, load_token_hash, store_token_hash, verify_token, TunnelRequest,
+    TunnelRequestKind, TunnelResponse,
+};
+use clawden_core::{ExecutionMode, ProcessManager, RuntimeInstaller, RuntimeLaunchSpec};
+use futures_util::{SinkExt, StreamExt};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::append_audit_file_for_session;
+
+const DEFAULT_RELAY_URL: &str = "wss://relay.clawden.dev/tunnel";
+
+fn relay_url() -> String {
+    std::env::var("CLAWDEN_TUNNEL_RELAY").unwrap_or_else(|_| DEFAULT_RELAY_URL.to_string())
+}
+
+/// Runs the host side: prints a fresh access token, registers with the
+/// relay under `host_id`, and serves requests until the connection drops.
+pub async fn run_host(host_id: &str) -> Result<()> {
+    let (token, hash) = generate_token();
+    store_token_hash(&hash)?;
+    println!("Tunnel access token (save this, it will not be shown again):");
+    println!("  {token}");
+    println!("Registering '{host_id}' with relay {}...", relay_url());
+
+    let endpoint = format!("{}/host/{host_id}", relay_url());
+    let (ws_stream, _) = tokio_tungstenite::connect_async(&endpoint)
+        .await
+        .with_context(|| format!("connecting to relay at {endpoint}"))?;
+    let (mut write, mut read) = ws_stream.split();
+
+    println!("Tunnel host '{host_id}' is ready for incoming sessions.");
+    while let Some(frame) = read.next().await {
+        let frame = frame.context("reading frame from relay")?;
+        let Message::Text(body) = frame else {
+            continue;
+        };
+        let request: TunnelRequest = match serde_json::from_str(&body) {
+            Ok(req) => req,
+            Err(e) => {
+                write
+                    .send(Message::Text(
+                        serde_json::to_string(&TunnelResponse::err("unknown", format!("malformed request: {e}")))?,
+                    ))
+                    .await?;
+                continue;
+            }
+        };
+        let response = handle_request(&request).await;
+        write
+            .send(Message::Text(serde_json::to_string(&response)?))
+            .await?;
+    }
+    Ok(())
+}
+
+async fn handle_request(request: &TunnelRequest) -> TunnelResponse {
+    let stored_hash = match load_token_hash() {
+        Ok(Some(hash)) => hash,
+        Ok(None) => return TunnelResponse::err(&request.session_id, "no tunnel token has been provisioned"),
+        Err(e) => return TunnelResponse::err(&request.session_id, e),
+    };
+    if !verify_token(&request.token, &stored_hash) {
+        return TunnelResponse::err(&request.session_id, "invalid tunnel access token");
+    }
+
+    match dispatch(&request.session_id, &request.kind) {
+        Ok(payload) => TunnelResponse::ok(&request.session_id, payload),
+        Err(e) => TunnelResponse::err(&request.session_id, e.to_string()),
+    }
+}
+
+fn dispatch(session_id: &str, kind: &TunnelRequestKind) -> Result<serde_json::Value> {
+    let process_manager = ProcessManager::new(ExecutionMode::Auto)?;
+    match kind {
+        TunnelRequestKind::Ps => {
+            let statuses = process_manager.list_statuses()?;
+            append_audit_file_for_session("tunnel.ps", "all", "ok", session_id)?;
+            Ok(serde_json::to_value(statuses)?)
+        }
+        TunnelRequestKind::Logs { runtime, lines } => {
+            let logs = process_manager.tail_logs(runtime, *lines)?;
+            append_audit_file_for_session("tunnel.logs", runtime, "ok", session_id)?;
+            Ok(serde_json::json!({ "logs": logs }))
+        }
+        TunnelRequestKind::Start { runtime } => {
+            let installer = RuntimeInstaller::new()?;
+            let executable = installer.runtime_executable(runtime).ok_or_else(|| {
+                anyhow!("runtime '{runtime}' not installed; run 'clawden install {runtime}' on the host")
+            })?;
+            let info = process_manager.start_direct(runtime, &executable, &RuntimeLaunchSpec::new())?;
+            append_audit_file_for_session("tunnel.start", runtime, "ok", session_id)?;
+            Ok(serde_json::to_value(info)?)
+        }
+        TunnelRequestKind::Stop { runtime } => {
+            process_manager.stop(runtime)?;
+            append_audit_file_for_session("tunnel.stop", runtime, "ok", session_id)?;
+            Ok(serde_json::json!({ "stopped": runtime }))
+        }
+        TunnelRequestKind::Send { agent_id, message } => {
+            process_manager.send_message(agent_id, message)?;
+            append_audit_file_for_session("tunnel.send", agent_id, "ok", session_id)?;
+            Ok(serde_json::json!({ "queued_for": agent_id }))
+        }
+    }
+}
+
+/// Runs the client side: dials the relay's `host_id` route and drives a
+/// small request/response REPL over stdin, one JSON-marshalled request per line.
+pub async fn connect_client(host_id: &str, token: &str) -> Result<()> {
+    let endpoint = format!("{}/connect/{host_id}", relay_url());
+    let (ws_stream, _) = tokio_tungstenite::connect_async(&endpoint)
+        .await
+        .with_context(|| format!("connecting to relay at {endpoint}"))?;
+    let (mut write, mut read) = ws_stream.split();
+    let session_id = new_session_id();
+
+    println!("Connected to '{host_id}' (session {session_id}). Commands: ps | logs <rt> [n] | start <rt> | stop <rt> | send <agent> <msg>");
+    let stdin = std::io::stdin();
+    loop {
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            break;
+        }
+        let Some(kind) = parse_command(line.trim()) else {
+            println!("unrecognized command");
+            continue;
+        };
+        let request = TunnelRequest {
+            session_id: session_id.clone(),
+            token: token.to_string(),
+            kind,
+        };
+        write
+            .send(Message::Text(serde_json::to_string(&request)?))
+            .await?;
+        match read.next().await {
+            Some(Ok(Message::Text(body))) => {
+                let response: TunnelResponse = serde_json::from_str(&body)?;
+                if response.ok {
+                    println!("{}", serde_json::to_string_pretty(&response.payload)?);
+                } else {
+                    println!("error: {}", response.message);
+                }
+            }
+            Some(Ok(_)) | None => break,
+            Some(Err(e)) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+fn parse_command(line: &str) -> Option<TunnelRequestKind> {
+    let mut parts = line.split_whitespace();
+    match parts.next()? {
+        "ps" => Some(TunnelRequestKind::Ps),
+        "logs" => {
+            let runtime = parts.next()?.to_string();
+            let lines = parts.next().and_then(|n| n.parse().ok()).unwrap_or(50);
+            Some(TunnelRequestKind::Logs { runtime, lines })
+        }
+        "start" => Some(TunnelRequestKind::Start {
+            runtime: parts.next()?.to_string(),
+        }),
+        "stop" => Some(TunnelRequestKind::Stop {
+            runtime: parts.next()?.to_string(),
+        }),
+        "send" => {
+            let agent_id = parts.next()?.to_string();
+            let message = parts.collect::<Vec<_>>().join(" ");
+            if message.is_empty() {
+                return None;
+            }
+            Some(TunnelRequestKind::Send { agent_id, message })
+        }
+        _ => None,
+    }
+}
+
+fn new_session_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_nanos();
+    hash_token(&format!("{nanos}-{}", std::process::id()))[..12].to_string()
+}
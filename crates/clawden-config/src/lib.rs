@@ -1,8 +1,24 @@
+mod scaffold;
+
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, Key, KeyInit, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use clawden_core::ClawRuntime;
+use ed25519_dalek::{Signer, Verifier};
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::Path;
+use thiserror::Error;
+use zeroize::Zeroize;
+
+pub use scaffold::{apply_toggles, scaffold_project, FeatureToggle, ScaffoldChange};
 
 // ---------------------------------------------------------------------------
 // clawden.yaml schema (spec 017)
@@ -29,7 +45,7 @@ use std::path::Path;
 ///     channels: [support-tg]
 ///     tools: [git, http]
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClawDenYaml {
     /// Single-runtime shorthand (mutually exclusive with `runtimes`).
     #[serde(default)]
@@ -49,7 +65,7 @@ pub struct ClawDenYaml {
 
     /// Single-runtime tools shorthand.
     #[serde(default)]
-    pub tools: Vec<String>,
+    pub tools: ToolsYaml,
 
     /// Single-runtime config overrides shorthand.
     #[serde(default)]
@@ -62,6 +78,23 @@ pub struct ClawDenYaml {
     /// Single-runtime model shorthand.
     #[serde(default)]
     pub model: Option<String>,
+
+    /// Toggleable infrastructure features that aren't runtimes or channels
+    /// (e.g. `redis` for shared session storage). Populated by `clawden init`.
+    #[serde(default)]
+    pub infra: HashMap<String, bool>,
+
+    /// OpenTelemetry export settings. Absent (the default) means telemetry
+    /// stays local: no traces/metrics/logs exporter is wired up.
+    #[serde(default)]
+    pub observability: ObservabilityYaml,
+
+    /// Named deltas merged over this config by [`ClawDenYaml::load_with_profile`]
+    /// (e.g. a `prod` profile overriding the provider and model of a shared
+    /// base file), so users don't have to duplicate whole configs per
+    /// environment.
+    #[serde(default)]
+    pub profiles: HashMap<String, ClawDenYaml>,
 }
 
 /// A channel instance entry in `clawden.yaml`.
@@ -119,7 +152,7 @@ pub struct RuntimeEntryYaml {
     #[serde(default)]
     pub channels: Vec<String>,
     #[serde(default)]
-    pub tools: Vec<String>,
+    pub tools: ToolsYaml,
     #[serde(default)]
     pub provider: Option<String>,
     #[serde(default)]
@@ -138,6 +171,11 @@ pub struct ProviderEntryYaml {
     pub base_url: Option<String>,
     #[serde(default)]
     pub org_id: Option<String>,
+    /// Overrides [`LlmProvider::capabilities`]'s defaults — mainly for
+    /// `Custom` providers, whose tool/streaming/vision support isn't known
+    /// ahead of time and has to be declared by the user.
+    #[serde(default)]
+    pub capabilities: Option<ProviderCapabilitiesOverride>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -148,6 +186,85 @@ impl ProviderEntryYaml {
             .clone()
             .or_else(|| LlmProvider::from_name(provider_name))
     }
+
+    /// The effective capabilities for this provider entry: `provider_type`'s
+    /// (or the name-inferred type's) defaults, with any fields set in
+    /// `capabilities` overridden.
+    fn resolved_capabilities(&self, provider_name: &str) -> ProviderCapabilities {
+        let mut capabilities = self
+            .resolved_type(provider_name)
+            .map(|t| t.capabilities())
+            .unwrap_or_default();
+        if let Some(overrides) = &self.capabilities {
+            overrides.apply(&mut capabilities);
+        }
+        capabilities
+    }
+}
+
+/// What a provider supports, used to validate tool wiring before a runtime
+/// ever tries to call a tool through it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderCapabilities {
+    pub supports_tools: bool,
+    pub supports_streaming: bool,
+    pub supports_vision: bool,
+    /// `None` when the provider doesn't document a hard cap.
+    pub max_tools: Option<u32>,
+}
+
+impl Default for ProviderCapabilities {
+    /// The conservative "unknown" default for providers without a hardcoded
+    /// entry in [`LlmProvider::capabilities`] (`Ollama`, `Custom`) — tools and
+    /// vision are not assumed until declared via `ProviderEntryYaml::capabilities`;
+    /// streaming is assumed supported.
+    fn default() -> Self {
+        Self {
+            supports_tools: false,
+            supports_streaming: true,
+            supports_vision: false,
+            max_tools: None,
+        }
+    }
+}
+
+/// Per-field override of [`ProviderCapabilities`]'s defaults in
+/// `clawden.yaml`, e.g.:
+/// ```yaml
+/// providers:
+///   local:
+///     type: custom
+///     base_url: http://localhost:1234/v1
+///     capabilities:
+///       supports_tools: true
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderCapabilitiesOverride {
+    #[serde(default)]
+    pub supports_tools: Option<bool>,
+    #[serde(default)]
+    pub supports_streaming: Option<bool>,
+    #[serde(default)]
+    pub supports_vision: Option<bool>,
+    #[serde(default)]
+    pub max_tools: Option<u32>,
+}
+
+impl ProviderCapabilitiesOverride {
+    fn apply(&self, capabilities: &mut ProviderCapabilities) {
+        if let Some(supports_tools) = self.supports_tools {
+            capabilities.supports_tools = supports_tools;
+        }
+        if let Some(supports_streaming) = self.supports_streaming {
+            capabilities.supports_streaming = supports_streaming;
+        }
+        if let Some(supports_vision) = self.supports_vision {
+            capabilities.supports_vision = supports_vision;
+        }
+        if self.max_tools.is_some() {
+            capabilities.max_tools = self.max_tools;
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -157,6 +274,53 @@ pub enum ProviderRefYaml {
     Inline(ProviderEntryYaml),
 }
 
+/// The wire protocol an OTLP exporter talks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObservabilityProtocol {
+    Grpc,
+    Http,
+}
+
+impl Default for ObservabilityProtocol {
+    fn default() -> Self {
+        Self::Grpc
+    }
+}
+
+/// `observability:` block: one OTLP exporter config driving traces, metrics,
+/// and logs together, instead of each runtime growing its own ad-hoc log
+/// file settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ObservabilityYaml {
+    /// OTLP collector endpoint (supports `$ENV_VAR` syntax).
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    #[serde(default)]
+    pub protocol: Option<ObservabilityProtocol>,
+    #[serde(default)]
+    pub service_name: Option<String>,
+    /// Trace sampling ratio, must be within `0.0..=1.0`.
+    #[serde(default)]
+    pub sample_ratio: Option<f64>,
+    #[serde(default)]
+    pub traces: Option<bool>,
+    #[serde(default)]
+    pub metrics: Option<bool>,
+    #[serde(default)]
+    pub logs: Option<bool>,
+    /// Extra OTLP exporter headers (e.g. `Authorization: Bearer ...`).
+    /// Redacted by [`ClawDenConfig::to_safe_json`] on the canonical side.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl ObservabilityYaml {
+    fn any_exporter_enabled(&self) -> bool {
+        self.traces.unwrap_or(false) || self.metrics.unwrap_or(false) || self.logs.unwrap_or(false)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum LlmProvider {
@@ -208,6 +372,53 @@ impl LlmProvider {
             Self::Ollama | Self::Custom(_) => None,
         }
     }
+
+    /// What this provider supports out of the box. `Ollama`/`Custom` default
+    /// to the conservative values from [`ProviderCapabilities::default`]
+    /// (no tools, no vision) since tool/vision support there
+    /// varies per deployment — declare it via `ProviderEntryYaml::capabilities`
+    /// instead.
+    pub fn capabilities(&self) -> ProviderCapabilities {
+        match self {
+            Self::OpenAi => ProviderCapabilities {
+                supports_tools: true,
+                supports_streaming: true,
+                supports_vision: true,
+                max_tools: Some(128),
+            },
+            Self::Anthropic => ProviderCapabilities {
+                supports_tools: true,
+                supports_streaming: true,
+                supports_vision: true,
+                max_tools: None,
+            },
+            Self::Mistral => ProviderCapabilities {
+                supports_tools: true,
+                supports_streaming: true,
+                supports_vision: false,
+                max_tools: None,
+            },
+            Self::Groq => ProviderCapabilities {
+                supports_tools: true,
+                supports_streaming: true,
+                supports_vision: false,
+                max_tools: None,
+            },
+            Self::OpenRouter => ProviderCapabilities {
+                supports_tools: true,
+                supports_streaming: true,
+                supports_vision: true,
+                max_tools: None,
+            },
+            Self::Google => ProviderCapabilities {
+                supports_tools: true,
+                supports_streaming: true,
+                supports_vision: true,
+                max_tools: None,
+            },
+            Self::Ollama | Self::Custom(_) => ProviderCapabilities::default(),
+        }
+    }
 }
 
 /// Known built-in tools.
@@ -225,6 +436,230 @@ pub const KNOWN_TOOLS: &[&str] = &[
     "compiler",
 ];
 
+/// `(name, description)` for every [`KNOWN_TOOLS`] entry, used to seed a
+/// [`ToolSpec`]'s defaults when a `tools:` entry only names the tool.
+const TOOL_REGISTRY: &[(&str, &str)] = &[
+    (
+        "git",
+        "Read and mutate the agent's git checkout (status, diff, commit).",
+    ),
+    ("http", "Issue outbound HTTP requests."),
+    (
+        "core-utils",
+        "Run coreutils-style shell commands (ls, cat, grep, ...).",
+    ),
+    ("python", "Execute Python code in a scratch interpreter."),
+    (
+        "code-tools",
+        "Search, read, and edit source files in the workspace.",
+    ),
+    ("database", "Query a configured database connection."),
+    (
+        "network",
+        "Inspect or configure network interfaces and sockets.",
+    ),
+    ("sandbox", "Run arbitrary commands in an isolated sandbox."),
+    ("browser", "Drive a headless browser session."),
+    (
+        "gui",
+        "Interact with a desktop GUI via screenshots and input events.",
+    ),
+    ("compiler", "Build the workspace with its native toolchain."),
+];
+
+fn tool_registry_description(name: &str) -> Option<&'static str> {
+    TOOL_REGISTRY
+        .iter()
+        .find(|(tool_name, _)| *tool_name == name)
+        .map(|(_, description)| *description)
+}
+
+/// Whether a tool call through this entry requires human sign-off before
+/// running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalMode {
+    /// Run without asking.
+    Auto,
+    /// Ask for confirmation before every call.
+    Prompt,
+    /// Never run; the tool is declared but unreachable.
+    Deny,
+}
+
+impl Default for ApprovalMode {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// A fully-resolved tool: its registry name, a JSON-Schema `parameters`
+/// object describing its call signature, and the [`ApprovalMode`] gating it.
+/// This is what `RuntimeConfigTranslator` impls hand to a runtime so it has
+/// a machine-readable signature to put in front of a function-calling model,
+/// instead of a bare tool name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_tool_parameters")]
+    pub parameters: Value,
+    #[serde(default)]
+    pub approval: ApprovalMode,
+}
+
+fn default_tool_parameters() -> Value {
+    serde_json::json!({ "type": "object", "properties": {}, "required": [] })
+}
+
+impl ToolSpec {
+    /// Build the registry default for `name` (`None` if it's not a known
+    /// tool), with empty JSON-Schema parameters until overridden.
+    fn registry_default(name: &str) -> Option<Self> {
+        tool_registry_description(name).map(|description| Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters: default_tool_parameters(),
+            approval: ApprovalMode::default(),
+        })
+    }
+
+    /// Reject a `parameters` value that isn't a JSON-Schema object shape:
+    /// `type: "object"`, `properties` an object if present, `required` an
+    /// array of strings if present.
+    fn validate_parameters(&self) -> Result<(), String> {
+        let Some(obj) = self.parameters.as_object() else {
+            return Err(format!(
+                "Tool '{}' parameters must be a JSON object",
+                self.name
+            ));
+        };
+        if obj.get("type").and_then(Value::as_str) != Some("object") {
+            return Err(format!(
+                "Tool '{}' parameters must have \"type\": \"object\"",
+                self.name
+            ));
+        }
+        if let Some(properties) = obj.get("properties") {
+            if !properties.is_object() {
+                return Err(format!(
+                    "Tool '{}' parameters.properties must be an object",
+                    self.name
+                ));
+            }
+        }
+        if let Some(required) = obj.get("required") {
+            let valid = required
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string));
+            if !valid {
+                return Err(format!(
+                    "Tool '{}' parameters.required must be an array of strings",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Full-form per-tool override in `tools: { <name>: { ... } }`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolEntryYaml {
+    #[serde(default)]
+    pub approval: Option<ApprovalMode>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<Value>,
+}
+
+impl ToolEntryYaml {
+    fn apply(&self, spec: &mut ToolSpec) {
+        if let Some(approval) = self.approval {
+            spec.approval = approval;
+        }
+        if let Some(description) = &self.description {
+            spec.description = description.clone();
+        }
+        if let Some(parameters) = &self.parameters {
+            spec.parameters = parameters.clone();
+        }
+    }
+}
+
+/// `tools:` accepts either the shorthand list (names expand to registry
+/// defaults) or the full map form keyed by tool name, mirroring the
+/// shorthand/full-form duality [`ProviderRefYaml`] already gives providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolsYaml {
+    List(Vec<String>),
+    Map(HashMap<String, ToolEntryYaml>),
+}
+
+impl Default for ToolsYaml {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+impl ToolsYaml {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::List(names) => names.is_empty(),
+            Self::Map(entries) => entries.is_empty(),
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        match self {
+            Self::List(names) => names.clone(),
+            Self::Map(entries) => entries.keys().cloned().collect(),
+        }
+    }
+
+    fn into_map(self) -> HashMap<String, ToolEntryYaml> {
+        match self {
+            Self::List(names) => names
+                .into_iter()
+                .map(|name| (name, ToolEntryYaml::default()))
+                .collect(),
+            Self::Map(entries) => entries,
+        }
+    }
+
+    /// Resolve every entry into a [`ToolSpec`], applying any full-form
+    /// override over the registry default. Collects one error per entry
+    /// that names an unknown tool or declares a malformed parameter schema,
+    /// instead of stopping at the first one.
+    pub fn resolve_specs(&self) -> Result<Vec<ToolSpec>, Vec<String>> {
+        let mut specs = Vec::new();
+        let mut errors = Vec::new();
+        for (name, entry) in self.clone().into_map() {
+            let Some(mut spec) = ToolSpec::registry_default(&name) else {
+                errors.push(format!(
+                    "Unknown tool '{}'; not in the tool registry.",
+                    name
+                ));
+                continue;
+            };
+            entry.apply(&mut spec);
+            if let Err(err) = spec.validate_parameters() {
+                errors.push(err);
+                continue;
+            }
+            specs.push(spec);
+        }
+        if errors.is_empty() {
+            Ok(specs)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 /// Known channel type names for type inference.
 const KNOWN_CHANNEL_TYPES: &[&str] = &[
     "telegram",
@@ -340,6 +775,44 @@ impl ClawDenYaml {
             }
         }
 
+        // Validate the observability block: a sane sample ratio, and an
+        // endpoint present whenever an exporter is actually turned on.
+        if let Some(ratio) = self.observability.sample_ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                errors.push(format!(
+                    "observability.sample_ratio must be between 0.0 and 1.0, got {}",
+                    ratio
+                ));
+            }
+        }
+        if self.observability.any_exporter_enabled()
+            && self
+                .observability
+                .otlp_endpoint
+                .as_deref()
+                .map_or(true, str::is_empty)
+        {
+            errors.push(
+                "observability: traces/metrics/logs export is enabled but 'otlp_endpoint' is not set"
+                    .to_string(),
+            );
+        }
+
+        // Validate the single-runtime tools shorthand and every per-runtime
+        // `tools:` resolve to known tools with well-formed parameter schemas.
+        if let Err(tool_errors) = self.tools.resolve_specs() {
+            errors.extend(tool_errors);
+        }
+        for rt in &self.runtimes {
+            if let Err(tool_errors) = rt.tools.resolve_specs() {
+                errors.extend(
+                    tool_errors
+                        .into_iter()
+                        .map(|err| format!("Runtime '{}': {}", rt.name, err)),
+                );
+            }
+        }
+
         for rt in &self.runtimes {
             if let Some(provider_name) = &rt.provider {
                 let unknown = !self.providers.contains_key(provider_name)
@@ -349,6 +822,22 @@ impl ClawDenYaml {
                         "Runtime '{}' references provider '{}' which is not defined in 'providers:' and is not a known shorthand provider",
                         rt.name, provider_name
                     ));
+                } else if !rt.tools.is_empty() {
+                    let capabilities = self
+                        .providers
+                        .get(provider_name)
+                        .map(|provider| provider.resolved_capabilities(provider_name))
+                        .or_else(|| {
+                            LlmProvider::from_name(provider_name).map(|p| p.capabilities())
+                        });
+                    if let Some(capabilities) = capabilities {
+                        if !capabilities.supports_tools {
+                            errors.push(format!(
+                                "Runtime '{}' uses provider '{}' which does not support tool calling; remove tools or pick a tool-capable provider.",
+                                rt.name, provider_name
+                            ));
+                        }
+                    }
                 }
             }
         }
@@ -369,6 +858,7 @@ impl ClawDenYaml {
             resolve_field(&mut ch.app_token, "Channel", name, "app_token", &mut errors);
             resolve_field(&mut ch.phone, "Channel", name, "phone", &mut errors);
             resolve_field(&mut ch.guild, "Channel", name, "guild", &mut errors);
+            resolve_value_map(&mut ch.extra, "Channel", name, &mut errors);
         }
         for (name, provider) in &mut self.providers {
             resolve_field(
@@ -385,6 +875,7 @@ impl ClawDenYaml {
                 "base_url",
                 &mut errors,
             );
+            resolve_value_map(&mut provider.extra, "Provider", name, &mut errors);
 
             if let Some(provider_type) = provider.resolved_type(name) {
                 if provider.api_key.is_none() {
@@ -415,6 +906,17 @@ impl ClawDenYaml {
                 &mut errors,
             );
         }
+        resolve_field(
+            &mut self.observability.otlp_endpoint,
+            "Observability",
+            "observability",
+            "otlp_endpoint",
+            &mut errors,
+        );
+        resolve_value_map(&mut self.config, "Config", "config", &mut errors);
+        for rt in &mut self.runtimes {
+            resolve_value_map(&mut rt.config, "Runtime", &rt.name, &mut errors);
+        }
         if errors.is_empty() {
             Ok(())
         } else {
@@ -432,110 +934,556 @@ impl ClawDenYaml {
             }
         })
     }
-}
 
-/// Resolve a single `$ENV_VAR` field in-place.
-fn resolve_field(
-    field: &mut Option<String>,
-    kind: &str,
-    instance: &str,
-    field_name: &str,
-    errors: &mut Vec<String>,
-) {
-    if let Some(val) = field.as_ref() {
-        if let Some(env_name) = val.strip_prefix('$') {
-            match std::env::var(env_name) {
-                Ok(resolved) => *field = Some(resolved),
-                Err(_) => errors.push(format!(
-                    "{} '{}' field '{}': environment variable '{}' is not set",
-                    kind, instance, field_name, env_name
-                )),
-            }
+    /// Load and [`Merge`] `paths` in order, so a shared base file plus
+    /// per-environment deltas compose into one effective config instead of
+    /// each environment duplicating the whole file. Later paths win.
+    /// `validate()` and `resolve_env_vars()` should run on the result, same
+    /// as a single-file load.
+    pub fn load_layered(paths: &[&Path]) -> Result<Self, String> {
+        let mut layers = paths.iter().map(|path| Self::from_file(path));
+        let mut merged = layers
+            .next()
+            .ok_or_else(|| "load_layered requires at least one path".to_string())??;
+        for layer in layers {
+            merged.merge(layer?);
         }
+        Ok(merged)
+    }
+
+    /// Load `base`, then [`Merge`] its `profiles[profile]` entry (if any)
+    /// over it, so a `prod` profile can override just the fields it cares
+    /// about instead of restating the whole config.
+    pub fn load_with_profile(base: &Path, profile: &str) -> Result<Self, String> {
+        let mut config = Self::from_file(base)?;
+        if let Some(profile_config) = config.profiles.remove(profile) {
+            config.merge(profile_config);
+        }
+        Ok(config)
     }
 }
 
 // ---------------------------------------------------------------------------
-// Canonical config types
+// Layered config merge (profiles, CLI overrides)
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ClawDenConfig {
-    pub agent: AgentConfig,
+/// In-place "later layer wins" merge, used to compose `clawden.yaml` layers
+/// (base file + profile, or a chain of `--config` paths) into one effective
+/// config. Maps are merged key-wise (recursing into matching entries);
+/// scalars fall back to `self` when `other` leaves them unset.
+pub trait Merge {
+    fn merge(&mut self, other: Self);
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AgentConfig {
-    pub name: String,
-    pub runtime: ClawRuntime,
-    pub model: ModelConfig,
-    #[serde(default)]
-    pub tools: Vec<ToolConfig>,
-    #[serde(default)]
-    pub channels: Vec<ChannelConfig>,
-    pub security: SecurityConfig,
-    #[serde(default)]
-    pub extras: Map<String, Value>,
+/// Key-wise map merge: a key present in both `base` and `other` recurses via
+/// `Merge` instead of `other`'s entry clobbering `base`'s outright.
+fn merge_maps<V: Merge>(base: &mut HashMap<String, V>, other: HashMap<String, V>) {
+    for (key, value) in other {
+        match base.entry(key) {
+            std::collections::hash_map::Entry::Occupied(mut entry) => entry.get_mut().merge(value),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ModelConfig {
-    pub provider: String,
-    pub name: String,
-    pub api_key_ref: Option<String>,
+/// Set-union merge for list fields like `tools`/`channels`, preserving
+/// `base`'s order and only appending entries `other` doesn't already have.
+fn union_vec(base: &mut Vec<String>, other: Vec<String>) {
+    for item in other {
+        if !base.contains(&item) {
+            base.push(item);
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ToolConfig {
-    pub name: String,
-    #[serde(default)]
-    pub allowed: bool,
+/// Merges `other`'s runtimes into `base` by `name`, recursing via `Merge`
+/// for an entry both sides define instead of listing the runtime twice.
+fn merge_runtimes(base: &mut Vec<RuntimeEntryYaml>, other: Vec<RuntimeEntryYaml>) {
+    for entry in other {
+        match base.iter_mut().find(|rt| rt.name == entry.name) {
+            Some(existing) => existing.merge(entry),
+            None => base.push(entry),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChannelConfig {
-    pub channel: String,
-    pub enabled: bool,
+impl Merge for ClawDenYaml {
+    fn merge(&mut self, other: Self) {
+        if other.runtime.is_some() {
+            self.runtime = other.runtime;
+        }
+        merge_maps(&mut self.channels, other.channels);
+        merge_maps(&mut self.providers, other.providers);
+        merge_runtimes(&mut self.runtimes, other.runtimes);
+        self.tools.merge(other.tools);
+        self.config.extend(other.config);
+        if other.provider.is_some() {
+            self.provider = other.provider;
+        }
+        if other.model.is_some() {
+            self.model = other.model;
+        }
+        self.infra.extend(other.infra);
+        self.observability.merge(other.observability);
+        merge_maps(&mut self.profiles, other.profiles);
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SecurityConfig {
-    #[serde(default)]
-    pub allowlist: Vec<String>,
-    #[serde(default)]
-    pub sandboxed: bool,
+impl Merge for ObservabilityYaml {
+    fn merge(&mut self, other: Self) {
+        if other.otlp_endpoint.is_some() {
+            self.otlp_endpoint = other.otlp_endpoint;
+        }
+        if other.protocol.is_some() {
+            self.protocol = other.protocol;
+        }
+        if other.service_name.is_some() {
+            self.service_name = other.service_name;
+        }
+        if other.sample_ratio.is_some() {
+            self.sample_ratio = other.sample_ratio;
+        }
+        if other.traces.is_some() {
+            self.traces = other.traces;
+        }
+        if other.metrics.is_some() {
+            self.metrics = other.metrics;
+        }
+        if other.logs.is_some() {
+            self.logs = other.logs;
+        }
+        self.headers.extend(other.headers);
+    }
 }
 
-impl ClawDenConfig {
-    pub fn validate(&self) -> Result<(), String> {
-        if self.agent.name.trim().is_empty() {
-            return Err("agent.name must not be empty".to_string());
+impl Merge for ChannelInstanceYaml {
+    fn merge(&mut self, other: Self) {
+        if other.channel_type.is_some() {
+            self.channel_type = other.channel_type;
         }
-
-        if self.agent.model.provider.trim().is_empty() || self.agent.model.name.trim().is_empty() {
-            return Err("agent.model provider and name must not be empty".to_string());
+        if other.token.is_some() {
+            self.token = other.token;
         }
-
-        Ok(())
+        if other.bot_token.is_some() {
+            self.bot_token = other.bot_token;
+        }
+        if other.app_token.is_some() {
+            self.app_token = other.app_token;
+        }
+        if other.phone.is_some() {
+            self.phone = other.phone;
+        }
+        if other.guild.is_some() {
+            self.guild = other.guild;
+        }
+        union_vec(&mut self.allowed_users, other.allowed_users);
+        union_vec(&mut self.allowed_roles, other.allowed_roles);
+        union_vec(&mut self.allowed_channels, other.allowed_channels);
+        if other.group_mode.is_some() {
+            self.group_mode = other.group_mode;
+        }
+        self.extra.extend(other.extra);
     }
+}
 
-    pub fn to_safe_json(&self) -> Value {
-        let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
-        if let Some(api_ref) = value
-            .get_mut("agent")
-            .and_then(|a| a.get_mut("model"))
-            .and_then(|m| m.get_mut("api_key_ref"))
-        {
-            *api_ref = Value::String("<redacted>".to_string());
+impl Merge for ProviderEntryYaml {
+    fn merge(&mut self, other: Self) {
+        if other.provider_type.is_some() {
+            self.provider_type = other.provider_type;
         }
-        value
+        if other.api_key.is_some() {
+            self.api_key = other.api_key;
+        }
+        if other.base_url.is_some() {
+            self.base_url = other.base_url;
+        }
+        if other.org_id.is_some() {
+            self.org_id = other.org_id;
+        }
+        match (&mut self.capabilities, other.capabilities) {
+            (Some(existing), Some(incoming)) => existing.merge(incoming),
+            (slot @ None, Some(incoming)) => *slot = Some(incoming),
+            _ => {}
+        }
+        self.extra.extend(other.extra);
     }
 }
 
-pub trait RuntimeConfigTranslator {
-    fn runtime(&self) -> ClawRuntime;
-    fn to_runtime_config(&self, canonical: &ClawDenConfig) -> Result<Value, String>;
-    #[allow(clippy::wrong_self_convention)]
+impl Merge for ProviderCapabilitiesOverride {
+    fn merge(&mut self, other: Self) {
+        if other.supports_tools.is_some() {
+            self.supports_tools = other.supports_tools;
+        }
+        if other.supports_streaming.is_some() {
+            self.supports_streaming = other.supports_streaming;
+        }
+        if other.supports_vision.is_some() {
+            self.supports_vision = other.supports_vision;
+        }
+        if other.max_tools.is_some() {
+            self.max_tools = other.max_tools;
+        }
+    }
+}
+
+impl Merge for ToolEntryYaml {
+    fn merge(&mut self, other: Self) {
+        if other.approval.is_some() {
+            self.approval = other.approval;
+        }
+        if other.description.is_some() {
+            self.description = other.description;
+        }
+        if other.parameters.is_some() {
+            self.parameters = other.parameters;
+        }
+    }
+}
+
+/// A list+list merge stays a union (same as `union_vec`); anything touching
+/// the full map form degrades the result to a map so per-tool overrides from
+/// either layer aren't lost.
+impl Merge for ToolsYaml {
+    fn merge(&mut self, other: Self) {
+        let base = std::mem::replace(self, Self::List(Vec::new()));
+        *self = match (base, other) {
+            (Self::List(mut base_names), Self::List(other_names)) => {
+                union_vec(&mut base_names, other_names);
+                Self::List(base_names)
+            }
+            (base, other) => {
+                let mut merged = base.into_map();
+                merge_maps(&mut merged, other.into_map());
+                Self::Map(merged)
+            }
+        };
+    }
+}
+
+impl Merge for RuntimeEntryYaml {
+    fn merge(&mut self, other: Self) {
+        union_vec(&mut self.channels, other.channels);
+        self.tools.merge(other.tools);
+        if other.provider.is_some() {
+            self.provider = other.provider;
+        }
+        if other.model.is_some() {
+            self.model = other.model;
+        }
+        self.config.extend(other.config);
+    }
+}
+
+/// CLI-flag overrides (`--provider`, `--model`, `--runtime`, `--tool k=v`)
+/// applied after file/profile merge but before `validate()`, so a one-off
+/// invocation doesn't need its own `clawden.yaml` layer just to swap a
+/// model or set a config value.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverride {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub runtime: Option<String>,
+    /// `k=v` pairs destined for the single-runtime `config:` shorthand.
+    pub tools: Vec<(String, String)>,
+}
+
+impl ConfigOverride {
+    /// Parse a single `--tool k=v` value into a `(key, value)` pair.
+    pub fn parse_tool_kv(raw: &str) -> Option<(String, String)> {
+        let (key, value) = raw.split_once('=')?;
+        Some((key.to_string(), value.to_string()))
+    }
+
+    pub fn apply(&self, yaml: &mut ClawDenYaml) {
+        if let Some(runtime) = &self.runtime {
+            yaml.runtime = Some(runtime.clone());
+        }
+        if let Some(provider) = &self.provider {
+            yaml.provider = Some(ProviderRefYaml::Name(provider.clone()));
+        }
+        if let Some(model) = &self.model {
+            yaml.model = Some(model.clone());
+        }
+        for (key, value) in &self.tools {
+            yaml.config
+                .insert(key.clone(), Value::String(value.clone()));
+        }
+    }
+}
+
+/// Resolve a single `$ENV_VAR` field in-place.
+fn resolve_field(
+    field: &mut Option<String>,
+    kind: &str,
+    instance: &str,
+    field_name: &str,
+    errors: &mut Vec<String>,
+) {
+    let Some(val) = field.as_ref() else {
+        return;
+    };
+    if !val.contains('$') {
+        return;
+    }
+    let mut local_errors = Vec::new();
+    match interpolate(val, &mut local_errors) {
+        Some(resolved) => *field = Some(resolved),
+        None => errors.extend(
+            local_errors
+                .into_iter()
+                .map(|err| format!("{} '{}' field '{}': {}", kind, instance, field_name, err)),
+        ),
+    }
+}
+
+/// Interpolates `$VAR` and `${...}` references against `std::env`, for
+/// inline embedding in otherwise-static strings (e.g.
+/// `https://${REGION}.api.example.com/v1`). `${VAR}` supports two POSIX-style
+/// modifiers: `${VAR:-fallback}` substitutes `fallback` when `VAR` is unset
+/// instead of erroring, and `${VAR:?message}` errors with `message` (falling
+/// back to the default "is not set" wording when `message` is empty) instead
+/// of the generic one. Returns `None` if any reference couldn't be resolved,
+/// with one entry appended to `errors` per failure.
+fn interpolate(input: &str, errors: &mut Vec<String>) -> Option<String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut out = String::new();
+    let mut ok = true;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '$' {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        if i + 1 < chars.len() && chars[i + 1] == '{' {
+            match chars[i + 2..].iter().position(|&c| c == '}') {
+                Some(rel) => {
+                    let token: String = chars[i + 2..i + 2 + rel].iter().collect();
+                    if !resolve_token(&token, &mut out, errors) {
+                        ok = false;
+                    }
+                    i += 2 + rel + 1;
+                }
+                None => {
+                    // Unterminated `${` — treat the `$` literally rather than erroring.
+                    out.push('$');
+                    i += 1;
+                }
+            }
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_') {
+            end += 1;
+        }
+        if end == start {
+            out.push('$');
+            i += 1;
+            continue;
+        }
+        let name: String = chars[start..end].iter().collect();
+        if !resolve_env_var(&name, &mut out, errors) {
+            ok = false;
+        }
+        i = end;
+    }
+    if ok {
+        Some(out)
+    } else {
+        None
+    }
+}
+
+/// Resolves one `${...}` token body (`VAR`, `VAR:-fallback`, or
+/// `VAR:?message`), appending the result to `out`. Returns `false` (after
+/// recording an error) when a required variable is unset.
+fn resolve_token(token: &str, out: &mut String, errors: &mut Vec<String>) -> bool {
+    if let Some((name, fallback)) = token.split_once(":-") {
+        let value = std::env::var(name).unwrap_or_else(|_| fallback.to_string());
+        out.push_str(&value);
+        return true;
+    }
+    if let Some((name, message)) = token.split_once(":?") {
+        return match std::env::var(name) {
+            Ok(value) => {
+                out.push_str(&value);
+                true
+            }
+            Err(_) => {
+                errors.push(if message.is_empty() {
+                    format!("environment variable '{}' is not set", name)
+                } else {
+                    message.to_string()
+                });
+                false
+            }
+        };
+    }
+    resolve_env_var(token, out, errors)
+}
+
+fn resolve_env_var(name: &str, out: &mut String, errors: &mut Vec<String>) -> bool {
+    match std::env::var(name) {
+        Ok(value) => {
+            out.push_str(&value);
+            true
+        }
+        Err(_) => {
+            errors.push(format!("environment variable '{}' is not set", name));
+            false
+        }
+    }
+}
+
+/// Interpolates every string value in a `extra:`/`config:` map in place
+/// (non-string values, e.g. numbers or nested objects, are left untouched),
+/// so channel-specific passthrough fields like webhook URLs pick up
+/// `$ENV_VAR` references the same as the named credential fields do.
+fn resolve_value_map(
+    map: &mut HashMap<String, Value>,
+    kind: &str,
+    instance: &str,
+    errors: &mut Vec<String>,
+) {
+    for (key, value) in map.iter_mut() {
+        let Value::String(raw) = value else {
+            continue;
+        };
+        if !raw.contains('$') {
+            continue;
+        }
+        let mut local_errors = Vec::new();
+        match interpolate(raw, &mut local_errors) {
+            Some(resolved) => *value = Value::String(resolved),
+            None => errors.extend(
+                local_errors
+                    .into_iter()
+                    .map(|err| format!("{} '{}' field '{}': {}", kind, instance, key, err)),
+            ),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Canonical config types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClawDenConfig {
+    pub agent: AgentConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentConfig {
+    pub name: String,
+    pub runtime: ClawRuntime,
+    pub model: ModelConfig,
+    #[serde(default)]
+    pub tools: Vec<ToolSpec>,
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+    pub security: SecurityConfig,
+    #[serde(default)]
+    pub observability: ObservabilityConfig,
+    #[serde(default)]
+    pub extras: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelConfig {
+    pub provider: String,
+    pub name: String,
+    pub api_key_ref: Option<String>,
+    /// The actual secret value [`SecretVault::resolve_config`] injected for
+    /// `api_key_ref`, populated only on the resolved copy handed to a
+    /// deploy step. Never serialized — `#[serde(skip)]` means a resolved
+    /// config accidentally round-tripped through `to_safe_json` or logged
+    /// as JSON carries only the ref name, never the plaintext.
+    #[serde(skip)]
+    pub resolved_api_key: Option<Secret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    pub channel: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    #[serde(default)]
+    pub sandboxed: bool,
+}
+
+/// Canonical OTLP exporter settings, carried into every `RuntimeConfigTranslator`
+/// output so OpenClaw/ZeroClaw/PicoClaw each receive the same traces/metrics/logs
+/// configuration instead of inventing their own.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ObservabilityConfig {
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    #[serde(default)]
+    pub protocol: ObservabilityProtocol,
+    #[serde(default)]
+    pub service_name: String,
+    #[serde(default)]
+    pub sample_ratio: f64,
+    #[serde(default)]
+    pub traces: bool,
+    #[serde(default)]
+    pub metrics: bool,
+    #[serde(default)]
+    pub logs: bool,
+    /// Exporter auth headers; redacted by [`ClawDenConfig::to_safe_json`].
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl ClawDenConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.agent.name.trim().is_empty() {
+            return Err("agent.name must not be empty".to_string());
+        }
+
+        if self.agent.model.provider.trim().is_empty() || self.agent.model.name.trim().is_empty() {
+            return Err("agent.model provider and name must not be empty".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn to_safe_json(&self) -> Value {
+        let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
+        if let Some(api_ref) = value
+            .get_mut("agent")
+            .and_then(|a| a.get_mut("model"))
+            .and_then(|m| m.get_mut("api_key_ref"))
+        {
+            *api_ref = Value::String("<redacted>".to_string());
+        }
+        if let Some(headers) = value
+            .get_mut("agent")
+            .and_then(|a| a.get_mut("observability"))
+            .and_then(|o| o.get_mut("headers"))
+            .and_then(Value::as_object_mut)
+        {
+            for header_value in headers.values_mut() {
+                *header_value = Value::String("<redacted>".to_string());
+            }
+        }
+        value
+    }
+}
+
+pub trait RuntimeConfigTranslator {
+    fn runtime(&self) -> ClawRuntime;
+    fn to_runtime_config(&self, canonical: &ClawDenConfig) -> Result<Value, String>;
+    #[allow(clippy::wrong_self_convention)]
     fn from_runtime_config(&self, runtime_config: &Value) -> Result<ClawDenConfig, String>;
 }
 
@@ -548,6 +1496,7 @@ impl RuntimeConfigTranslator for OpenClawConfigTranslator {
         ClawRuntime::OpenClaw
     }
 
+    #[tracing::instrument(skip(self, canonical), fields(runtime = ?self.runtime()))]
     fn to_runtime_config(&self, canonical: &ClawDenConfig) -> Result<Value, String> {
         canonical.validate()?;
         Ok(serde_json::json!({
@@ -559,10 +1508,12 @@ impl RuntimeConfigTranslator for OpenClawConfigTranslator {
             "tools": canonical.agent.tools,
             "channels": canonical.agent.channels,
             "security": canonical.agent.security,
+            "observability": canonical.agent.observability,
             "extras": canonical.agent.extras,
         }))
     }
 
+    #[tracing::instrument(skip(self, runtime_config), fields(runtime = ?self.runtime()))]
     fn from_runtime_config(&self, runtime_config: &Value) -> Result<ClawDenConfig, String> {
         let agent = runtime_config
             .get("agent")
@@ -597,6 +1548,7 @@ impl RuntimeConfigTranslator for ZeroClawConfigTranslator {
         ClawRuntime::ZeroClaw
     }
 
+    #[tracing::instrument(skip(self, canonical), fields(runtime = ?self.runtime()))]
     fn to_runtime_config(&self, canonical: &ClawDenConfig) -> Result<Value, String> {
         canonical.validate()?;
         Ok(serde_json::json!({
@@ -607,11 +1559,13 @@ impl RuntimeConfigTranslator for ZeroClawConfigTranslator {
                 "tools": canonical.agent.tools,
                 "channels": canonical.agent.channels,
                 "security": canonical.agent.security,
+                "observability": canonical.agent.observability,
             },
             "extras": canonical.agent.extras,
         }))
     }
 
+    #[tracing::instrument(skip(self, runtime_config), fields(runtime = ?self.runtime()))]
     fn from_runtime_config(&self, runtime_config: &Value) -> Result<ClawDenConfig, String> {
         let agent_obj = runtime_config
             .get("agent")
@@ -646,6 +1600,7 @@ impl RuntimeConfigTranslator for PicoClawConfigTranslator {
         ClawRuntime::PicoClaw
     }
 
+    #[tracing::instrument(skip(self, canonical), fields(runtime = ?self.runtime()))]
     fn to_runtime_config(&self, canonical: &ClawDenConfig) -> Result<Value, String> {
         canonical.validate()?;
         Ok(serde_json::json!({
@@ -659,10 +1614,12 @@ impl RuntimeConfigTranslator for PicoClawConfigTranslator {
             "tools": canonical.agent.tools,
             "channels": canonical.agent.channels,
             "policy": canonical.agent.security,
+            "observability": canonical.agent.observability,
             "extras": canonical.agent.extras,
         }))
     }
 
+    #[tracing::instrument(skip(self, runtime_config), fields(runtime = ?self.runtime()))]
     fn from_runtime_config(&self, runtime_config: &Value) -> Result<ClawDenConfig, String> {
         let name = runtime_config
             .get("name")
@@ -733,6 +1690,17 @@ fn base_config_with_runtime(
         })
         .unwrap_or_else(|| Value::Object(Map::new()));
 
+    let observability = runtime_config
+        .get("observability")
+        .cloned()
+        .or_else(|| {
+            runtime_config
+                .get("agent")
+                .and_then(|agent| agent.get("observability"))
+                .cloned()
+        })
+        .unwrap_or_else(|| Value::Object(Map::new()));
+
     let extras = runtime_config
         .get("extras")
         .and_then(Value::as_object)
@@ -747,6 +1715,7 @@ fn base_config_with_runtime(
                 provider: provider.to_string(),
                 name: model.to_string(),
                 api_key_ref: None,
+                resolved_api_key: None,
             },
             tools: serde_json::from_value(tools).unwrap_or_default(),
             channels: serde_json::from_value(channels).unwrap_or_default(),
@@ -754,6 +1723,7 @@ fn base_config_with_runtime(
                 allowlist: vec![],
                 sandboxed: false,
             }),
+            observability: serde_json::from_value(observability).unwrap_or_default(),
             extras,
         },
     }
@@ -763,73 +1733,553 @@ fn base_config_with_runtime(
 // Secret Vault — encrypted at-rest secret store
 // ---------------------------------------------------------------------------
 
-/// A simple XOR-based obfuscation key for the in-memory vault.
-/// In production, this would delegate to age/sops or a system keychain;
-/// here we provide the API surface with a basic symmetric cipher to protect
-/// secrets at rest in memory dumps.
-pub struct SecretVault {
-    /// Secrets stored as (name → encrypted_bytes).
-    store: HashMap<String, Vec<u8>>,
-    /// Symmetric key for XOR obfuscation. In production, use a real KDF + AES.
-    key: Vec<u8>,
-}
-
-impl SecretVault {
-    /// Create a new vault with the given encryption key.
-    pub fn new(key: &[u8]) -> Self {
-        assert!(!key.is_empty(), "vault key must not be empty");
+/// Argon2id cost parameters for deriving the vault key from a passphrase.
+/// `m_cost` is in KiB; these follow the OWASP-recommended minimum for
+/// interactive logins and are fixed rather than configurable so every vault
+/// derives its key the same way regardless of who created it.
+const ARGON2_M_COST_KIB: u32 = 19_456;
+const ARGON2_T_COST: u32 = 2;
+const ARGON2_P_COST: u32 = 1;
+const AES_GCM_NONCE_LEN: usize = 12;
+
+/// A secret value that zeroizes its buffer on drop and never prints its
+/// contents — the "safe password" pattern where the credential type itself
+/// enforces non-disclosure instead of relying on every call site (logging,
+/// `Debug` derives, error messages) to remember not to leak it. Deliberately
+/// does not implement `Serialize`, so a struct holding one can't accidentally
+/// round-trip the plaintext out through `serde_json::to_value`.
+#[derive(Clone)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// Read the plaintext. Named to make every call site grep-able and to
+    /// read as a deliberate, explicit choice rather than an accident.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Failure modes for [`SecretVault::get_checked`] — distinguishes a missing
+/// secret from one that exists but couldn't be authenticated (wrong
+/// passphrase, corrupted ciphertext, or tampering), which `Option::None`
+/// alone can't tell apart.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum VaultError {
+    #[error("secret '{0}' not found in vault")]
+    NotFound(String),
+    #[error("decryption or authentication failed for secret '{0}'")]
+    AuthenticationFailed(String),
+    #[error("token does not authorize 'vault/resolve' on secret '{0}'")]
+    NotAuthorized(String),
+}
+
+/// Storage half of a [`SecretVault`], factored out the same way
+/// `RuntimeConfigTranslator` factors "which runtime" out of config shape —
+/// implementations decide *where* sealed bytes live, never what they mean,
+/// so swapping one in doesn't touch `put`/`get`/`resolve_config` call sites.
+pub trait VaultBackend {
+    /// Returns the sealed (`nonce || ciphertext || tag`) bytes for `name`,
+    /// if present.
+    fn load(&self, name: &str) -> Option<Vec<u8>>;
+    /// Persists the sealed bytes for `name`, overwriting any previous value.
+    fn store(&mut self, name: &str, sealed: Vec<u8>);
+    /// Removes `name`. Returns whether it was present.
+    fn delete(&mut self, name: &str) -> bool;
+    /// Every known secret name, sorted.
+    fn list_names(&self) -> Vec<String>;
+}
+
+/// The original in-memory backend: sealed bytes live only for the process
+/// lifetime. Appropriate for tests and for deploy jobs that build a vault
+/// from freshly-fetched secrets and never need it to survive a restart.
+#[derive(Default, Clone)]
+pub struct InMemoryBackend {
+    entries: HashMap<String, Vec<u8>>,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_entries(entries: HashMap<String, Vec<u8>>) -> Self {
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &HashMap<String, Vec<u8>> {
+        &self.entries
+    }
+}
+
+impl VaultBackend for InMemoryBackend {
+    fn load(&self, name: &str) -> Option<Vec<u8>> {
+        self.entries.get(name).cloned()
+    }
+
+    fn store(&mut self, name: &str, sealed: Vec<u8>) {
+        self.entries.insert(name.to_string(), sealed);
+    }
+
+    fn delete(&mut self, name: &str) -> bool {
+        self.entries.remove(name).is_some()
+    }
+
+    fn list_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.entries.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Persists the whole sealed store as one [age](https://age-encryption.org)
+/// blob, encrypted to one or more recipients, so a vault survives a process
+/// restart and the resulting file can be committed to source control like
+/// any other `sops`/`age`-encrypted secret.
+pub struct AgeFileBackend {
+    path: std::path::PathBuf,
+    recipients: Vec<age::x25519::Recipient>,
+    identity: age::x25519::Identity,
+    cache: HashMap<String, Vec<u8>>,
+}
+
+impl AgeFileBackend {
+    /// Opens `path`, decrypting its existing contents with `identity` if the
+    /// file is already present, or starting empty otherwise. Every
+    /// subsequent mutation re-encrypts the whole store to `recipients` and
+    /// rewrites `path`.
+    pub fn open(
+        path: std::path::PathBuf,
+        recipients: Vec<age::x25519::Recipient>,
+        identity: age::x25519::Identity,
+    ) -> Result<Self, String> {
+        let cache = if path.exists() {
+            Self::decrypt(&path, &identity)?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            path,
+            recipients,
+            identity,
+            cache,
+        })
+    }
+
+    fn decrypt(
+        path: &std::path::Path,
+        identity: &age::x25519::Identity,
+    ) -> Result<HashMap<String, Vec<u8>>, String> {
+        use std::io::Read;
+
+        let encrypted = std::fs::read(path).map_err(|e| e.to_string())?;
+        let decryptor =
+            age::Decryptor::new(&encrypted[..]).map_err(|e| format!("invalid age file: {e}"))?;
+        let mut plaintext = Vec::new();
+        let mut reader = decryptor
+            .decrypt(std::iter::once(identity as &dyn age::Identity))
+            .map_err(|e| format!("age decryption failed: {e}"))?;
+        reader
+            .read_to_end(&mut plaintext)
+            .map_err(|e| e.to_string())?;
+        serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        use std::io::Write;
+
+        let plaintext = serde_json::to_vec(&self.cache).map_err(|e| e.to_string())?;
+        let recipients: Vec<Box<dyn age::Recipient + Send>> = self
+            .recipients
+            .iter()
+            .map(|r| Box::new(r.clone()) as Box<dyn age::Recipient + Send>)
+            .collect();
+        let encryptor = age::Encryptor::with_recipients(recipients)
+            .map_err(|e| format!("building age encryptor: {e}"))?;
+        let mut encrypted = Vec::new();
+        let mut writer = encryptor
+            .wrap_output(&mut encrypted)
+            .map_err(|e| e.to_string())?;
+        writer.write_all(&plaintext).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, encrypted).map_err(|e| e.to_string())
+    }
+}
+
+impl VaultBackend for AgeFileBackend {
+    fn load(&self, name: &str) -> Option<Vec<u8>> {
+        self.cache.get(name).cloned()
+    }
+
+    fn store(&mut self, name: &str, sealed: Vec<u8>) {
+        self.cache.insert(name.to_string(), sealed);
+        // Best-effort: a failed write leaves the in-memory cache ahead of
+        // disk, which the next successful mutation reconciles.
+        let _ = self.persist();
+    }
+
+    fn delete(&mut self, name: &str) -> bool {
+        let existed = self.cache.remove(name).is_some();
+        if existed {
+            let _ = self.persist();
+        }
+        existed
+    }
+
+    fn list_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.cache.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Delegates storage to the OS secret service (macOS Keychain, Windows
+/// Credential Manager, Linux Secret Service / kwallet) via the `keyring`
+/// crate, so sealed bytes never touch disk as a file ClawDen manages itself.
+///
+/// OS secret services don't expose a "list all entries under this service"
+/// API, so `list_names` can only report names this process has itself
+/// stored or deleted since it started — unlike the other two backends, it
+/// is not a durable source of truth for enumeration across restarts.
+pub struct KeychainBackend {
+    service: String,
+    known_names: std::sync::Mutex<HashSet<String>>,
+}
+
+impl KeychainBackend {
+    pub fn new(service: impl Into<String>) -> Self {
         Self {
-            store: HashMap::new(),
-            key: key.to_vec(),
+            service: service.into(),
+            known_names: std::sync::Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn entry(&self, name: &str) -> keyring::Entry {
+        keyring::Entry::new(&self.service, name)
+            .expect("service/username strings are always valid keyring entry keys")
+    }
+}
+
+impl VaultBackend for KeychainBackend {
+    fn load(&self, name: &str) -> Option<Vec<u8>> {
+        let encoded = self.entry(name).get_password().ok()?;
+        BASE64.decode(encoded).ok()
+    }
+
+    fn store(&mut self, name: &str, sealed: Vec<u8>) {
+        if self.entry(name).set_password(&BASE64.encode(sealed)).is_ok() {
+            self.known_names
+                .lock()
+                .expect("keychain name index mutex poisoned")
+                .insert(name.to_string());
+        }
+    }
+
+    fn delete(&mut self, name: &str) -> bool {
+        let deleted = self.entry(name).delete_credential().is_ok();
+        if deleted {
+            self.known_names
+                .lock()
+                .expect("keychain name index mutex poisoned")
+                .remove(name);
+        }
+        deleted
+    }
+
+    fn list_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .known_names
+            .lock()
+            .expect("keychain name index mutex poisoned")
+            .iter()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// One grant within an [`UnsignedCapabilityGrant`]: the right to perform
+/// `ability` on `resource`. Modeled on UCAN's `{resource, ability}`
+/// capability shape.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Capability {
+    pub resource: String,
+    pub ability: String,
+}
+
+/// A delegable capability descriptor shaped like a UCAN token (`issuer`
+/// grants `audience` the listed `capabilities`, optionally because `issuer`
+/// was themselves delegated those capabilities by `proofs` — a chain of
+/// grants each vouching for the next) but **not cryptographically signed**.
+/// `issuer`/`audience` are bare, unverified strings: anyone who can
+/// construct this JSON can claim to be any issuer. [`check_grants`] only
+/// checks that the delegation chain is internally consistent and rooted in
+/// a name from `trusted_issuers` — it does not and cannot prove that chain
+/// actually came from that issuer. Use this only in a trusted environment
+/// where whoever can produce this JSON is already authorized (e.g. loaded
+/// from local config you control); do not accept one from an untrusted
+/// caller or expose it over a network boundary. For an authenticity
+/// guarantee, sign the config itself with [`sign_config`]/[`verify_config`]
+/// instead, the way [`SignedConfig`] does.
+///
+/// [`check_grants`]: UnsignedCapabilityGrant::check_grants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsignedCapabilityGrant {
+    pub issuer: String,
+    pub audience: String,
+    pub capabilities: Vec<Capability>,
+    #[serde(default)]
+    pub proofs: Vec<UnsignedCapabilityGrant>,
+}
+
+impl UnsignedCapabilityGrant {
+    /// Whether this grant's delegation chain authorizes `ability` on
+    /// `resource`, ultimately rooted in one of `trusted_issuers`.
+    ///
+    /// Each link in `proofs` must chain `audience -> issuer` into the next
+    /// (so delegation can't be forged by splicing in an unrelated grant),
+    /// and a child's capabilities must attenuate its parent's: the
+    /// resource must match by prefix and the ability exactly, so a
+    /// delegate can only narrow what it was granted, never widen it. The
+    /// root of the chain (the grant with no further proofs) must claim to
+    /// have been issued by a trusted party — this is a structural check
+    /// only, since nothing here is signed; see the struct-level doc comment
+    /// before trusting this across a privilege boundary.
+    pub fn check_grants(
+        &self,
+        resource: &str,
+        ability: &str,
+        trusted_issuers: &HashSet<String>,
+    ) -> Result<(), VaultError> {
+        if !Self::capabilities_grant(&self.capabilities, resource, ability) {
+            return Err(VaultError::NotAuthorized(resource.to_string()));
+        }
+
+        let mut current = self;
+        loop {
+            match current.proofs.as_slice() {
+                [] => {
+                    return if trusted_issuers.contains(&current.issuer) {
+                        Ok(())
+                    } else {
+                        Err(VaultError::NotAuthorized(resource.to_string()))
+                    };
+                }
+                proofs => {
+                    let parent = proofs
+                        .iter()
+                        .find(|proof| proof.audience == current.issuer)
+                        .ok_or_else(|| VaultError::NotAuthorized(resource.to_string()))?;
+                    if !Self::capabilities_grant(&parent.capabilities, resource, ability) {
+                        return Err(VaultError::NotAuthorized(resource.to_string()));
+                    }
+                    current = parent;
+                }
+            }
         }
     }
 
-    /// Store a secret. The value is encrypted before being stored.
+    fn capabilities_grant(capabilities: &[Capability], resource: &str, ability: &str) -> bool {
+        capabilities
+            .iter()
+            .any(|cap| cap.ability == ability && resource.starts_with(&cap.resource))
+    }
+}
+
+/// Encrypted-at-rest secret store. The vault key is derived from a
+/// passphrase with Argon2id over a random salt (stored alongside the vault
+/// so the derivation is reproducible when the vault is reloaded), and each
+/// secret is sealed independently with AES-256-GCM under a fresh random
+/// nonce, so two secrets encrypted with the same key never share a nonce.
+/// Generic over [`VaultBackend`] so operators choose at-rest storage
+/// without touching any `put`/`get`/`resolve_config` call site.
+pub struct SecretVault<B: VaultBackend = InMemoryBackend> {
+    backend: B,
+    /// AES-256-GCM key derived from the vault passphrase.
+    key: [u8; 32],
+    /// Random salt the key was derived against; persist this alongside the
+    /// vault so [`Self::load`] can re-derive the same key later.
+    salt: [u8; 16],
+}
+
+impl SecretVault<InMemoryBackend> {
+    /// Create a new in-memory vault, deriving its key from `passphrase` with
+    /// Argon2id over a freshly generated random salt.
+    pub fn new(passphrase: &[u8]) -> Self {
+        Self::with_backend(passphrase, InMemoryBackend::default())
+    }
+
+    /// Re-opens an in-memory vault previously persisted with [`Self::salt`]
+    /// and [`Self::store`], re-deriving the same key from `passphrase`
+    /// against `salt` rather than generating a new one.
+    pub fn load(passphrase: &[u8], salt: [u8; 16], store: HashMap<String, Vec<u8>>) -> Self {
+        Self::with_backend_and_salt(passphrase, salt, InMemoryBackend::from_entries(store))
+    }
+
+    /// The sealed (`nonce || ciphertext || tag`) store, for persisting
+    /// alongside [`Self::salt`].
+    pub fn store(&self) -> &HashMap<String, Vec<u8>> {
+        self.backend.entries()
+    }
+}
+
+impl<B: VaultBackend> SecretVault<B> {
+    /// Create a new vault backed by `backend`, deriving its key from
+    /// `passphrase` with Argon2id over a freshly generated random salt.
+    pub fn with_backend(passphrase: &[u8], backend: B) -> Self {
+        assert!(!passphrase.is_empty(), "vault passphrase must not be empty");
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = Self::derive_key(passphrase, &salt);
+        Self { backend, key, salt }
+    }
+
+    /// Create a vault backed by `backend`, re-deriving its key from
+    /// `passphrase` against a previously persisted `salt`.
+    pub fn with_backend_and_salt(passphrase: &[u8], salt: [u8; 16], backend: B) -> Self {
+        assert!(!passphrase.is_empty(), "vault passphrase must not be empty");
+        let key = Self::derive_key(passphrase, &salt);
+        Self { backend, key, salt }
+    }
+
+    /// The salt the vault key was derived against, for persisting alongside
+    /// the backend's storage so a future `with_backend_and_salt` call can
+    /// reopen the vault later.
+    pub fn salt(&self) -> [u8; 16] {
+        self.salt
+    }
+
+    fn derive_key(passphrase: &[u8], salt: &[u8; 16]) -> [u8; 32] {
+        let params = Params::new(ARGON2_M_COST_KIB, ARGON2_T_COST, ARGON2_P_COST, Some(32))
+            .expect("static Argon2 params are valid");
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        let mut key = [0u8; 32];
+        argon2
+            .hash_password_into(passphrase, salt, &mut key)
+            .expect("Argon2 derivation with static params cannot fail");
+        key
+    }
+
+    /// Store a secret. The value is sealed with AES-256-GCM under a fresh
+    /// random nonce before being stored.
     pub fn put(&mut self, name: &str, plaintext: &str) {
-        let encrypted = Self::xor_bytes(plaintext.as_bytes(), &self.key);
-        self.store.insert(name.to_string(), encrypted);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key));
+        let mut nonce_bytes = [0u8; AES_GCM_NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+            .expect("AES-256-GCM encryption with a fresh nonce cannot fail");
+
+        let mut sealed = Vec::with_capacity(AES_GCM_NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce_bytes);
+        sealed.extend_from_slice(&ciphertext);
+        self.backend.store(name, sealed);
     }
 
-    /// Retrieve and decrypt a secret by name. Returns `None` if not found.
-    pub fn get(&self, name: &str) -> Option<String> {
-        self.store.get(name).map(|encrypted| {
-            let decrypted = Self::xor_bytes(encrypted, &self.key);
-            String::from_utf8_lossy(&decrypted).into_owned()
-        })
+    /// Retrieve and decrypt a secret by name. Returns `None` if the secret
+    /// is missing *or* fails to authenticate — use [`Self::get_checked`] to
+    /// tell those cases apart. Returns a [`Secret`] rather than a plain
+    /// `String` so the plaintext doesn't linger in memory past its last use.
+    pub fn get(&self, name: &str) -> Option<Secret> {
+        self.get_checked(name).ok()
+    }
+
+    /// Same as [`Self::get`], but returns a [`VaultError`] distinguishing
+    /// "not found" from "tag verification failed" instead of collapsing
+    /// both to `None`.
+    pub fn get_checked(&self, name: &str) -> Result<Secret, VaultError> {
+        let sealed = self
+            .backend
+            .load(name)
+            .ok_or_else(|| VaultError::NotFound(name.to_string()))?;
+        self.open(name, &sealed)
+    }
+
+    fn open(&self, name: &str, sealed: &[u8]) -> Result<Secret, VaultError> {
+        if sealed.len() < AES_GCM_NONCE_LEN {
+            return Err(VaultError::AuthenticationFailed(name.to_string()));
+        }
+        let (nonce_bytes, ciphertext) = sealed.split_at(AES_GCM_NONCE_LEN);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| VaultError::AuthenticationFailed(name.to_string()))?;
+        String::from_utf8(plaintext)
+            .map(Secret::new)
+            .map_err(|_| VaultError::AuthenticationFailed(name.to_string()))
     }
 
     /// Remove a secret.
     pub fn remove(&mut self, name: &str) -> bool {
-        self.store.remove(name).is_some()
+        self.backend.delete(name)
     }
 
     /// List all secret names (values are never exposed).
     pub fn list_names(&self) -> Vec<String> {
-        let mut names: Vec<_> = self.store.keys().cloned().collect();
-        names.sort();
-        names
+        self.backend.list_names()
     }
 
-    /// Resolve all `api_key_ref` values in a config by injecting from the vault.
-    /// Returns a new config with the `api_key_ref` field replaced by the actual
-    /// secret value. This is intended for deploy-time injection only; the result
-    /// should never be logged or persisted.
+    /// Resolve all `api_key_ref` values in a config by injecting from the
+    /// vault. Returns a new config with the resolved secret populated into
+    /// [`ModelConfig::resolved_api_key`] — `api_key_ref` itself is left
+    /// alone, so the ref name (not the secret value) is still what gets
+    /// logged or persisted if this config is serialized. This is intended
+    /// for deploy-time injection only.
     pub fn resolve_config(&self, config: &ClawDenConfig) -> Result<ClawDenConfig, String> {
         let mut resolved = config.clone();
         if let Some(ref key_ref) = resolved.agent.model.api_key_ref {
             let secret = self
                 .get(key_ref)
                 .ok_or_else(|| format!("secret '{}' not found in vault", key_ref))?;
-            resolved.agent.model.api_key_ref = Some(secret);
+            resolved.agent.model.resolved_api_key = Some(secret);
         }
         Ok(resolved)
     }
 
-    fn xor_bytes(data: &[u8], key: &[u8]) -> Vec<u8> {
-        data.iter()
-            .enumerate()
-            .map(|(i, byte)| byte ^ key[i % key.len()])
-            .collect()
+    /// Same as [`Self::resolve_config`], but only injects `api_key_ref` if
+    /// `grant`'s delegation chain authorizes `vault/resolve` on exactly
+    /// that resource name, rooted in `trusted_issuers` — so a caller with
+    /// the vault key but only an attenuated grant can't read secrets
+    /// outside what it was delegated, unlike the unconditional access
+    /// [`Self::resolve_config`] gives. `grant` is unsigned (see
+    /// [`UnsignedCapabilityGrant`]'s doc comment): only call this with a
+    /// grant that already came from a trusted source, never one supplied by
+    /// an untrusted caller.
+    pub fn resolve_config_with_token(
+        &self,
+        config: &ClawDenConfig,
+        grant: &UnsignedCapabilityGrant,
+        trusted_issuers: &HashSet<String>,
+    ) -> Result<ClawDenConfig, VaultError> {
+        let mut resolved = config.clone();
+        if let Some(ref key_ref) = resolved.agent.model.api_key_ref {
+            grant.check_grants(key_ref, "vault/resolve", trusted_issuers)?;
+            let secret = self.get_checked(key_ref)?;
+            resolved.agent.model.resolved_api_key = Some(secret);
+        }
+        Ok(resolved)
     }
 }
 
@@ -846,11 +2296,17 @@ pub struct ConfigDiff {
 }
 
 /// Compare two configs and return the list of differences.
+///
+/// Span fields carry only the resulting `diff_count`, never the diffs
+/// themselves — `ConfigDiff::expected`/`actual` can legitimately include
+/// config values, and those don't belong in trace backends.
+#[tracing::instrument(skip_all, fields(diff_count = tracing::field::Empty))]
 pub fn diff_configs(expected: &ClawDenConfig, actual: &ClawDenConfig) -> Vec<ConfigDiff> {
     let expected_json = serde_json::to_value(expected).unwrap_or(Value::Null);
     let actual_json = serde_json::to_value(actual).unwrap_or(Value::Null);
     let mut diffs = Vec::new();
     diff_value("", &expected_json, &actual_json, &mut diffs);
+    tracing::Span::current().record("diff_count", diffs.len());
     diffs
 }
 
@@ -890,15 +2346,333 @@ fn diff_value(path: &str, expected: &Value, actual: &Value, diffs: &mut Vec<Conf
     }
 }
 
-/// Detect drift: compare the canonical config against the runtime's current config.
-/// Returns an empty vec if in sync.
-pub fn detect_drift(
+/// Detect drift: compare the canonical config against the runtime's current config.
+/// Returns an empty vec if in sync.
+#[tracing::instrument(
+    skip(translator, canonical, runtime_native),
+    fields(runtime = ?translator.runtime(), diff_count = tracing::field::Empty)
+)]
+pub fn detect_drift(
+    translator: &dyn RuntimeConfigTranslator,
+    canonical: &ClawDenConfig,
+    runtime_native: &Value,
+) -> Result<Vec<ConfigDiff>, String> {
+    let actual_canonical = translator.from_runtime_config(runtime_native)?;
+    let diffs = diff_configs(canonical, &actual_canonical);
+    tracing::Span::current().record("diff_count", diffs.len());
+    #[cfg(feature = "otel")]
+    otel_metrics::record_drift_run(&format!("{:?}", translator.runtime()), diffs.len());
+    Ok(diffs)
+}
+
+/// OpenTelemetry metric instruments for the drift-detection path, gated
+/// behind the `otel` cargo feature so crates that don't enable it never pull
+/// in an OTLP exporter or pay for metric recording.
+#[cfg(feature = "otel")]
+mod otel_metrics {
+    use opentelemetry::metrics::{Counter, Histogram};
+    use opentelemetry::KeyValue;
+    use std::sync::OnceLock;
+
+    fn meter() -> opentelemetry::metrics::Meter {
+        opentelemetry::global::meter("clawden_config")
+    }
+
+    fn drift_runs() -> &'static Counter<u64> {
+        static COUNTER: OnceLock<Counter<u64>> = OnceLock::new();
+        COUNTER.get_or_init(|| {
+            meter()
+                .u64_counter("clawden_config.drift.runs")
+                .with_description("Number of detect_drift calls")
+                .init()
+        })
+    }
+
+    fn drift_outcomes() -> &'static Counter<u64> {
+        static COUNTER: OnceLock<Counter<u64>> = OnceLock::new();
+        COUNTER.get_or_init(|| {
+            meter()
+                .u64_counter("clawden_config.drift.outcomes")
+                .with_description(
+                    "detect_drift outcomes, tagged outcome=drift_detected|in_sync",
+                )
+                .init()
+        })
+    }
+
+    fn drift_changed_fields() -> &'static Histogram<u64> {
+        static HISTOGRAM: OnceLock<Histogram<u64>> = OnceLock::new();
+        HISTOGRAM.get_or_init(|| {
+            meter()
+                .u64_histogram("clawden_config.drift.changed_fields")
+                .with_description("Number of changed fields observed per detect_drift call")
+                .init()
+        })
+    }
+
+    pub(super) fn record_drift_run(runtime: &str, diff_count: usize) {
+        let runtime_attr = KeyValue::new("runtime", runtime.to_string());
+        drift_runs().add(1, &[runtime_attr.clone()]);
+        let outcome = if diff_count > 0 {
+            "drift_detected"
+        } else {
+            "in_sync"
+        };
+        drift_outcomes().add(
+            1,
+            &[runtime_attr.clone(), KeyValue::new("outcome", outcome)],
+        );
+        drift_changed_fields().record(diff_count as u64, &[runtime_attr]);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Signed config bundles (spec 019)
+// ---------------------------------------------------------------------------
+
+/// A config plus a detached Ed25519 signature over its canonical JSON
+/// encoding, so [`detect_drift_verified`] (or a runtime receiving the
+/// bundle over the wire) can confirm it came from a trusted operator key
+/// before trusting its contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedConfig {
+    pub config: ClawDenConfig,
+    /// Base64-encoded detached Ed25519 signature over
+    /// `canonical_json(&config)`.
+    pub signature: String,
+    /// Identifies which trusted public key to verify against; callers look
+    /// this up in their own key registry rather than trusting an embedded key.
+    pub key_id: String,
+}
+
+/// Serializes `value` with object keys sorted and no insignificant
+/// whitespace, so two semantically-equal configs always sign (or diff)
+/// identically regardless of struct field order. Reuses [`diff_value`]'s
+/// object/array traversal shape, but rebuilds rather than compares.
+fn canonical_json(value: &Value) -> String {
+    fn canonicalize(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                let mut sorted = Map::new();
+                for (key, val) in entries {
+                    sorted.insert(key.clone(), canonicalize(val));
+                }
+                Value::Object(sorted)
+            }
+            Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+            other => other.clone(),
+        }
+    }
+    canonicalize(value).to_string()
+}
+
+/// Canonicalizes `config`, signs it with `signing_key`, and wraps the result
+/// in a [`SignedConfig`] tagged with `key_id` so a verifier knows which
+/// trusted key to check the signature against. This is a detached
+/// signature in the style of a compact JWS with `alg: EdDSA` and `b64:
+/// false` — the signed bytes are the canonical JSON itself, not a
+/// re-encoding of it.
+pub fn sign_config(
+    config: &ClawDenConfig,
+    signing_key: &ed25519_dalek::SigningKey,
+    key_id: &str,
+) -> Result<SignedConfig, String> {
+    let json = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    let canonical = canonical_json(&json);
+    let signature = signing_key.sign(canonical.as_bytes());
+    Ok(SignedConfig {
+        config: config.clone(),
+        signature: BASE64.encode(signature.to_bytes()),
+        key_id: key_id.to_string(),
+    })
+}
+
+/// Verifies `bundle`'s signature against `public_key`, returning the signed
+/// config only if it checks out.
+pub fn verify_config(
+    bundle: &SignedConfig,
+    public_key: &ed25519_dalek::VerifyingKey,
+) -> Result<ClawDenConfig, String> {
+    let json = serde_json::to_value(&bundle.config).map_err(|e| e.to_string())?;
+    let canonical = canonical_json(&json);
+    let sig_bytes = BASE64
+        .decode(&bundle.signature)
+        .map_err(|e| format!("invalid signature encoding: {e}"))?;
+    let sig_array: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| "signature must be 64 bytes".to_string())?;
+    let signature = ed25519_dalek::Signature::from_bytes(&sig_array);
+    public_key
+        .verify(canonical.as_bytes(), &signature)
+        .map_err(|_| format!("signature verification failed for key '{}'", bundle.key_id))?;
+    Ok(bundle.config.clone())
+}
+
+/// Same as [`detect_drift`], but first verifies `canonical`'s signature
+/// against whichever of `trusted_keys` matches its `key_id` — drift is only
+/// meaningful against a config bundle known to have come from a trusted
+/// operator, not an arbitrary unsigned blob that happened to parse.
+pub fn detect_drift_verified(
     translator: &dyn RuntimeConfigTranslator,
-    canonical: &ClawDenConfig,
+    canonical: &SignedConfig,
+    trusted_keys: &HashMap<String, ed25519_dalek::VerifyingKey>,
     runtime_native: &Value,
 ) -> Result<Vec<ConfigDiff>, String> {
-    let actual_canonical = translator.from_runtime_config(runtime_native)?;
-    Ok(diff_configs(canonical, &actual_canonical))
+    let public_key = trusted_keys.get(&canonical.key_id).ok_or_else(|| {
+        format!(
+            "no trusted key registered for key_id '{}'",
+            canonical.key_id
+        )
+    })?;
+    let verified_config = verify_config(canonical, public_key)?;
+    detect_drift(translator, &verified_config, runtime_native)
+}
+
+// ---------------------------------------------------------------------------
+// RFC 8188 encrypted envelopes for remote config delivery (spec 020)
+// ---------------------------------------------------------------------------
+
+/// Record size (`rs`) used for every envelope this crate produces: each
+/// record's plaintext-plus-delimiter must fit within this many bytes.
+const RFC8188_RECORD_SIZE: u32 = 4096;
+const RFC8188_SALT_LEN: usize = 16;
+const AES_GCM_TAG_LEN: usize = 16;
+
+fn derive_rfc8188_keys(ikm: &[u8], salt: &[u8]) -> ([u8; 16], [u8; 12]) {
+    let hk = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let mut content_encryption_key = [0u8; 16];
+    hk.expand(b"Content-Encoding: aes128gcm\0", &mut content_encryption_key)
+        .expect("HKDF expand for a 16-byte CEK cannot fail");
+    let mut base_nonce = [0u8; 12];
+    hk.expand(b"Content-Encoding: nonce\0", &mut base_nonce)
+        .expect("HKDF expand for a 12-byte nonce cannot fail");
+    (content_encryption_key, base_nonce)
+}
+
+fn rfc8188_nonce(base_nonce: &[u8; 12], seq: u64) -> [u8; 12] {
+    let mut nonce = *base_nonce;
+    for (byte, seq_byte) in nonce[4..].iter_mut().zip(seq.to_be_bytes()) {
+        *byte ^= seq_byte;
+    }
+    nonce
+}
+
+/// Encrypts `resolved_config` for `recipient_public_key` using the
+/// `aes128gcm` HTTP encrypted-content-encoding scheme ([RFC
+/// 8188](https://www.rfc-editor.org/rfc/rfc8188)), so the config can cross
+/// the wire to a remote OpenClaw/ZeroClaw host without appearing in the
+/// clear — only the holder of `recipient_public_key`'s matching private key
+/// can decrypt it with [`decrypt_from_runtime`]. The envelope holds the
+/// config's serde JSON encoding, so [`ModelConfig::resolved_api_key`]
+/// (`#[serde(skip)]`) is not included: a [`Secret`] injected by
+/// [`SecretVault::resolve_config`] is not carried, and the decrypted
+/// config's `resolved_api_key` is `None`.
+pub fn encrypt_for_runtime(
+    resolved_config: &ClawDenConfig,
+    recipient_public_key: &p256::PublicKey,
+) -> Result<Vec<u8>, String> {
+    let plaintext = serde_json::to_vec(resolved_config).map_err(|e| e.to_string())?;
+
+    let ephemeral_secret = p256::ecdh::EphemeralSecret::random(&mut rand::thread_rng());
+    let ephemeral_public = p256::PublicKey::from(&ephemeral_secret);
+    let shared_secret = ephemeral_secret.diffie_hellman(recipient_public_key);
+
+    let mut salt = [0u8; RFC8188_SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key_id = ephemeral_public.to_sec1_bytes();
+    let (content_encryption_key, base_nonce) =
+        derive_rfc8188_keys(shared_secret.raw_secret_bytes(), &salt);
+
+    let mut output = Vec::with_capacity(RFC8188_SALT_LEN + 4 + 1 + key_id.len());
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&RFC8188_RECORD_SIZE.to_be_bytes());
+    output.push(key_id.len() as u8);
+    output.extend_from_slice(&key_id);
+
+    let cipher = Aes128Gcm::new(Key::<Aes128Gcm>::from_slice(&content_encryption_key));
+    let content_capacity = RFC8188_RECORD_SIZE as usize - 1;
+    let mut offset = 0;
+    let mut seq: u64 = 0;
+    loop {
+        let end = (offset + content_capacity).min(plaintext.len());
+        let is_final = end == plaintext.len();
+        let mut record = plaintext[offset..end].to_vec();
+        record.push(if is_final { 0x02 } else { 0x01 });
+
+        let nonce = rfc8188_nonce(&base_nonce, seq);
+        let sealed = cipher
+            .encrypt(Nonce::from_slice(&nonce), record.as_ref())
+            .map_err(|_| "aes128gcm record encryption failed".to_string())?;
+        output.extend_from_slice(&sealed);
+
+        if is_final {
+            break;
+        }
+        offset = end;
+        seq += 1;
+    }
+    Ok(output)
+}
+
+/// Decrypts an envelope produced by [`encrypt_for_runtime`] using the
+/// recipient's P-256 private key.
+pub fn decrypt_from_runtime(
+    envelope: &[u8],
+    recipient_secret_key: &p256::SecretKey,
+) -> Result<ClawDenConfig, String> {
+    if envelope.len() < RFC8188_SALT_LEN + 4 + 1 {
+        return Err("envelope too short for an aes128gcm header".to_string());
+    }
+    let salt = &envelope[..RFC8188_SALT_LEN];
+    let record_size = u32::from_be_bytes(
+        envelope[RFC8188_SALT_LEN..RFC8188_SALT_LEN + 4]
+            .try_into()
+            .expect("slice of length 4"),
+    );
+    let key_id_len = envelope[RFC8188_SALT_LEN + 4] as usize;
+    let key_id_start = RFC8188_SALT_LEN + 4 + 1;
+    let key_id_end = key_id_start + key_id_len;
+    if envelope.len() < key_id_end {
+        return Err("envelope truncated before end of keyid".to_string());
+    }
+
+    let ephemeral_public = p256::PublicKey::from_sec1_bytes(&envelope[key_id_start..key_id_end])
+        .map_err(|e| format!("invalid ephemeral public key in header: {e}"))?;
+    let shared_secret = p256::ecdh::diffie_hellman(
+        recipient_secret_key.to_nonzero_scalar(),
+        ephemeral_public.as_affine(),
+    );
+    let (content_encryption_key, base_nonce) =
+        derive_rfc8188_keys(shared_secret.raw_secret_bytes(), salt);
+
+    let cipher = Aes128Gcm::new(Key::<Aes128Gcm>::from_slice(&content_encryption_key));
+    let sealed_record_len = record_size as usize + AES_GCM_TAG_LEN;
+    let body = &envelope[key_id_end..];
+
+    let mut plaintext = Vec::new();
+    let mut offset = 0;
+    let mut seq: u64 = 0;
+    while offset < body.len() {
+        let end = (offset + sealed_record_len).min(body.len());
+        let is_final = end == body.len();
+        let nonce = rfc8188_nonce(&base_nonce, seq);
+        let mut record = cipher
+            .decrypt(Nonce::from_slice(&nonce), &body[offset..end])
+            .map_err(|_| "aes128gcm record decryption failed".to_string())?;
+        let delimiter = record
+            .pop()
+            .ok_or_else(|| "empty decrypted record".to_string())?;
+        let expected_delimiter = if is_final { 0x02 } else { 0x01 };
+        if delimiter != expected_delimiter {
+            return Err("unexpected record delimiter octet".to_string());
+        }
+        plaintext.extend_from_slice(&record);
+        offset = end;
+        seq += 1;
+    }
+    serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
 }
 
 // ---------------------------------------------------------------------------
@@ -1006,13 +2780,13 @@ impl ChannelCredentialMapper {
 #[cfg(test)]
 mod tests {
     use super::{
-        diff_configs, ClawDenConfig, ClawDenYaml, LlmProvider, ModelConfig,
-        OpenClawConfigTranslator, PicoClawConfigTranslator, RuntimeConfigTranslator, SecretVault,
-        ZeroClawConfigTranslator,
+        diff_configs, ClawDenConfig, ClawDenYaml, ConfigOverride, LlmProvider, Merge, ModelConfig,
+        OpenClawConfigTranslator, PicoClawConfigTranslator, ProviderCapabilitiesOverride,
+        RuntimeConfigTranslator, Secret, SecretVault, VaultError, ZeroClawConfigTranslator,
     };
-    use crate::{AgentConfig, ChannelConfig, SecurityConfig, ToolConfig};
+    use crate::{AgentConfig, ChannelConfig, SecurityConfig, ToolSpec};
     use clawden_core::ClawRuntime;
-    use serde_json::Map;
+    use serde_json::{Map, Value};
 
     fn sample_config(runtime: ClawRuntime) -> ClawDenConfig {
         ClawDenConfig {
@@ -1023,10 +2797,17 @@ mod tests {
                     provider: "openai".to_string(),
                     name: "gpt-5-mini".to_string(),
                     api_key_ref: Some("secret/openai".to_string()),
+                    resolved_api_key: None,
                 },
-                tools: vec![ToolConfig {
+                tools: vec![ToolSpec {
                     name: "web_search".to_string(),
-                    allowed: true,
+                    description: "Search the web.".to_string(),
+                    parameters: serde_json::json!({
+                        "type": "object",
+                        "properties": {},
+                        "required": []
+                    }),
+                    approval: super::ApprovalMode::Auto,
                 }],
                 channels: vec![ChannelConfig {
                     channel: "telegram".to_string(),
@@ -1036,6 +2817,18 @@ mod tests {
                     allowlist: vec!["team".to_string()],
                     sandboxed: true,
                 },
+                observability: super::ObservabilityConfig {
+                    otlp_endpoint: Some("https://otel.example.com".to_string()),
+                    protocol: super::ObservabilityProtocol::Grpc,
+                    service_name: "alpha".to_string(),
+                    sample_ratio: 0.5,
+                    traces: true,
+                    metrics: true,
+                    logs: false,
+                    headers: [("Authorization".to_string(), "Bearer secret".to_string())]
+                        .into_iter()
+                        .collect(),
+                },
                 extras: Map::new(),
             },
         }
@@ -1101,7 +2894,10 @@ mod tests {
         let mut vault = SecretVault::new(b"test-encryption-key");
         vault.put("secret/openai", "sk-abc123");
 
-        assert_eq!(vault.get("secret/openai").as_deref(), Some("sk-abc123"));
+        assert_eq!(
+            vault.get("secret/openai").as_ref().map(Secret::expose_secret),
+            Some("sk-abc123")
+        );
         assert_eq!(vault.list_names(), vec!["secret/openai".to_string()]);
     }
 
@@ -1121,9 +2917,64 @@ mod tests {
         let config = sample_config(ClawRuntime::OpenClaw);
         let resolved = vault.resolve_config(&config).unwrap();
         assert_eq!(
-            resolved.agent.model.api_key_ref.as_deref(),
+            resolved
+                .agent
+                .model
+                .resolved_api_key
+                .as_ref()
+                .map(Secret::expose_secret),
             Some("sk-real-key-123")
         );
+        // `api_key_ref` still names the vault entry, not the plaintext.
+        assert_eq!(
+            resolved.agent.model.api_key_ref.as_deref(),
+            Some("secret/openai")
+        );
+    }
+
+    #[test]
+    fn secret_vault_get_checked_distinguishes_not_found_from_auth_failure() {
+        let mut vault = SecretVault::new(b"key");
+        vault.put("secret/openai", "sk-abc123");
+
+        assert_eq!(
+            vault.get_checked("secret/missing"),
+            Err(VaultError::NotFound("secret/missing".to_string()))
+        );
+
+        // Tamper with the sealed ciphertext so the GCM tag no longer verifies.
+        if let Some(byte) = vault
+            .backend
+            .entries
+            .get_mut("secret/openai")
+            .unwrap()
+            .last_mut()
+        {
+            *byte ^= 0xff;
+        }
+        assert_eq!(
+            vault.get_checked("secret/openai"),
+            Err(VaultError::AuthenticationFailed("secret/openai".to_string()))
+        );
+        assert!(vault.get("secret/openai").is_none());
+    }
+
+    #[test]
+    fn secret_vault_load_reopens_with_same_salt() {
+        let mut vault = SecretVault::new(b"passphrase");
+        vault.put("secret/openai", "sk-abc123");
+
+        let reopened = SecretVault::load(b"passphrase", vault.salt(), vault.store().clone());
+        assert_eq!(
+            reopened
+                .get("secret/openai")
+                .as_ref()
+                .map(Secret::expose_secret),
+            Some("sk-abc123")
+        );
+
+        let wrong_passphrase = SecretVault::load(b"wrong", vault.salt(), vault.store().clone());
+        assert!(wrong_passphrase.get("secret/openai").is_none());
     }
 
     #[test]
@@ -1188,6 +3039,115 @@ providers:
         );
     }
 
+    #[test]
+    fn interpolate_embeds_braced_var_inline() {
+        std::env::set_var("REGION", "eu-west-1");
+        let mut yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+providers:
+  openai:
+    base_url: "https://${REGION}.api.example.com/v1"
+"#,
+        )
+        .expect("yaml should parse");
+        yaml.resolve_env_vars().expect("env vars should resolve");
+        assert_eq!(
+            yaml.providers["openai"].base_url.as_deref(),
+            Some("https://eu-west-1.api.example.com/v1")
+        );
+    }
+
+    #[test]
+    fn interpolate_applies_default_when_var_unset() {
+        std::env::remove_var("CLAWDEN_TEST_UNSET_REGION");
+        let mut yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+providers:
+  openai:
+    base_url: "https://${CLAWDEN_TEST_UNSET_REGION:-us-east-1}.api.example.com/v1"
+"#,
+        )
+        .expect("yaml should parse");
+        yaml.resolve_env_vars().expect("env vars should resolve");
+        assert_eq!(
+            yaml.providers["openai"].base_url.as_deref(),
+            Some("https://us-east-1.api.example.com/v1")
+        );
+    }
+
+    #[test]
+    fn interpolate_required_marker_uses_custom_message() {
+        std::env::remove_var("CLAWDEN_TEST_REQUIRED_TOKEN");
+        let mut yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+channels:
+  telegram:
+    token: "${CLAWDEN_TEST_REQUIRED_TOKEN:?set CLAWDEN_TEST_REQUIRED_TOKEN before deploying}"
+"#,
+        )
+        .expect("yaml should parse");
+        let errors = yaml.resolve_env_vars().expect_err("required var missing");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("set CLAWDEN_TEST_REQUIRED_TOKEN before deploying")));
+    }
+
+    #[test]
+    fn interpolate_bare_dollar_var_still_resolves_whole_value() {
+        std::env::set_var("CLAWDEN_TEST_BARE_TOKEN", "bare-value");
+        let mut yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+channels:
+  telegram:
+    token: $CLAWDEN_TEST_BARE_TOKEN
+"#,
+        )
+        .expect("yaml should parse");
+        yaml.resolve_env_vars().expect("env vars should resolve");
+        assert_eq!(
+            yaml.channels["telegram"].token.as_deref(),
+            Some("bare-value")
+        );
+    }
+
+    #[test]
+    fn interpolate_resolves_channel_extra_and_runtime_config_maps() {
+        std::env::set_var("CLAWDEN_TEST_WEBHOOK_HOST", "hooks.example.com");
+        let mut yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+channels:
+  telegram:
+    webhook_path: "https://${CLAWDEN_TEST_WEBHOOK_HOST}/telegram"
+runtimes:
+  - name: zeroclaw
+    config:
+      endpoint: "https://${CLAWDEN_TEST_WEBHOOK_HOST}/zeroclaw"
+"#,
+        )
+        .expect("yaml should parse");
+        yaml.resolve_env_vars().expect("env vars should resolve");
+
+        assert_eq!(
+            yaml.channels["telegram"]
+                .extra
+                .get("webhook_path")
+                .and_then(Value::as_str),
+            Some("https://hooks.example.com/telegram")
+        );
+        assert_eq!(
+            yaml.runtimes[0]
+                .config
+                .get("endpoint")
+                .and_then(Value::as_str),
+            Some("https://hooks.example.com/zeroclaw")
+        );
+    }
+
     #[test]
     fn custom_provider_requires_base_url() {
         let mut parsed = ClawDenYaml::parse_yaml("runtime: zeroclaw").expect("yaml should parse");
@@ -1198,6 +3158,7 @@ providers:
                 api_key: None,
                 base_url: None,
                 org_id: None,
+                capabilities: None,
                 extra: std::collections::HashMap::new(),
             },
         );
@@ -1223,10 +3184,393 @@ runtimes:
 "#;
         let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
         let errors = parsed.validate().expect_err("validation should fail");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("references provider 'not-a-real-provider'")));
+    }
+
+    #[test]
+    fn merge_prefers_later_layer_scalars_and_unions_tools() {
+        let mut base = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+model: gpt-4o
+tools: [git, http]
+"#,
+        )
+        .expect("base should parse");
+        let override_layer = ClawDenYaml::parse_yaml(
+            r#"
+model: gpt-5-mini
+tools: [http, python]
+"#,
+        )
+        .expect("override should parse");
+
+        base.merge(override_layer);
+
+        assert_eq!(base.runtime.as_deref(), Some("zeroclaw"));
+        assert_eq!(base.model.as_deref(), Some("gpt-5-mini"));
+        assert_eq!(base.tools.names(), vec!["git", "http", "python"]);
+    }
+
+    #[test]
+    fn merge_recurses_into_matching_channel_and_shallow_merges_extra() {
+        let mut base = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+channels:
+  support-tg:
+    type: telegram
+    token: $BASE_TOKEN
+    webhook_path: /base
+"#,
+        )
+        .expect("base should parse");
+        let prod = ClawDenYaml::parse_yaml(
+            r#"
+channels:
+  support-tg:
+    token: $PROD_TOKEN
+"#,
+        )
+        .expect("prod layer should parse");
+
+        base.merge(prod);
+
+        let merged = base.channels.get("support-tg").expect("channel survives");
+        assert_eq!(merged.channel_type.as_deref(), Some("telegram"));
+        assert_eq!(merged.token.as_deref(), Some("$PROD_TOKEN"));
+        assert_eq!(
+            merged.extra.get("webhook_path").and_then(Value::as_str),
+            Some("/base")
+        );
+    }
+
+    #[test]
+    fn load_with_profile_merges_named_profile_over_base() {
+        let dir = std::env::temp_dir().join(format!(
+            "clawden-load-with-profile-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("temp dir should be created");
+        let path = dir.join("clawden.yaml");
+        std::fs::write(
+            &path,
+            r#"
+runtime: zeroclaw
+model: gpt-4o
+profiles:
+  prod:
+    model: gpt-5-mini
+"#,
+        )
+        .expect("base should be written");
+
+        let base = ClawDenYaml::load_with_profile(&path, "prod").expect("base should load");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(base.model.as_deref(), Some("gpt-5-mini"));
+        assert!(base.profiles.is_empty());
+    }
+
+    #[test]
+    fn config_override_sets_provider_model_runtime_and_tool_kv() {
+        let mut yaml = ClawDenYaml::parse_yaml("runtime: zeroclaw").expect("yaml should parse");
+        let override_flags = ConfigOverride {
+            provider: Some("anthropic".to_string()),
+            model: Some("claude-opus".to_string()),
+            runtime: Some("openclaw".to_string()),
+            tools: vec![ConfigOverride::parse_tool_kv("timeout=30s").expect("parses k=v")],
+        };
+
+        override_flags.apply(&mut yaml);
+
+        assert_eq!(yaml.runtime.as_deref(), Some("openclaw"));
+        assert_eq!(yaml.model.as_deref(), Some("claude-opus"));
+        assert_eq!(
+            yaml.config.get("timeout").and_then(Value::as_str),
+            Some("30s")
+        );
+    }
+
+    #[test]
+    fn config_override_parse_tool_kv_rejects_missing_equals() {
+        assert_eq!(ConfigOverride::parse_tool_kv("no-equals-sign"), None);
+    }
+
+    #[test]
+    fn known_providers_default_to_tool_support() {
+        assert!(LlmProvider::OpenAi.capabilities().supports_tools);
+        assert!(LlmProvider::Anthropic.capabilities().supports_tools);
+        assert!(LlmProvider::Mistral.capabilities().supports_tools);
+        assert!(LlmProvider::Groq.capabilities().supports_tools);
+        assert!(LlmProvider::OpenRouter.capabilities().supports_tools);
+    }
+
+    #[test]
+    fn ollama_and_custom_default_to_unknown_tool_support() {
+        assert!(!LlmProvider::Ollama.capabilities().supports_tools);
         assert!(
-            errors
-                .iter()
-                .any(|e| e.contains("references provider 'not-a-real-provider'"))
+            !LlmProvider::Custom("lm-studio".to_string())
+                .capabilities()
+                .supports_tools
+        );
+    }
+
+    #[test]
+    fn runtime_tools_with_non_tool_capable_provider_fails_validation() {
+        let yaml = r#"
+runtime: zeroclaw
+runtimes:
+  - name: zeroclaw
+    provider: ollama
+    tools: [git]
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        let errors = parsed.validate().expect_err("validation should fail");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("does not support tool calling")));
+    }
+
+    #[test]
+    fn capabilities_override_unlocks_tool_support_for_custom_provider() {
+        let yaml = r#"
+runtime: zeroclaw
+providers:
+  local:
+    type: custom
+    base_url: http://localhost:1234/v1
+    capabilities:
+      supports_tools: true
+runtimes:
+  - name: zeroclaw
+    provider: local
+    tools: [git]
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        assert!(parsed.validate().is_ok());
+    }
+
+    #[test]
+    fn capabilities_override_merges_field_wise() {
+        let mut capabilities = super::ProviderCapabilities::default();
+        let overrides = ProviderCapabilitiesOverride {
+            supports_tools: Some(true),
+            supports_streaming: None,
+            supports_vision: None,
+            max_tools: Some(4),
+        };
+        overrides.apply(&mut capabilities);
+
+        assert!(capabilities.supports_tools);
+        assert!(capabilities.supports_streaming);
+        assert_eq!(capabilities.max_tools, Some(4));
+    }
+
+    #[test]
+    fn tools_shorthand_list_resolves_to_registry_defaults() {
+        let yaml = ClawDenYaml::parse_yaml("runtime: zeroclaw\ntools: [git, http]")
+            .expect("yaml should parse");
+        let mut specs = yaml.tools.resolve_specs().expect("known tools resolve");
+        specs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].name, "git");
+        assert_eq!(specs[0].approval, super::ApprovalMode::Auto);
+        assert!(!specs[0].description.is_empty());
+    }
+
+    #[test]
+    fn tools_full_form_overrides_approval_and_description() {
+        let yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+tools:
+  git:
+    approval: prompt
+    description: "Custom git access"
+"#,
+        )
+        .expect("yaml should parse");
+        let specs = yaml.tools.resolve_specs().expect("known tool resolves");
+
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].approval, super::ApprovalMode::Prompt);
+        assert_eq!(specs[0].description, "Custom git access");
+    }
+
+    #[test]
+    fn unknown_tool_name_fails_validation() {
+        let yaml = ClawDenYaml::parse_yaml("runtime: zeroclaw\ntools: [not-a-real-tool]")
+            .expect("yaml should parse");
+        let errors = yaml.validate().expect_err("validation should fail");
+        assert!(errors.iter().any(|e| e.contains("Unknown tool")));
+    }
+
+    #[test]
+    fn malformed_tool_parameters_fail_validation() {
+        let yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+tools:
+  git:
+    parameters:
+      type: string
+"#,
+        )
+        .expect("yaml should parse");
+        let errors = yaml.validate().expect_err("validation should fail");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("must have \"type\": \"object\"")));
+    }
+
+    #[test]
+    fn tools_merge_unions_list_form() {
+        let mut base =
+            ClawDenYaml::parse_yaml("runtime: zeroclaw\ntools: [git]").expect("base parses");
+        let other =
+            ClawDenYaml::parse_yaml("runtime: zeroclaw\ntools: [git, http]").expect("other parses");
+
+        base.merge(other);
+
+        assert_eq!(base.tools.names().len(), 2);
+    }
+
+    #[test]
+    fn tools_merge_degrades_list_to_map_when_other_is_full_form() {
+        let mut base =
+            ClawDenYaml::parse_yaml("runtime: zeroclaw\ntools: [git]").expect("base parses");
+        let other = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+tools:
+  git:
+    approval: deny
+"#,
+        )
+        .expect("other parses");
+
+        base.merge(other);
+
+        let specs = base.tools.resolve_specs().expect("tool resolves");
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].approval, super::ApprovalMode::Deny);
+    }
+
+    #[test]
+    fn observability_rejects_out_of_range_sample_ratio() {
+        let yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+observability:
+  otlp_endpoint: http://collector:4317
+  traces: true
+  sample_ratio: 1.5
+"#,
+        )
+        .expect("yaml should parse");
+        let errors = yaml.validate().expect_err("validation should fail");
+        assert!(errors.iter().any(|e| e.contains("sample_ratio")));
+    }
+
+    #[test]
+    fn observability_requires_endpoint_when_exporter_enabled() {
+        let yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+observability:
+  metrics: true
+"#,
+        )
+        .expect("yaml should parse");
+        let errors = yaml.validate().expect_err("validation should fail");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("otlp_endpoint' is not set")));
+    }
+
+    #[test]
+    fn observability_endpoint_resolves_from_env() {
+        std::env::set_var("OTEL_COLLECTOR_URL", "http://collector:4317");
+        let mut yaml = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+observability:
+  otlp_endpoint: $OTEL_COLLECTOR_URL
+  traces: true
+"#,
+        )
+        .expect("yaml should parse");
+        yaml.resolve_env_vars().expect("env vars should resolve");
+        assert_eq!(
+            yaml.observability.otlp_endpoint.as_deref(),
+            Some("http://collector:4317")
+        );
+    }
+
+    #[test]
+    fn observability_merge_prefers_later_layer_and_unions_headers() {
+        let mut base = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+observability:
+  otlp_endpoint: http://base:4317
+  traces: true
+  headers:
+    X-Base: base-value
+"#,
+        )
+        .expect("base should parse");
+        let prod = ClawDenYaml::parse_yaml(
+            r#"
+runtime: zeroclaw
+observability:
+  otlp_endpoint: http://prod:4317
+  headers:
+    X-Prod: prod-value
+"#,
+        )
+        .expect("prod should parse");
+
+        base.merge(prod);
+
+        assert_eq!(
+            base.observability.otlp_endpoint.as_deref(),
+            Some("http://prod:4317")
+        );
+        assert!(base.observability.traces.unwrap_or(false));
+        assert_eq!(base.observability.headers.len(), 2);
+    }
+
+    #[test]
+    fn safe_json_redacts_observability_headers() {
+        let config = sample_config(ClawRuntime::OpenClaw);
+        let safe = config.to_safe_json();
+        let header = safe["agent"]["observability"]["headers"]["Authorization"]
+            .as_str()
+            .unwrap();
+        assert_eq!(header, "<redacted>");
+    }
+
+    #[test]
+    fn openclaw_to_runtime_config_carries_observability_settings() {
+        let translator = OpenClawConfigTranslator;
+        let canonical = sample_config(ClawRuntime::OpenClaw);
+        let native = translator
+            .to_runtime_config(&canonical)
+            .expect("openclaw to native should succeed");
+        let decoded = translator
+            .from_runtime_config(&native)
+            .expect("openclaw from native should succeed");
+
+        assert_eq!(
+            decoded.agent.observability.otlp_endpoint.as_deref(),
+            Some("https://otel.example.com")
         );
+        assert_eq!(decoded.agent.observability.sample_ratio, 0.5);
+        assert!(decoded.agent.observability.traces);
     }
 }
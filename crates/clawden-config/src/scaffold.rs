@@ -0,0 +1,291 @@
+//! Feature-toggle scaffolding for `clawden init` (spec 017 extension).
+//!
+//! Generates and incrementally updates a `clawden.yaml` plus per-channel
+//! credential stubs from a set of on/off feature flags. Re-running `init`
+//! against an existing project is idempotent: enabling a feature adds its
+//! config block if it's missing, disabling one removes it, and anything
+//! the user already has in unrelated blocks is left untouched.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{ChannelInstanceYaml, ClawDenYaml, RuntimeEntryYaml, ToolsYaml, KNOWN_CHANNEL_TYPES};
+
+/// Runtime slugs the scaffolder recognizes for the `runtimes:` list.
+const KNOWN_RUNTIMES: &[&str] = &["zeroclaw", "openclaw", "picoclaw", "nanoclaw"];
+
+/// A single `--<feature>=on|off` flag parsed from the CLI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureToggle {
+    pub name: String,
+    pub enabled: bool,
+}
+
+impl FeatureToggle {
+    /// Parse a CLI argument of the form `--name=on` / `--name=off`.
+    /// Also accepts `true`/`false` and `1`/`0` as synonyms for `on`/`off`.
+    pub fn parse(arg: &str) -> Option<Self> {
+        let rest = arg.strip_prefix("--")?;
+        let (name, value) = rest.split_once('=')?;
+        let enabled = match value.to_ascii_lowercase().as_str() {
+            "on" | "true" | "1" | "yes" => true,
+            "off" | "false" | "0" | "no" => false,
+            _ => return None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            enabled,
+        })
+    }
+}
+
+/// One line of the diff that `--dry-run` prints instead of writing to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScaffoldChange {
+    AddRuntime(String),
+    RemoveRuntime(String),
+    AddChannel(String),
+    RemoveChannel(String),
+    AddInfra(String),
+    RemoveInfra(String),
+    Unchanged(String),
+}
+
+impl std::fmt::Display for ScaffoldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddRuntime(n) => write!(f, "+ runtimes: {n}"),
+            Self::RemoveRuntime(n) => write!(f, "- runtimes: {n}"),
+            Self::AddChannel(n) => write!(f, "+ channels: {n}"),
+            Self::RemoveChannel(n) => write!(f, "- channels: {n}"),
+            Self::AddInfra(n) => write!(f, "+ infra: {n}"),
+            Self::RemoveInfra(n) => write!(f, "- infra: {n}"),
+            Self::Unchanged(n) => write!(f, "  {n} (already in requested state)"),
+        }
+    }
+}
+
+/// Applies `toggles` to `yaml` in place and returns the changes that were made.
+///
+/// A toggle matching a known runtime slug grows/shrinks the `runtimes:`
+/// list; one matching a known channel type grows/shrinks `channels:` (wired
+/// to an `$ENV_VAR` stub so secrets never land in the file); anything else
+/// is tracked as a generic `infra:` flag (e.g. `redis`).
+pub fn apply_toggles(yaml: &mut ClawDenYaml, toggles: &[FeatureToggle]) -> Vec<ScaffoldChange> {
+    let mut changes = Vec::new();
+    for toggle in toggles {
+        if KNOWN_RUNTIMES.contains(&toggle.name.as_str()) {
+            changes.push(apply_runtime_toggle(yaml, toggle));
+        } else if KNOWN_CHANNEL_TYPES.contains(&toggle.name.as_str()) {
+            changes.push(apply_channel_toggle(yaml, toggle));
+        } else {
+            changes.push(apply_infra_toggle(yaml, toggle));
+        }
+    }
+    changes
+}
+
+fn apply_runtime_toggle(yaml: &mut ClawDenYaml, toggle: &FeatureToggle) -> ScaffoldChange {
+    let present = yaml.runtimes.iter().any(|rt| rt.name == toggle.name);
+    match (toggle.enabled, present) {
+        (true, false) => {
+            yaml.runtimes.push(RuntimeEntryYaml {
+                name: toggle.name.clone(),
+                channels: Vec::new(),
+                tools: ToolsYaml::default(),
+                provider: None,
+                model: None,
+                config: HashMap::new(),
+            });
+            ScaffoldChange::AddRuntime(toggle.name.clone())
+        }
+        (false, true) => {
+            yaml.runtimes.retain(|rt| rt.name != toggle.name);
+            ScaffoldChange::RemoveRuntime(toggle.name.clone())
+        }
+        _ => ScaffoldChange::Unchanged(toggle.name.clone()),
+    }
+}
+
+fn apply_channel_toggle(yaml: &mut ClawDenYaml, toggle: &FeatureToggle) -> ScaffoldChange {
+    let present = yaml.channels.contains_key(&toggle.name);
+    match (toggle.enabled, present) {
+        (true, false) => {
+            let env_var = format!("{}_BOT_TOKEN", toggle.name.to_ascii_uppercase());
+            yaml.channels.insert(
+                toggle.name.clone(),
+                ChannelInstanceYaml {
+                    channel_type: Some(toggle.name.clone()),
+                    token: Some(format!("${env_var}")),
+                    bot_token: None,
+                    app_token: None,
+                    phone: None,
+                    guild: None,
+                    allowed_users: Vec::new(),
+                    allowed_roles: Vec::new(),
+                    allowed_channels: Vec::new(),
+                    group_mode: None,
+                    extra: HashMap::new(),
+                },
+            );
+            ScaffoldChange::AddChannel(toggle.name.clone())
+        }
+        (false, true) => {
+            yaml.channels.remove(&toggle.name);
+            ScaffoldChange::RemoveChannel(toggle.name.clone())
+        }
+        _ => ScaffoldChange::Unchanged(toggle.name.clone()),
+    }
+}
+
+fn apply_infra_toggle(yaml: &mut ClawDenYaml, toggle: &FeatureToggle) -> ScaffoldChange {
+    let was_enabled = yaml.infra.get(&toggle.name).copied().unwrap_or(false);
+    if was_enabled == toggle.enabled && yaml.infra.contains_key(&toggle.name) {
+        return ScaffoldChange::Unchanged(toggle.name.clone());
+    }
+    yaml.infra.insert(toggle.name.clone(), toggle.enabled);
+    if toggle.enabled {
+        ScaffoldChange::AddInfra(toggle.name.clone())
+    } else {
+        ScaffoldChange::RemoveInfra(toggle.name.clone())
+    }
+}
+
+/// Loads the `clawden.yaml` in `project_dir` (or starts from an empty config
+/// if it doesn't exist yet), applies `toggles`, and either writes the result
+/// (plus `.env.example` stubs for newly enabled channels) or, if `dry_run`
+/// is set, leaves the filesystem untouched and only returns the diff.
+pub fn scaffold_project(
+    project_dir: &Path,
+    toggles: &[FeatureToggle],
+    dry_run: bool,
+) -> Result<Vec<ScaffoldChange>, String> {
+    let yaml_path = project_dir.join("clawden.yaml");
+    let mut yaml = if yaml_path.exists() {
+        ClawDenYaml::from_file(&yaml_path)?
+    } else {
+        ClawDenYaml::default()
+    };
+
+    let changes = apply_toggles(&mut yaml, toggles);
+    if dry_run {
+        return Ok(changes);
+    }
+
+    fs::create_dir_all(project_dir)
+        .map_err(|e| format!("failed to create {}: {e}", project_dir.display()))?;
+
+    let rendered =
+        serde_yaml::to_string(&yaml).map_err(|e| format!("failed to render clawden.yaml: {e}"))?;
+    fs::write(&yaml_path, rendered)
+        .map_err(|e| format!("failed to write {}: {e}", yaml_path.display()))?;
+
+    write_env_stub(project_dir, &changes)?;
+    Ok(changes)
+}
+
+/// Appends `$ENV_VAR=` placeholder lines to `.env.example` for newly added
+/// channels, without touching lines a user may have already filled in.
+fn write_env_stub(project_dir: &Path, changes: &[ScaffoldChange]) -> Result<(), String> {
+    let added: Vec<&String> = changes
+        .iter()
+        .filter_map(|c| match c {
+            ScaffoldChange::AddChannel(name) => Some(name),
+            _ => None,
+        })
+        .collect();
+    if added.is_empty() {
+        return Ok(());
+    }
+
+    let env_path: PathBuf = project_dir.join(".env.example");
+    let existing = fs::read_to_string(&env_path).unwrap_or_default();
+    let mut lines: Vec<String> = existing.lines().map(str::to_string).collect();
+
+    for name in added {
+        let key = format!("{}_BOT_TOKEN", name.to_ascii_uppercase());
+        if !lines
+            .iter()
+            .any(|line| line.starts_with(&format!("{key}=")))
+        {
+            lines.push(format!("{key}="));
+        }
+    }
+
+    fs::write(&env_path, format!("{}\n", lines.join("\n")))
+        .map_err(|e| format!("failed to write {}: {e}", env_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_on_and_off_values() {
+        assert_eq!(
+            FeatureToggle::parse("--redis=on"),
+            Some(FeatureToggle {
+                name: "redis".to_string(),
+                enabled: true
+            })
+        );
+        assert_eq!(
+            FeatureToggle::parse("--telegram=off"),
+            Some(FeatureToggle {
+                name: "telegram".to_string(),
+                enabled: false
+            })
+        );
+        assert_eq!(FeatureToggle::parse("--not-a-toggle"), None);
+    }
+
+    #[test]
+    fn enabling_a_runtime_twice_is_idempotent() {
+        let mut yaml = ClawDenYaml::default();
+        let toggle = FeatureToggle {
+            name: "zeroclaw".to_string(),
+            enabled: true,
+        };
+        apply_toggles(&mut yaml, &[toggle.clone()]);
+        let changes = apply_toggles(&mut yaml, &[toggle]);
+        assert_eq!(yaml.runtimes.len(), 1);
+        assert!(matches!(changes[0], ScaffoldChange::Unchanged(_)));
+    }
+
+    #[test]
+    fn disabling_a_channel_removes_its_block() {
+        let mut yaml = ClawDenYaml::default();
+        apply_toggles(
+            &mut yaml,
+            &[FeatureToggle {
+                name: "telegram".to_string(),
+                enabled: true,
+            }],
+        );
+        assert!(yaml.channels.contains_key("telegram"));
+
+        apply_toggles(
+            &mut yaml,
+            &[FeatureToggle {
+                name: "telegram".to_string(),
+                enabled: false,
+            }],
+        );
+        assert!(!yaml.channels.contains_key("telegram"));
+    }
+
+    #[test]
+    fn unknown_toggle_name_is_tracked_as_infra() {
+        let mut yaml = ClawDenYaml::default();
+        let changes = apply_toggles(
+            &mut yaml,
+            &[FeatureToggle {
+                name: "redis".to_string(),
+                enabled: true,
+            }],
+        );
+        assert_eq!(yaml.infra.get("redis"), Some(&true));
+        assert!(matches!(changes[0], ScaffoldChange::AddInfra(_)));
+    }
+}
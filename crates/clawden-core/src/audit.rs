@@ -11,6 +11,11 @@ pub struct AuditEvent {
     pub action: String,
     pub target: String,
     pub timestamp_unix_ms: u64,
+    /// Ties this event to the request (or chain of requests) that produced
+    /// it, e.g. so every step of a deploy shows up together under
+    /// `GET /audit?correlation_id=...`. `None` for events appended without
+    /// one, via [`append_audit`].
+    pub correlation_id: Option<String>,
 }
 
 #[derive(Clone, Default)]
@@ -30,9 +35,167 @@ impl AuditLog {
             .lock()
             .map_or_else(|_| Vec::new(), |guard| guard.clone())
     }
+
+    /// Selects a window of history per `query.selector`, narrowed first by
+    /// `query`'s `actor`/`action`/`target` filters, and returns it ordered by
+    /// `timestamp_unix_ms` alongside a cursor for paging further back.
+    ///
+    /// Filters/windows a clone of the append-only log under the lock, so the
+    /// lock itself is never held for longer than the copy.
+    pub fn query(&self, query: &AuditQuery) -> AuditPage {
+        let events = self
+            .inner
+            .lock()
+            .map_or_else(|_| Vec::new(), |guard| guard.clone());
+
+        let mut filtered: Vec<(usize, AuditEvent)> = events
+            .into_iter()
+            .enumerate()
+            .filter(|(_, event)| {
+                query.actor.as_deref().map_or(true, |actor| actor == event.actor)
+            })
+            .filter(|(_, event)| {
+                query.action.as_deref().map_or(true, |action| action == event.action)
+            })
+            .filter(|(_, event)| {
+                query.target.as_deref().map_or(true, |target| target == event.target)
+            })
+            .filter(|(_, event)| {
+                query
+                    .correlation_id
+                    .as_deref()
+                    .map_or(true, |id| Some(id) == event.correlation_id.as_deref())
+            })
+            .collect();
+        // Stable sort: same-millisecond events keep their append order.
+        filtered.sort_by_key(|(_, event)| event.timestamp_unix_ms);
+
+        let windowed: Vec<(usize, AuditEvent)> = match query.selector {
+            AuditSelector::Latest { limit } => {
+                let start = filtered.len().saturating_sub(limit);
+                filtered[start..].to_vec()
+            }
+            AuditSelector::Before { ts, limit } => {
+                let older: Vec<(usize, AuditEvent)> = filtered
+                    .into_iter()
+                    .filter(|(_, event)| event.timestamp_unix_ms < ts)
+                    .collect();
+                let start = older.len().saturating_sub(limit);
+                older[start..].to_vec()
+            }
+            AuditSelector::After { ts, limit } => filtered
+                .into_iter()
+                .filter(|(_, event)| event.timestamp_unix_ms > ts)
+                .take(limit)
+                .collect(),
+            AuditSelector::Between { start, end, limit } => filtered
+                .into_iter()
+                .filter(|(_, event)| event.timestamp_unix_ms >= start && event.timestamp_unix_ms <= end)
+                .take(limit)
+                .collect(),
+        };
+
+        let next_cursor = windowed.first().map(|(index, event)| AuditCursor {
+            timestamp_unix_ms: event.timestamp_unix_ms,
+            index: *index,
+        });
+
+        AuditPage {
+            events: windowed.into_iter().map(|(_, event)| event).collect(),
+            next_cursor,
+        }
+    }
+}
+
+/// Which slice of history to return. `Before`/`After` are exclusive of the
+/// anchor timestamp; `Between` is inclusive of both bounds. Every variant
+/// honors `limit` by truncating from the anchor side, e.g. `Before` keeps
+/// the `limit` newest events older than `ts`.
+#[derive(Debug, Clone, Copy)]
+pub enum AuditSelector {
+    Latest { limit: usize },
+    Before { ts: u64, limit: usize },
+    After { ts: u64, limit: usize },
+    Between { start: u64, end: u64, limit: usize },
+}
+
+impl Default for AuditSelector {
+    fn default() -> Self {
+        Self::Latest { limit: 50 }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub selector: AuditSelector,
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub correlation_id: Option<String>,
+}
+
+/// A stable position in the log: the anchor event's timestamp plus its
+/// index in the snapshot used to answer the query, to disambiguate
+/// same-millisecond ties when a client anchors its next query on it.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AuditCursor {
+    pub timestamp_unix_ms: u64,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditPage {
+    pub events: Vec<AuditEvent>,
+    pub next_cursor: Option<AuditCursor>,
+}
+
+/// Persistence strategy for the audit trail. `AuditLog` is the default
+/// in-memory implementation; [`SqliteAuditStore`] durably persists the same
+/// history so a restart doesn't erase it. Handlers and the health monitor
+/// are written against this trait rather than a concrete type so `main` can
+/// pick the backend once, at startup, via `CLAWDEN_AUDIT_DB`.
+pub trait AuditStore: Send + Sync {
+    fn append(&self, event: AuditEvent);
+    fn list(&self) -> Vec<AuditEvent>;
+    fn query(&self, query: &AuditQuery) -> AuditPage;
+
+    /// Forces any buffered events to durable storage. Called during graceful
+    /// shutdown so the `server.stop` event (and anything appended just
+    /// before it) survives the process exiting. The default is a no-op,
+    /// which [`AuditLog`]'s in-memory Vec uses (nothing to flush);
+    /// [`SqliteAuditStore`] commits each insert and additionally runs a WAL
+    /// checkpoint here.
+    fn flush(&self) {}
 }
 
-pub fn append_audit(audit: &Arc<AuditLog>, actor: &str, action: &str, target: &str) {
+impl AuditStore for AuditLog {
+    fn append(&self, event: AuditEvent) {
+        AuditLog::append(self, event)
+    }
+
+    fn list(&self) -> Vec<AuditEvent> {
+        AuditLog::list(self)
+    }
+
+    fn query(&self, query: &AuditQuery) -> AuditPage {
+        AuditLog::query(self, query)
+    }
+}
+
+pub fn append_audit(audit: &dyn AuditStore, actor: &str, action: &str, target: &str) {
+    append_audit_correlated(audit, actor, action, target, None);
+}
+
+/// Same as [`append_audit`], but tags the event with `correlation_id` so a
+/// multi-step operation's rows can be pulled back out together later via
+/// `AuditQuery::correlation_id`.
+pub fn append_audit_correlated(
+    audit: &dyn AuditStore,
+    actor: &str,
+    action: &str,
+    target: &str,
+    correlation_id: Option<&str>,
+) {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("system clock before UNIX_EPOCH")
@@ -43,6 +206,7 @@ pub fn append_audit(audit: &Arc<AuditLog>, actor: &str, action: &str, target: &s
         action: action.to_string(),
         target: target.to_string(),
         timestamp_unix_ms: now,
+        correlation_id: correlation_id.map(str::to_string),
     });
 
     // Best-effort file mirroring for cross-process audit visibility.
@@ -69,3 +233,178 @@ fn append_file_audit(
     let line = format!("{timestamp_unix_ms}\t{action}\t{target}\t{actor}\n");
     file.write_all(line.as_bytes())
 }
+
+/// Durable [`AuditStore`] backed by a single SQLite database, selected by
+/// `main` when `CLAWDEN_AUDIT_DB` is set. Holds one open connection for the
+/// life of the process behind a mutex — the same "durable open handle as a
+/// first-class model object" shape `LifecycleManager` uses for its
+/// `AdapterRegistry` — rather than reopening the file per call.
+pub struct SqliteAuditStore {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteAuditStore {
+    /// Opens (creating if needed) the database at `path` and applies the
+    /// `audit_events` table migration idempotently.
+    pub fn open(path: &std::path::Path) -> rusqlite::Result<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS audit_events (
+                timestamp_unix_ms INTEGER NOT NULL,
+                actor TEXT NOT NULL,
+                action TEXT NOT NULL,
+                target TEXT NOT NULL,
+                correlation_id TEXT
+            );
+            CREATE INDEX IF NOT EXISTS audit_events_timestamp_idx
+                ON audit_events (timestamp_unix_ms);",
+        )?;
+        // `correlation_id` was added after this table's first release —
+        // `CREATE TABLE IF NOT EXISTS` above is a no-op against an older
+        // database, so back it in with `ALTER TABLE` for anyone upgrading in
+        // place. SQLite has no `ADD COLUMN IF NOT EXISTS`, so tolerate the
+        // "duplicate column" error a fresh database's `CREATE TABLE` already
+        // satisfied.
+        let _ = conn.execute_batch("ALTER TABLE audit_events ADD COLUMN correlation_id TEXT;");
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+impl AuditStore for SqliteAuditStore {
+    fn append(&self, event: AuditEvent) {
+        if let Ok(conn) = self.conn.lock() {
+            let _ = conn.execute(
+                "INSERT INTO audit_events (timestamp_unix_ms, actor, action, target, correlation_id) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    event.timestamp_unix_ms as i64,
+                    event.actor,
+                    event.action,
+                    event.target,
+                    event.correlation_id
+                ],
+            );
+        }
+    }
+
+    fn list(&self) -> Vec<AuditEvent> {
+        self.query(&AuditQuery {
+            selector: AuditSelector::Latest { limit: usize::MAX },
+            ..Default::default()
+        })
+        .events
+    }
+
+    fn query(&self, query: &AuditQuery) -> AuditPage {
+        let Ok(conn) = self.conn.lock() else {
+            return AuditPage {
+                events: Vec::new(),
+                next_cursor: None,
+            };
+        };
+        sqlite_query(&conn, query)
+    }
+
+    fn flush(&self) {
+        if let Ok(conn) = self.conn.lock() {
+            let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
+        }
+    }
+}
+
+/// Builds and runs the `SELECT` for `query`, narrowed by its equality
+/// filters and windowed by its selector, mirroring `AuditLog::query`'s
+/// semantics but pushed down into SQL instead of filtering an in-memory
+/// clone.
+fn sqlite_query(conn: &rusqlite::Connection, query: &AuditQuery) -> AuditPage {
+    let mut sql = String::from(
+        "SELECT timestamp_unix_ms, actor, action, target, correlation_id FROM audit_events WHERE 1=1",
+    );
+    let mut binds: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(actor) = &query.actor {
+        sql.push_str(" AND actor = ?");
+        binds.push(Box::new(actor.clone()));
+    }
+    if let Some(action) = &query.action {
+        sql.push_str(" AND action = ?");
+        binds.push(Box::new(action.clone()));
+    }
+    if let Some(target) = &query.target {
+        sql.push_str(" AND target = ?");
+        binds.push(Box::new(target.clone()));
+    }
+    if let Some(correlation_id) = &query.correlation_id {
+        sql.push_str(" AND correlation_id = ?");
+        binds.push(Box::new(correlation_id.clone()));
+    }
+
+    let (order_newest_first, limit) = match query.selector {
+        AuditSelector::Latest { limit } => (true, limit),
+        AuditSelector::Before { ts, limit } => {
+            sql.push_str(" AND timestamp_unix_ms < ?");
+            binds.push(Box::new(ts as i64));
+            (true, limit)
+        }
+        AuditSelector::After { ts, limit } => {
+            sql.push_str(" AND timestamp_unix_ms > ?");
+            binds.push(Box::new(ts as i64));
+            (false, limit)
+        }
+        AuditSelector::Between { start, end, limit } => {
+            sql.push_str(" AND timestamp_unix_ms >= ? AND timestamp_unix_ms <= ?");
+            binds.push(Box::new(start as i64));
+            binds.push(Box::new(end as i64));
+            (false, limit)
+        }
+    };
+    sql.push_str(if order_newest_first {
+        " ORDER BY timestamp_unix_ms DESC"
+    } else {
+        " ORDER BY timestamp_unix_ms ASC"
+    });
+    sql.push_str(" LIMIT ?");
+    binds.push(Box::new(limit.min(i64::MAX as usize) as i64));
+
+    let Ok(mut stmt) = conn.prepare(&sql) else {
+        return AuditPage {
+            events: Vec::new(),
+            next_cursor: None,
+        };
+    };
+    let params: Vec<&dyn rusqlite::ToSql> = binds.iter().map(|bind| bind.as_ref()).collect();
+    let rows = stmt.query_map(params.as_slice(), |row| {
+        Ok(AuditEvent {
+            timestamp_unix_ms: row.get::<_, i64>(0)? as u64,
+            actor: row.get(1)?,
+            action: row.get(2)?,
+            target: row.get(3)?,
+            correlation_id: row.get(4)?,
+        })
+    });
+
+    let Ok(rows) = rows else {
+        return AuditPage {
+            events: Vec::new(),
+            next_cursor: None,
+        };
+    };
+
+    // `Latest`/`Before` query newest-first so `LIMIT` keeps the most recent
+    // rows; re-sort ascending so the page matches `AuditLog::query`'s
+    // chronological ordering contract regardless of selector.
+    let mut events: Vec<AuditEvent> = rows.filter_map(Result::ok).collect();
+    events.sort_by_key(|event| event.timestamp_unix_ms);
+
+    let next_cursor = events.first().map(|event| AuditCursor {
+        timestamp_unix_ms: event.timestamp_unix_ms,
+        index: 0,
+    });
+
+    AuditPage {
+        events,
+        next_cursor,
+    }
+}
@@ -75,6 +75,23 @@ impl ChannelStore {
         self.configs.remove(instance_name).is_some()
     }
 
+    /// Reinserts a `ChannelInstanceConfig` loaded from a persistence layer
+    /// on boot, bypassing `upsert_config`'s request validation since the
+    /// config was already valid when it was first saved.
+    pub fn restore_config(&mut self, config: ChannelInstanceConfig) {
+        self.configs.insert(config.instance_name.clone(), config);
+    }
+
+    /// Reinserts a `ChannelBinding` loaded from a persistence layer on boot,
+    /// recomputing the `(channel_type, bot_token_hash)` key `bind` would
+    /// have used rather than re-deriving it from a raw token we no longer
+    /// have.
+    pub fn restore_binding(&mut self, binding: ChannelBinding) {
+        let key = (binding.channel_type.to_string(), binding.bot_token_hash.clone());
+        self.bindings.insert(key, binding);
+        self.next_binding_id += 1;
+    }
+
     pub fn list_configs_by_type(&self, channel_type: &ChannelType) -> Vec<&ChannelInstanceConfig> {
         self.configs
             .values()
@@ -224,6 +241,20 @@ impl ChannelStore {
             .unwrap_or(ChannelConnectionStatus::Disconnected)
     }
 
+    /// Records the connection status observed for an (agent, channel) pair.
+    /// Until the background monitor started calling this, `connection_status`
+    /// was only ever read, so every cell in [`Self::build_matrix`] reported
+    /// `Disconnected` regardless of reality.
+    pub fn set_connection_status(
+        &mut self,
+        agent_id: &str,
+        channel_name: &str,
+        status: ChannelConnectionStatus,
+    ) {
+        self.connection_status
+            .insert((agent_id.to_string(), channel_name.to_string()), status);
+    }
+
     pub fn build_matrix(&self, agents: &[(String, String)]) -> Vec<MatrixRow> {
         let mut rows = Vec::new();
         for config in self.configs.values() {
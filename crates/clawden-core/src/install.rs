@@ -1,5 +1,9 @@
 use anyhow::{anyhow, bail, Context, Result};
-use serde::Serialize;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -10,6 +14,199 @@ pub struct InstalledRuntime {
     pub runtime: String,
     pub version: String,
     pub executable: PathBuf,
+    /// Subresource-Integrity string (`sha256-<base64>`) recorded in
+    /// `runtimes.lock` for this artifact, or `None` for install paths that
+    /// don't go through `download_to_cache` (`openclaw`'s `npm install`,
+    /// `nanoclaw`'s `git clone`).
+    pub integrity: Option<String>,
+    /// How this runtime was acquired; see [`InstallStrategy`].
+    pub strategy: InstallStrategy,
+}
+
+/// How a runtime's executable is acquired, resolved per-install from
+/// `CLAWDEN_INSTALL_STRATEGY` (and an optional `CLAWDEN_<RUNTIME>_INSTALL_STRATEGY`
+/// override) the way ORT's build selects between downloading a release and
+/// compiling locally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStrategy {
+    /// Download a prebuilt release artifact (the default).
+    Prebuilt,
+    /// Clone the runtime's source and build it locally.
+    FromSource,
+    /// Skip acquisition entirely and resolve an existing binary on `PATH`.
+    System,
+}
+
+impl InstallStrategy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Prebuilt => "prebuilt",
+            Self::FromSource => "from_source",
+            Self::System => "system",
+        }
+    }
+
+    fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "prebuilt" => Some(Self::Prebuilt),
+            "from_source" | "from-source" | "source" => Some(Self::FromSource),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+}
+
+impl Default for InstallStrategy {
+    fn default() -> Self {
+        Self::Prebuilt
+    }
+}
+
+/// Resolves the install strategy for `runtime`: a `CLAWDEN_<RUNTIME>_INSTALL_STRATEGY`
+/// override takes priority over the blanket `CLAWDEN_INSTALL_STRATEGY`, which
+/// in turn falls back to [`InstallStrategy::Prebuilt`].
+fn resolve_install_strategy(runtime: &str) -> InstallStrategy {
+    let per_runtime_var = format!("CLAWDEN_{}_INSTALL_STRATEGY", runtime.to_ascii_uppercase());
+    if let Some(strategy) = std::env::var(&per_runtime_var)
+        .ok()
+        .and_then(|value| InstallStrategy::from_env_value(&value))
+    {
+        return strategy;
+    }
+
+    std::env::var("CLAWDEN_INSTALL_STRATEGY")
+        .ok()
+        .and_then(|value| InstallStrategy::from_env_value(&value))
+        .unwrap_or_default()
+}
+
+/// `(runtime, version, asset_name) -> expected SRI string` pins, persisted
+/// as `runtimes.lock` next to the other `.clawden` state. The first install
+/// of a given key records the observed hash (trust-on-first-use); every
+/// install after that is verified against it, so a corrupted or tampered
+/// re-download is caught instead of silently installed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RuntimeLockFile {
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+}
+
+/// The subset of `package-lock.json` the offline prefetcher cares about:
+/// each dependency's tarball URL and integrity string, whichever of the two
+/// shapes npm wrote it in.
+#[derive(Debug, Default, Deserialize)]
+struct PackageLock {
+    /// lockfileVersion 2/3: flat map of install path (e.g. `node_modules/foo`) to entry.
+    #[serde(default)]
+    packages: BTreeMap<String, PackageLockEntry>,
+    /// lockfileVersion 1 (legacy): recursively nested dependency tree.
+    #[serde(default)]
+    dependencies: BTreeMap<String, PackageLockEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageLockEntry {
+    resolved: Option<String>,
+    integrity: Option<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, PackageLockEntry>,
+}
+
+impl PackageLock {
+    /// Every `(resolved, integrity)` pair that names a fetchable tarball —
+    /// git dependencies and bundled/workspace entries have no `resolved`
+    /// URL and are skipped.
+    fn fetchable_entries(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        for entry in self.packages.values() {
+            push_fetchable(entry, &mut entries);
+        }
+        for entry in self.dependencies.values() {
+            collect_legacy(entry, &mut entries);
+        }
+        entries
+    }
+}
+
+fn push_fetchable(entry: &PackageLockEntry, out: &mut Vec<(String, String)>) {
+    if let (Some(resolved), Some(integrity)) = (&entry.resolved, &entry.integrity) {
+        if resolved.starts_with("http") {
+            out.push((resolved.clone(), integrity.clone()));
+        }
+    }
+}
+
+fn collect_legacy(entry: &PackageLockEntry, out: &mut Vec<(String, String)>) {
+    push_fetchable(entry, out);
+    for dep in entry.dependencies.values() {
+        collect_legacy(dep, out);
+    }
+}
+
+/// Verifies `path` against an npm-style `<algo>-<base64 digest>` integrity
+/// string (`sha512` is what npm actually writes; `sha256` is accepted for
+/// parity with our own [`sha256_sri`] pins).
+fn verify_npm_integrity(path: &Path, integrity: &str) -> Result<()> {
+    let Some((algo, expected_digest)) = integrity.split_once('-') else {
+        bail!("malformed npm lockfile integrity string: {integrity}");
+    };
+
+    let observed_digest = match algo {
+        "sha256" => sha256_sri(path)?
+            .strip_prefix("sha256-")
+            .expect("sha256_sri always returns a sha256- prefixed string")
+            .to_string(),
+        "sha512" => sha512_base64(path)?,
+        other => bail!("unsupported npm lockfile integrity algorithm: {other}"),
+    };
+
+    if observed_digest != expected_digest {
+        bail!("hash mismatch: expected {integrity} got {algo}-{observed_digest}");
+    }
+    Ok(())
+}
+
+fn sha512_base64(path: &Path) -> Result<String> {
+    use sha2::Sha512;
+    use std::io::Read;
+
+    let mut file =
+        fs::File::open(path).with_context(|| format!("reading {} for hashing", path.display()))?;
+    let mut hasher = Sha512::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(BASE64.encode(hasher.finalize()))
+}
+
+fn lock_key(runtime: &str, version: &str, asset_name: &str) -> String {
+    format!("{runtime}/{version}/{asset_name}")
+}
+
+/// Computes the Subresource-Integrity string (`sha256-<base64>`) for the
+/// file at `path`, streaming it through the hasher in fixed-size chunks
+/// rather than reading the whole (potentially large) artifact into memory.
+fn sha256_sri(path: &Path) -> Result<String> {
+    use std::io::Read;
+
+    let mut file =
+        fs::File::open(path).with_context(|| format!("reading {} for hashing", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("sha256-{}", BASE64.encode(hasher.finalize())))
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -24,6 +221,12 @@ pub struct RuntimeInstaller {
     cache_dir: PathBuf,
     logs_dir: PathBuf,
     lock_path: PathBuf,
+    /// Content-addressed cache of `npm` tarballs prefetched from a
+    /// `package-lock.json`, keyed by each entry's integrity string.
+    npm_cache_dir: PathBuf,
+    /// Persistent `pnpm` store dir, reused across installs so pnpm's own
+    /// content-addressable store avoids re-fetching packages it already has.
+    pnpm_store_dir: PathBuf,
 }
 
 impl RuntimeInstaller {
@@ -32,9 +235,13 @@ impl RuntimeInstaller {
         let runtimes_dir = root_dir.join("runtimes");
         let cache_dir = root_dir.join("cache").join("downloads");
         let logs_dir = root_dir.join("logs");
+        let npm_cache_dir = root_dir.join("cache").join("npm");
+        let pnpm_store_dir = root_dir.join("cache").join("pnpm-store");
         fs::create_dir_all(&runtimes_dir)?;
         fs::create_dir_all(&cache_dir)?;
         fs::create_dir_all(&logs_dir)?;
+        fs::create_dir_all(&npm_cache_dir)?;
+        fs::create_dir_all(&pnpm_store_dir)?;
 
         Ok(Self {
             root_dir: root_dir.clone(),
@@ -42,6 +249,8 @@ impl RuntimeInstaller {
             cache_dir,
             logs_dir,
             lock_path: root_dir.join(".install.lock"),
+            npm_cache_dir,
+            pnpm_store_dir,
         })
     }
 
@@ -52,8 +261,14 @@ impl RuntimeInstaller {
     ) -> Result<InstalledRuntime> {
         ensure_runtime_supported(runtime)?;
         let _lock = InstallLock::acquire(&self.lock_path)?;
+        let strategy = resolve_install_strategy(runtime);
+
+        if strategy == InstallStrategy::System {
+            return self.install_system(runtime);
+        }
 
-        let version = requested_version.unwrap_or("latest");
+        let version = self.resolve_version(runtime, requested_version.unwrap_or("latest"))?;
+        let version = version.as_str();
         let runtime_dir = self.runtimes_dir.join(runtime);
         let tmp_dir = runtime_dir.join(format!(".{version}.tmp"));
         let final_dir = runtime_dir.join(version);
@@ -63,11 +278,24 @@ impl RuntimeInstaller {
         }
 
         fs::create_dir_all(&tmp_dir)?;
-        let executable = match runtime {
-            "zeroclaw" => self.install_zeroclaw(version, &tmp_dir)?,
-            "picoclaw" => self.install_picoclaw(version, &tmp_dir)?,
-            "openclaw" => self.install_openclaw(version, &tmp_dir)?,
-            "nanoclaw" => self.install_nanoclaw(version, &tmp_dir)?,
+        let (executable, integrity) = match (runtime, strategy) {
+            ("zeroclaw", InstallStrategy::Prebuilt) => self.install_zeroclaw(version, &tmp_dir)?,
+            ("zeroclaw", InstallStrategy::FromSource) => {
+                self.install_zeroclaw_from_source(version, &tmp_dir)?
+            }
+            ("picoclaw", InstallStrategy::Prebuilt) => self.install_picoclaw(version, &tmp_dir)?,
+            ("picoclaw", InstallStrategy::FromSource) => {
+                self.install_picoclaw_from_source(version, &tmp_dir)?
+            }
+            ("openclaw", InstallStrategy::Prebuilt) => self.install_openclaw(version, &tmp_dir)?,
+            ("openclaw", InstallStrategy::FromSource) => {
+                self.install_openclaw_from_source(version, &tmp_dir)?
+            }
+            ("nanoclaw", InstallStrategy::Prebuilt) => self.install_nanoclaw(version, &tmp_dir)?,
+            ("nanoclaw", InstallStrategy::FromSource) => {
+                self.install_nanoclaw_from_source(version, &tmp_dir)?
+            }
+            (_, InstallStrategy::System) => unreachable!("handled above"),
             _ => unreachable!("validated by ensure_runtime_supported"),
         };
         validate_runtime_artifact(runtime, &executable)?;
@@ -86,12 +314,40 @@ impl RuntimeInstaller {
         std::os::unix::fs::symlink(version, &current_link)
             .with_context(|| format!("updating current symlink for {runtime}"))?;
 
-        self.append_audit("runtime.install", runtime, "ok")?;
+        self.append_audit(
+            "runtime.install",
+            runtime,
+            &format!("ok strategy={} version={version}", strategy.as_str()),
+        )?;
 
         Ok(InstalledRuntime {
             runtime: runtime.to_string(),
             version: version.to_string(),
             executable: final_dir.join(runtime),
+            integrity,
+            strategy,
+        })
+    }
+
+    /// Resolves an existing binary on `PATH` instead of acquiring one,
+    /// recording it as an [`InstalledRuntime`] with no managed files under
+    /// `runtimes_dir`.
+    fn install_system(&self, runtime: &str) -> Result<InstalledRuntime> {
+        let executable = resolve_system_binary(runtime)?;
+        self.append_audit(
+            "runtime.install",
+            runtime,
+            &format!(
+                "ok strategy={} version=system",
+                InstallStrategy::System.as_str()
+            ),
+        )?;
+        Ok(InstalledRuntime {
+            runtime: runtime.to_string(),
+            version: "system".to_string(),
+            executable,
+            integrity: None,
+            strategy: InstallStrategy::System,
         })
     }
 
@@ -114,6 +370,172 @@ impl RuntimeInstaller {
         Ok(())
     }
 
+    /// Repoints `current` at an already-installed `version`, validating it
+    /// exists first. Leaves every other installed version untouched.
+    pub fn set_default(&self, runtime: &str, version: &str) -> Result<()> {
+        ensure_runtime_supported(runtime)?;
+        let _lock = InstallLock::acquire(&self.lock_path)?;
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        let version_dir = runtime_dir.join(version);
+        if !version_dir.exists() {
+            bail!("{runtime} version {version} is not installed");
+        }
+
+        let current_link = runtime_dir.join("current");
+        if current_link.exists() || current_link.is_symlink() {
+            let _ = fs::remove_file(&current_link);
+            let _ = fs::remove_dir_all(&current_link);
+        }
+        std::os::unix::fs::symlink(version, &current_link)
+            .with_context(|| format!("updating current symlink for {runtime}"))?;
+
+        self.append_audit("runtime.set_default", runtime, "ok")?;
+        Ok(())
+    }
+
+    /// Removes one installed version directory, refusing if it is the
+    /// active `current` target (use [`Self::set_default`] to move off it
+    /// first).
+    pub fn uninstall_version(&self, runtime: &str, version: &str) -> Result<()> {
+        ensure_runtime_supported(runtime)?;
+        let _lock = InstallLock::acquire(&self.lock_path)?;
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        let version_dir = runtime_dir.join(version);
+        if !version_dir.exists() {
+            bail!("{runtime} version {version} is not installed");
+        }
+
+        let current_link = runtime_dir.join("current");
+        if let Ok(active) = fs::read_link(&current_link) {
+            if active.to_string_lossy() == version {
+                bail!(
+                    "cannot uninstall {runtime} version {version}: it is the active default (set_default to another version first)"
+                );
+            }
+        }
+
+        fs::remove_dir_all(&version_dir)?;
+        self.append_audit("runtime.uninstall_version", runtime, "ok")?;
+        Ok(())
+    }
+
+    /// Every installed version directory for `runtime`, skipping `.tmp`
+    /// staging dirs and the `current` symlink itself.
+    pub fn installed_versions(&self, runtime: &str) -> Result<Vec<String>> {
+        ensure_runtime_supported(runtime)?;
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        if !runtime_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(&runtime_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == "current" || name.starts_with('.') || !entry.path().is_dir() {
+                continue;
+            }
+            versions.push(name);
+        }
+
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Every published release tag for `runtime`'s upstream repository,
+    /// newest-first, paging through `/repos/{owner}/{repo}/releases` via the
+    /// `Link: rel="next"` header and authenticating with `GITHUB_TOKEN` when
+    /// set to avoid the unauthenticated rate limit.
+    pub fn available_versions(&self, runtime: &str) -> Result<Vec<String>> {
+        ensure_runtime_supported(runtime)?;
+        ensure_command_available("curl", "curl")?;
+        let (owner, repo) = runtime_repo(runtime)?;
+        let token = std::env::var("GITHUB_TOKEN").ok();
+
+        let mut tags = Vec::new();
+        let mut url = format!("https://api.github.com/repos/{owner}/{repo}/releases?per_page=100");
+        loop {
+            let (page, next) = github_api_page(&url, token.as_deref())?;
+            let Some(releases) = page.as_array() else {
+                break;
+            };
+            for release in releases {
+                if let Some(tag) = release.get("tag_name").and_then(|v| v.as_str()) {
+                    tags.push(tag.to_string());
+                }
+            }
+
+            match next {
+                Some(next_url) => url = next_url,
+                None => break,
+            }
+        }
+
+        Ok(tags)
+    }
+
+    /// Resolves a requested version spec to a concrete published tag.
+    /// `"latest"` and exact tags pass straight through to the existing
+    /// per-runtime installer (which already knows how to fetch those);
+    /// a semver range like `^0.3` or `~0.3.1` is matched against
+    /// [`Self::available_versions`] and resolved to the highest match.
+    fn resolve_version(&self, runtime: &str, spec: &str) -> Result<String> {
+        if !spec.starts_with('^') && !spec.starts_with('~') {
+            return Ok(spec.to_string());
+        }
+
+        let available = self.available_versions(runtime)?;
+        resolve_version_spec(spec, &available)
+            .ok_or_else(|| anyhow!("no published version of {runtime} matches spec '{spec}'"))
+    }
+
+    /// Repoints `current` at the previously-active version, found by
+    /// scanning `runtime.install` audit log entries for `runtime`
+    /// newest-first and picking the first one before the currently-active
+    /// version that is still installed on disk. No re-download happens —
+    /// this only works if the prior version's files are still present.
+    pub fn rollback(&self, runtime: &str) -> Result<String> {
+        ensure_runtime_supported(runtime)?;
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        let active = fs::read_link(runtime_dir.join("current"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+
+        let audit_path = self.logs_dir.join("audit.log");
+        let body = fs::read_to_string(&audit_path).unwrap_or_default();
+
+        let mut installs = Vec::new();
+        for line in body.lines() {
+            let mut fields = line.split('\t');
+            let (Some(_ts), Some(action), Some(event_runtime), Some(outcome)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if action != "runtime.install" || event_runtime != runtime {
+                continue;
+            }
+            if let Some(version) = outcome
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("version="))
+            {
+                installs.push(version.to_string());
+            }
+        }
+
+        let previous = installs
+            .into_iter()
+            .rev()
+            .find(|version| {
+                Some(version) != active.as_ref() && runtime_dir.join(version).exists()
+            })
+            .ok_or_else(|| anyhow!("no previous installed version of {runtime} to roll back to"))?;
+
+        self.set_default(runtime, &previous)?;
+        self.append_audit("runtime.rollback", runtime, &format!("ok version={previous}"))?;
+        Ok(previous)
+    }
+
     pub fn list_installed(&self) -> Result<Vec<InstalledRuntime>> {
         let mut rows = Vec::new();
         if !self.runtimes_dir.exists() {
@@ -136,6 +558,12 @@ impl RuntimeInstaller {
                     runtime,
                     version,
                     executable,
+                    // `runtimes.lock` is keyed by asset name, which we don't
+                    // have here — just the already-installed directory.
+                    integrity: None,
+                    // The strategy used isn't persisted per-version; `Prebuilt`
+                    // is the best-effort default for a scan of existing dirs.
+                    strategy: InstallStrategy::Prebuilt,
                 });
             }
         }
@@ -154,7 +582,26 @@ impl RuntimeInstaller {
         executable.exists().then_some(executable)
     }
 
-    fn install_zeroclaw(&self, version: &str, tmp_dir: &Path) -> Result<PathBuf> {
+    /// Writes a wrapper script into `bin_dir` for each supported runtime,
+    /// reusing [`write_launcher`] so putting `bin_dir` on `PATH` makes every
+    /// runtime's `current` executable reachable by its bare name. Each shim
+    /// honors a `CLAWDEN_<RUNTIME>_VERSION` env override so a single
+    /// invocation can target a non-default installed version.
+    pub fn generate_shims(&self, bin_dir: &Path) -> Result<()> {
+        fs::create_dir_all(bin_dir)?;
+        for runtime in ["zeroclaw", "openclaw", "picoclaw", "nanoclaw"] {
+            let runtime_dir = self.runtimes_dir.join(runtime);
+            let env_var = format!("CLAWDEN_{}_VERSION", runtime.to_ascii_uppercase());
+            let body = format!(
+                "VERSION=\"${{{env_var}:-}}\"\nif [ -z \"$VERSION\" ]; then\n  VERSION=\"$(readlink \"{rd}/current\")\"\nfi\nexec \"{rd}/$VERSION/{runtime}\" \"$@\"",
+                rd = runtime_dir.display(),
+            );
+            write_launcher(&bin_dir.join(runtime), runtime, &body)?;
+        }
+        Ok(())
+    }
+
+    fn install_zeroclaw(&self, version: &str, tmp_dir: &Path) -> Result<(PathBuf, Option<String>)> {
         let (os, arch) = host_os_arch()?;
         let release = github_release_assets("zeroclaw-labs", "zeroclaw", version)?;
 
@@ -190,7 +637,7 @@ impl RuntimeInstaller {
             )
         })?;
 
-        let archive_path = self.download_to_cache(
+        let (archive_path, integrity) = self.download_to_cache(
             "zeroclaw",
             release.tag.trim_start_matches('v'),
             &asset.name,
@@ -208,14 +655,64 @@ impl RuntimeInstaller {
         let target = tmp_dir.join("zeroclaw");
         fs::rename(candidate, &target)?;
         make_executable(&target)?;
-        Ok(target)
+        Ok((target, Some(integrity)))
+    }
+
+    fn install_zeroclaw_from_source(
+        &self,
+        version: &str,
+        tmp_dir: &Path,
+    ) -> Result<(PathBuf, Option<String>)> {
+        ensure_command_available("git", "git")?;
+        ensure_command_available("cargo", "rustup")?;
+
+        let ref_name = if version == "latest" {
+            "main".to_string()
+        } else {
+            format!("v{}", normalize_version(version))
+        };
+
+        let repo_dir = tmp_dir.join("zeroclaw-src");
+        run_command(
+            Command::new("git")
+                .arg("clone")
+                .arg("--depth")
+                .arg("1")
+                .arg("--branch")
+                .arg(&ref_name)
+                .arg("https://github.com/zeroclaw-labs/zeroclaw.git")
+                .arg(&repo_dir),
+            "clone zeroclaw repository",
+        )?;
+
+        run_command(
+            command_in_dir("cargo", &repo_dir)
+                .arg("build")
+                .arg("--release"),
+            "build zeroclaw from source",
+        )?;
+
+        let candidate = repo_dir.join("target").join("release").join("zeroclaw");
+        if !candidate.exists() {
+            bail!("cargo build did not produce target/release/zeroclaw");
+        }
+
+        let target = tmp_dir.join("zeroclaw");
+        fs::rename(candidate, &target)?;
+        make_executable(&target)?;
+        Ok((target, None))
     }
 
-    fn install_picoclaw(&self, _version: &str, tmp_dir: &Path) -> Result<PathBuf> {
+    fn install_picoclaw(
+        &self,
+        _version: &str,
+        tmp_dir: &Path,
+    ) -> Result<(PathBuf, Option<String>)> {
         let archive_name = "picoclaw_x64.7z";
         let url =
             "https://github.com/picoclaw-labs/picoclaw/releases/download/picoclaw/picoclaw_x64.7z";
-        let archive_path = self.download_to_cache("picoclaw", "latest", archive_name, url)?;
+        let (archive_path, integrity) =
+            self.download_to_cache("picoclaw", "latest", archive_name, url)?;
 
         ensure_command_available("7z", "p7zip")?;
         run_command(
@@ -235,10 +732,20 @@ impl RuntimeInstaller {
         let target = tmp_dir.join("picoclaw");
         fs::rename(candidate, &target)?;
         make_executable(&target)?;
-        Ok(target)
+        Ok((target, Some(integrity)))
     }
 
-    fn install_openclaw(&self, version: &str, tmp_dir: &Path) -> Result<PathBuf> {
+    fn install_picoclaw_from_source(
+        &self,
+        _version: &str,
+        _tmp_dir: &Path,
+    ) -> Result<(PathBuf, Option<String>)> {
+        bail!(
+            "picoclaw has no from-source build available; use the prebuilt or system install strategy"
+        )
+    }
+
+    fn install_openclaw(&self, version: &str, tmp_dir: &Path) -> Result<(PathBuf, Option<String>)> {
         ensure_command_available("node", "node")?;
         ensure_command_available("npm", "npm")?;
 
@@ -271,10 +778,63 @@ impl RuntimeInstaller {
             "openclaw",
             "\"$SCRIPT_DIR/openclaw-runtime/current/bin/openclaw\" \"$@\"",
         )?;
-        Ok(launcher)
+        Ok((launcher, None))
     }
 
-    fn install_nanoclaw(&self, version: &str, tmp_dir: &Path) -> Result<PathBuf> {
+    fn install_openclaw_from_source(
+        &self,
+        version: &str,
+        tmp_dir: &Path,
+    ) -> Result<(PathBuf, Option<String>)> {
+        ensure_command_available("git", "git")?;
+        ensure_command_available("node", "node")?;
+        ensure_command_available("npm", "npm")?;
+
+        let ref_name = if version == "latest" {
+            "main".to_string()
+        } else {
+            format!("v{}", normalize_version(version))
+        };
+
+        let repo_dir = tmp_dir.join("openclaw-src");
+        run_command(
+            Command::new("git")
+                .arg("clone")
+                .arg("--depth")
+                .arg("1")
+                .arg("--branch")
+                .arg(&ref_name)
+                .arg("https://github.com/openclaw-dev/openclaw.git")
+                .arg(&repo_dir),
+            "clone openclaw repository",
+        )?;
+
+        let npm_cache = self.prefetch_npm_lockfile(&repo_dir)?;
+        let mut install_cmd = command_in_dir("npm", &repo_dir);
+        install_cmd.arg("install");
+        if let Some(cache_dir) = npm_cache {
+            install_cmd.arg("--offline").arg("--cache").arg(cache_dir);
+        }
+        run_command(&mut install_cmd, "install openclaw build dependencies")?;
+        run_command(
+            command_in_dir("npm", &repo_dir).arg("run").arg("build"),
+            "build openclaw from source",
+        )?;
+
+        let runtime_root = tmp_dir.join("openclaw-runtime");
+        fs::create_dir_all(&runtime_root)?;
+        fs::rename(repo_dir, runtime_root.join("current"))?;
+
+        let launcher = tmp_dir.join("openclaw");
+        write_launcher(
+            &launcher,
+            "openclaw",
+            "\"$SCRIPT_DIR/openclaw-runtime/current/bin/openclaw\" \"$@\"",
+        )?;
+        Ok((launcher, None))
+    }
+
+    fn install_nanoclaw(&self, version: &str, tmp_dir: &Path) -> Result<(PathBuf, Option<String>)> {
         ensure_command_available("git", "git")?;
         ensure_command_available("node", "node")?;
         ensure_command_available("pnpm", "pnpm")?;
@@ -302,7 +862,12 @@ impl RuntimeInstaller {
             command_in_dir("pnpm", &repo_dir)
                 .arg("install")
                 .arg("--prod")
-                .arg("--ignore-scripts"),
+                .arg("--ignore-scripts")
+                // pnpm's own store is content-addressed; pointing every
+                // install at the same persistent dir means a package pnpm
+                // has already fetched once is never re-downloaded.
+                .arg("--store-dir")
+                .arg(&self.pnpm_store_dir),
             "install nanoclaw dependencies",
         )?;
 
@@ -316,25 +881,92 @@ impl RuntimeInstaller {
             "nanoclaw",
             "cd \"$SCRIPT_DIR/nanoclaw-src\" && pnpm start -- \"$@\"",
         )?;
-        Ok(launcher)
+        Ok((launcher, None))
+    }
+
+    fn install_nanoclaw_from_source(
+        &self,
+        version: &str,
+        tmp_dir: &Path,
+    ) -> Result<(PathBuf, Option<String>)> {
+        ensure_command_available("git", "git")?;
+        ensure_command_available("node", "node")?;
+        ensure_command_available("pnpm", "pnpm")?;
+
+        let ref_name = if version == "latest" {
+            "main".to_string()
+        } else {
+            normalize_version(version)
+        };
+
+        let repo_dir = tmp_dir.join("nanoclaw-src");
+        run_command(
+            Command::new("git")
+                .arg("clone")
+                .arg("--depth")
+                .arg("1")
+                .arg("--branch")
+                .arg(&ref_name)
+                .arg("https://github.com/qwibitai/nanoclaw.git")
+                .arg(&repo_dir),
+            "clone nanoclaw repository",
+        )?;
+
+        run_command(
+            command_in_dir("pnpm", &repo_dir)
+                .arg("install")
+                .arg("--store-dir")
+                .arg(&self.pnpm_store_dir),
+            "install nanoclaw build dependencies",
+        )?;
+        run_command(
+            command_in_dir("pnpm", &repo_dir).arg("build"),
+            "build nanoclaw from source",
+        )?;
+
+        if !repo_dir.join("package.json").exists() {
+            bail!("nanoclaw validation failed: expected package.json missing");
+        }
+
+        let launcher = tmp_dir.join("nanoclaw");
+        write_launcher(
+            &launcher,
+            "nanoclaw",
+            "cd \"$SCRIPT_DIR/nanoclaw-src\" && pnpm start -- \"$@\"",
+        )?;
+        Ok((launcher, None))
     }
 
+    /// Downloads `url` into the cache, verifying the result against the
+    /// pinned SRI hash in `runtimes.lock` (or recording one, trust-on-first-use,
+    /// if this `(runtime, version, artifact_name)` has never been seen).
+    /// Returns the cached path and the integrity string that was matched or
+    /// recorded.
     fn download_to_cache(
         &self,
         runtime: &str,
         version: &str,
         artifact_name: &str,
         url: &str,
-    ) -> Result<PathBuf> {
+    ) -> Result<(PathBuf, String)> {
         if !url.starts_with("https://") {
             bail!("refusing non-https runtime download URL: {url}");
         }
 
+        let key = lock_key(runtime, version, artifact_name);
+        let mut lock = self.load_lock();
+
         let runtime_cache = self.cache_dir.join(runtime).join(version);
         fs::create_dir_all(&runtime_cache)?;
         let final_path = runtime_cache.join(artifact_name);
         if final_path.exists() && fs::metadata(&final_path)?.len() > 0 {
-            return Ok(final_path);
+            let integrity = sha256_sri(&final_path)?;
+            if let Some(expected) = lock.entries.get(&key) {
+                if expected != &integrity {
+                    bail!("hash mismatch: expected {expected} got {integrity}");
+                }
+            }
+            return Ok((final_path, integrity));
         }
 
         let tmp_path = runtime_cache.join(format!(".{artifact_name}.tmp"));
@@ -356,8 +988,21 @@ impl RuntimeInstaller {
             bail!("downloaded artifact is empty: {artifact_name}");
         }
 
+        let integrity = sha256_sri(&tmp_path)?;
+        match lock.entries.get(&key) {
+            Some(expected) if expected != &integrity => {
+                let _ = fs::remove_file(&tmp_path);
+                bail!("hash mismatch: expected {expected} got {integrity}");
+            }
+            Some(_) => {}
+            None => {
+                lock.entries.insert(key, integrity.clone());
+                self.save_lock(&lock)?;
+            }
+        }
+
         fs::rename(&tmp_path, &final_path)?;
-        Ok(final_path)
+        Ok((final_path, integrity))
     }
 
     fn extract_tar_gz(&self, archive: &Path, output_dir: &Path) -> Result<()> {
@@ -372,6 +1017,74 @@ impl RuntimeInstaller {
         )
     }
 
+    /// Parses `<repo_dir>/package-lock.json` (lockfile version 2/3 `packages`
+    /// plus the legacy `dependencies` map) and prefetches every resolvable
+    /// tarball into [`Self::npm_cache_dir`], verified against its recorded
+    /// integrity, so the subsequent `npm install --offline` makes no network
+    /// calls. Returns `None` (and prefetches nothing) when there is no
+    /// lockfile to read from — npm falls back to a live install in that case.
+    fn prefetch_npm_lockfile(&self, repo_dir: &Path) -> Result<Option<&Path>> {
+        let lock_path = repo_dir.join("package-lock.json");
+        if !lock_path.exists() {
+            return Ok(None);
+        }
+
+        let body = fs::read_to_string(&lock_path)
+            .with_context(|| format!("reading {}", lock_path.display()))?;
+        let lock: PackageLock = serde_json::from_str(&body)
+            .with_context(|| format!("parsing {}", lock_path.display()))?;
+
+        for (resolved, integrity) in lock.fetchable_entries() {
+            self.fetch_into_npm_cache(&resolved, &integrity)?;
+        }
+
+        Ok(Some(&self.npm_cache_dir))
+    }
+
+    fn fetch_into_npm_cache(&self, url: &str, integrity: &str) -> Result<PathBuf> {
+        let cache_name = integrity.replace(['/', '+', '=', ':'], "_");
+        let cached_path = self.npm_cache_dir.join(format!("{cache_name}.tgz"));
+        if cached_path.exists() {
+            return Ok(cached_path);
+        }
+
+        let tmp_path = self.npm_cache_dir.join(format!(".{cache_name}.tmp"));
+        ensure_command_available("curl", "curl")?;
+        run_command(
+            Command::new("curl")
+                .arg("-fsSL")
+                .arg(url)
+                .arg("-o")
+                .arg(&tmp_path),
+            &format!("prefetch npm tarball from {url}"),
+        )?;
+
+        if let Err(e) = verify_npm_integrity(&tmp_path, integrity) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+
+        fs::rename(&tmp_path, &cached_path)?;
+        Ok(cached_path)
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.root_dir.join("runtimes.lock")
+    }
+
+    fn load_lock(&self) -> RuntimeLockFile {
+        let Ok(body) = fs::read_to_string(self.lock_path()) else {
+            return RuntimeLockFile::default();
+        };
+        serde_json::from_str(&body).unwrap_or_default()
+    }
+
+    fn save_lock(&self, lock: &RuntimeLockFile) -> Result<()> {
+        let body = serde_json::to_string_pretty(lock)?;
+        fs::write(self.lock_path(), body)?;
+        Ok(())
+    }
+
     fn append_audit(&self, action: &str, runtime: &str, outcome: &str) -> Result<()> {
         let audit_path = self.logs_dir.join("audit.log");
         let now_ms = std::time::SystemTime::now()
@@ -390,6 +1103,183 @@ impl RuntimeInstaller {
     pub fn root_dir(&self) -> &Path {
         &self.root_dir
     }
+
+    /// Read-only host/toolchain/runtime report, modeled on tauri-cli's
+    /// `info`: resolved versions of every external tool the installer shells
+    /// out to, the installed-runtime table augmented with integrity and
+    /// symlink health, and a flat list of actionable problems (dangling
+    /// `current` symlinks, stale `.tmp` dirs from a crashed install, a dead
+    /// install lock).
+    pub fn diagnose(&self) -> Result<Diagnostics> {
+        let (os, arch) = host_os_arch()?;
+
+        let mut tools = Vec::new();
+        let mut missing_tools = Vec::new();
+        for tool in ["curl", "tar", "7z", "node", "npm", "pnpm", "git"] {
+            match tool_version(tool) {
+                Some(version) => tools.push(ToolVersion {
+                    tool: tool.to_string(),
+                    version: Some(version),
+                }),
+                None => {
+                    tools.push(ToolVersion {
+                        tool: tool.to_string(),
+                        version: None,
+                    });
+                    missing_tools.push(tool.to_string());
+                }
+            }
+        }
+
+        let mut runtimes = Vec::new();
+        let mut problems = Vec::new();
+        for row in self.list_installed()? {
+            let current_symlink_ok = row.executable.exists();
+            if !current_symlink_ok {
+                problems.push(format!(
+                    "{} current symlink points at a missing executable ({})",
+                    row.runtime,
+                    row.executable.display()
+                ));
+            }
+            runtimes.push(RuntimeDiagnostic {
+                runtime: row.runtime,
+                version: row.version,
+                executable: row.executable,
+                integrity: row.integrity,
+                current_symlink_ok,
+            });
+        }
+
+        if self.runtimes_dir.exists() {
+            for entry in fs::read_dir(&self.runtimes_dir)? {
+                let entry = entry?;
+                if !entry.path().is_dir() {
+                    continue;
+                }
+                // `list_installed` omits runtimes whose `current` no longer
+                // resolves to an executable, so dangling links are caught here.
+                let current = entry.path().join("current");
+                let executable = current.join(entry.file_name());
+                if current.is_symlink() && !executable.exists() {
+                    problems.push(format!(
+                        "{} current symlink points at a missing executable ({})",
+                        entry.file_name().to_string_lossy(),
+                        executable.display()
+                    ));
+                }
+                for version_entry in fs::read_dir(entry.path())? {
+                    let version_entry = version_entry?;
+                    let name = version_entry.file_name().to_string_lossy().to_string();
+                    if name.starts_with('.') && name.ends_with(".tmp") {
+                        problems.push(format!(
+                            "stale install temp dir left by a crashed install: {}",
+                            version_entry.path().display()
+                        ));
+                    }
+                }
+            }
+        }
+
+        if self.lock_path.exists() && !is_lock_active(&self.lock_path) {
+            problems.push(format!(
+                "install lock {} references a dead process; a crashed install left it behind",
+                self.lock_path.display()
+            ));
+        }
+
+        Ok(Diagnostics {
+            os: os.to_string(),
+            arch: arch.to_string(),
+            tools,
+            missing_tools,
+            runtimes,
+            problems,
+        })
+    }
+}
+
+/// The resolved `--version` of one external tool the installer depends on,
+/// or `None` if it isn't on `PATH`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolVersion {
+    pub tool: String,
+    pub version: Option<String>,
+}
+
+/// One row of [`RuntimeInstaller::list_installed`], augmented with whether
+/// its `current` symlink resolves to an executable that still exists.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeDiagnostic {
+    pub runtime: String,
+    pub version: String,
+    pub executable: PathBuf,
+    pub integrity: Option<String>,
+    pub current_symlink_ok: bool,
+}
+
+/// Read-only report produced by [`RuntimeInstaller::diagnose`]. Serializes
+/// to JSON for machine consumption; [`std::fmt::Display`] renders the same
+/// data as a human-readable table.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub os: String,
+    pub arch: String,
+    pub tools: Vec<ToolVersion>,
+    pub missing_tools: Vec<String>,
+    pub runtimes: Vec<RuntimeDiagnostic>,
+    pub problems: Vec<String>,
+}
+
+impl std::fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "os={} arch={}", self.os, self.arch)?;
+        writeln!(f)?;
+        writeln!(f, "TOOL\tVERSION")?;
+        for tool in &self.tools {
+            writeln!(
+                f,
+                "{}\t{}",
+                tool.tool,
+                tool.version.as_deref().unwrap_or("missing")
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "RUNTIME\tVERSION\tINTEGRITY\tCURRENT\tEXECUTABLE")?;
+        for runtime in &self.runtimes {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{}",
+                runtime.runtime,
+                runtime.version,
+                runtime.integrity.as_deref().unwrap_or("-"),
+                if runtime.current_symlink_ok { "ok" } else { "dangling" },
+                runtime.executable.display()
+            )?;
+        }
+
+        if !self.problems.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "problems:")?;
+            for problem in &self.problems {
+                writeln!(f, "  - {problem}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn tool_version(tool: &str) -> Option<String> {
+    let output = Command::new(tool).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
 }
 
 struct GithubRelease {
@@ -415,12 +1305,19 @@ fn github_release_assets(
         format!("https://api.github.com/repos/{owner}/{repo}/releases/tags/v{normalized}")
     };
 
-    let output = Command::new("curl")
+    let mut command = Command::new("curl");
+    command
         .arg("-fsSL")
         .arg("-H")
         .arg("Accept: application/vnd.github+json")
         .arg("-H")
-        .arg("User-Agent: clawden")
+        .arg("User-Agent: clawden");
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        command
+            .arg("-H")
+            .arg(format!("Authorization: Bearer {token}"));
+    }
+    let output = command
         .arg(&url)
         .output()
         .with_context(|| format!("failed to query GitHub release API: {url}"))?;
@@ -457,6 +1354,171 @@ fn github_release_assets(
     Ok(GithubRelease { tag, assets })
 }
 
+fn runtime_repo(runtime: &str) -> Result<(&'static str, &'static str)> {
+    match runtime {
+        "zeroclaw" => Ok(("zeroclaw-labs", "zeroclaw")),
+        "picoclaw" => Ok(("picoclaw-labs", "picoclaw")),
+        "openclaw" => Ok(("openclaw-dev", "openclaw")),
+        "nanoclaw" => Ok(("qwibitai", "nanoclaw")),
+        _ => Err(anyhow!(
+            "runtime '{}' not supported by direct installer",
+            runtime
+        )),
+    }
+}
+
+/// Fetches one page of a paginated GitHub API endpoint, returning the
+/// decoded JSON body and the next page's URL if the response's `Link`
+/// header carries a `rel="next"` entry.
+fn github_api_page(url: &str, token: Option<&str>) -> Result<(serde_json::Value, Option<String>)> {
+    let header_file =
+        std::env::temp_dir().join(format!("clawden-gh-headers-{}.tmp", std::process::id()));
+
+    let mut command = Command::new("curl");
+    command
+        .arg("-fsSL")
+        .arg("-D")
+        .arg(&header_file)
+        .arg("-H")
+        .arg("Accept: application/vnd.github+json")
+        .arg("-H")
+        .arg("User-Agent: clawden");
+    if let Some(token) = token {
+        command
+            .arg("-H")
+            .arg(format!("Authorization: Bearer {token}"));
+    }
+
+    let output = command
+        .arg(url)
+        .output()
+        .with_context(|| format!("failed to query GitHub API: {url}"))?;
+
+    let headers = fs::read_to_string(&header_file).unwrap_or_default();
+    let _ = fs::remove_file(&header_file);
+
+    if !output.status.success() {
+        bail!("failed to query GitHub API: {url}");
+    }
+
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("invalid GitHub API response: {url}"))?;
+
+    Ok((value, parse_link_next(&headers)))
+}
+
+/// Extracts the `rel="next"` URL from a raw `Link` response header, e.g.
+/// `Link: <https://api.github.com/...&page=2>; rel="next", <...>; rel="last"`.
+fn parse_link_next(headers: &str) -> Option<String> {
+    for line in headers.lines() {
+        // The status line (`HTTP/2 200`) and the trailing blank line have
+        // no colon; skip them rather than giving up on the whole block.
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !name.trim().eq_ignore_ascii_case("link") {
+            continue;
+        }
+        for part in value.split(',') {
+            let mut segments = part.split(';').map(str::trim);
+            let url_part = segments.next()?;
+            if segments.any(|segment| segment == "rel=\"next\"") {
+                return Some(
+                    url_part
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string(),
+                );
+            }
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SemVer {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+fn parse_semver(version: &str) -> Option<SemVer> {
+    let trimmed = version.trim_start_matches('v');
+    let mut parts = trimmed.splitn(3, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().unwrap_or("0").parse().ok()?;
+    let patch_field = parts.next().unwrap_or("0");
+    let patch_digits: String = patch_field.chars().take_while(char::is_ascii_digit).collect();
+    let patch = if patch_digits.is_empty() {
+        0
+    } else {
+        patch_digits.parse().ok()?
+    };
+    Some(SemVer {
+        major,
+        minor,
+        patch,
+    })
+}
+
+/// Resolves a `^` or `~` semver range spec against a set of published tags,
+/// npm-style: `^1.2.3` allows patch and minor bumps below `2.0.0`; below
+/// `1.0.0` caret narrows to the first nonzero component (`^0.3.1` stays
+/// under `0.4.0`, `^0.0.3` stays under `0.0.4`). `~1.2.3` allows patch bumps
+/// below `1.3.0`, as does `~1.2` (no patch given); `~1` (no minor given)
+/// allows minor bumps below `2.0.0`.
+fn resolve_version_spec(spec: &str, available: &[String]) -> Option<String> {
+    let (op, rest) = if let Some(rest) = spec.strip_prefix('^') {
+        ('^', rest)
+    } else if let Some(rest) = spec.strip_prefix('~') {
+        ('~', rest)
+    } else {
+        return available
+            .iter()
+            .find(|tag| normalize_version(tag) == normalize_version(spec))
+            .cloned();
+    };
+
+    let base = parse_semver(rest)?;
+    let component_count = rest.matches('.').count() + 1;
+
+    let max_exclusive = match op {
+        '^' if base.major > 0 => SemVer {
+            major: base.major + 1,
+            minor: 0,
+            patch: 0,
+        },
+        '^' if base.minor > 0 => SemVer {
+            major: 0,
+            minor: base.minor + 1,
+            patch: 0,
+        },
+        '^' => SemVer {
+            major: 0,
+            minor: 0,
+            patch: base.patch + 1,
+        },
+        '~' if component_count >= 2 => SemVer {
+            major: base.major,
+            minor: base.minor + 1,
+            patch: 0,
+        },
+        '~' => SemVer {
+            major: base.major + 1,
+            minor: 0,
+            patch: 0,
+        },
+        _ => unreachable!("op is only ever '^' or '~'"),
+    };
+
+    available
+        .iter()
+        .filter_map(|tag| parse_semver(tag).map(|version| (tag, version)))
+        .filter(|(_, version)| *version >= base && *version < max_exclusive)
+        .max_by_key(|(_, version)| *version)
+        .map(|(tag, _)| tag.clone())
+}
+
 fn pick_asset<'a>(
     assets: &'a [GithubAsset],
     patterns: &[&str],
@@ -585,6 +1647,20 @@ fn ensure_command_available(command: &str, install_hint: &str) -> Result<()> {
     )
 }
 
+fn resolve_system_binary(command: &str) -> Result<PathBuf> {
+    let output = Command::new("which")
+        .arg(command)
+        .output()
+        .with_context(|| format!("looking up system {command} binary"))?;
+
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || path.is_empty() {
+        bail!("system install strategy requires '{command}' on PATH, but it was not found");
+    }
+
+    Ok(PathBuf::from(path))
+}
+
 fn run_command(command: &mut Command, action: &str) -> Result<()> {
     let status = command
         .status()
@@ -1,3 +1,6 @@
+pub mod sasl;
+pub mod supervisor;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -1,19 +1,184 @@
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::os::unix::process::CommandExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Quiet period [`ProcessManager::watch`] waits for after a filesystem event
+/// before restarting, so a multi-file save collapses into one restart.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How long [`ProcessManager::supervised_restart`] waits for a replacement
+/// instance to report healthy before giving up and keeping the old one.
+const WATCH_HEALTH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The systemd socket-activation convention: the first (and here, only)
+/// passed fd always lands at this number in the child.
+const LISTEN_FD_NUMBER: i32 = 3;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExecutionMode {
     Docker,
     Direct,
+    Oci,
     Auto,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// CPU quota, expressed as a fraction of one core (e.g. `1.5` = 1.5 cores).
+    pub cpu_cores: Option<f64>,
+    pub memory_mb: Option<u64>,
+}
+
+/// Per-runtime launch configuration consumed by `start_direct`/`start_docker`.
+/// `OsString` args support non-UTF-8 paths/arguments, and an explicit `env`
+/// map (with an `env_clear` escape hatch) lets two runtimes use the same
+/// variable name without colliding, instead of both inheriting the parent
+/// process's environment wholesale.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeLaunchSpec {
+    pub args: Vec<OsString>,
+    pub env: HashMap<OsString, OsString>,
+    pub env_clear: bool,
+    pub working_dir: Option<PathBuf>,
+}
+
+impl RuntimeLaunchSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_env_clear(mut self, clear: bool) -> Self {
+        self.env_clear = clear;
+        self
+    }
+
+    pub fn with_working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    /// Applies `args`/`env`/`env_clear`/`working_dir` to `command`, leaving
+    /// the executable and stdio redirection to the caller.
+    fn apply(&self, command: &mut Command) {
+        command.args(&self.args);
+        if self.env_clear {
+            command.env_clear();
+        }
+        for (key, value) in &self.env {
+            command.env(key, value);
+        }
+        if let Some(dir) = &self.working_dir {
+            command.current_dir(dir);
+        }
+    }
+}
+
+/// Thresholds used to derive a [`crate::HealthStatus`] from sampled process
+/// metrics. Crossing a `degraded` threshold without crossing the matching
+/// `unhealthy` one yields `Degraded`; crossing `unhealthy` always wins.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthThresholds {
+    pub cpu_degraded_percent: f32,
+    pub cpu_unhealthy_percent: f32,
+    pub memory_degraded_mb: f32,
+    pub memory_unhealthy_mb: f32,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            cpu_degraded_percent: 80.0,
+            cpu_unhealthy_percent: 98.0,
+            memory_degraded_mb: 768.0,
+            memory_unhealthy_mb: 1536.0,
+        }
+    }
+}
+
+/// A single health check target, replacing the old `curl`-shelled HTTP-only
+/// check with native multi-protocol probing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HealthProbe {
+    /// HTTP(S) GET, healthy when the response status is one of `expected_status`.
+    Http {
+        url: String,
+        #[serde(default = "default_expected_status")]
+        expected_status: Vec<u16>,
+    },
+    /// A bare TCP connect to `addr` (`host:port`).
+    Tcp { addr: String },
+    /// Runs `command` with `args` to completion; a zero exit code is healthy.
+    Exec { command: String, args: Vec<String> },
+}
+
+fn default_expected_status() -> Vec<u16> {
+    vec![200]
+}
+
+/// Wraps a [`HealthProbe`] with interval/timeout/hysteresis settings so
+/// `list_statuses` can report `starting`/`healthy`/`unhealthy` instead of a
+/// single point-in-time sample.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeConfig {
+    pub probe: HealthProbe,
+    #[serde(default = "default_probe_interval_ms")]
+    pub interval_ms: u64,
+    #[serde(default = "default_probe_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default = "default_probe_threshold")]
+    pub consecutive_successes: u32,
+    #[serde(default = "default_probe_threshold")]
+    pub consecutive_failures: u32,
+}
+
+fn default_probe_interval_ms() -> u64 {
+    5_000
+}
+
+fn default_probe_timeout_ms() -> u64 {
+    2_000
+}
+
+fn default_probe_threshold() -> u32 {
+    2
+}
+
+/// Sliding hysteresis state for a runtime's probe, persisted in its pidfile
+/// so consecutive-success/failure counts survive across separate
+/// `list_statuses` calls (each a fresh CLI invocation) instead of resetting
+/// to zero every time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProbeState {
+    pub consecutive_successes: u32,
+    pub consecutive_failures: u32,
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub runtime: String,
@@ -23,6 +188,45 @@ pub struct ProcessInfo {
     pub log_path: PathBuf,
     pub restart_policy: Option<String>,
     pub health_url: Option<String>,
+    /// Native multi-protocol health probe; takes priority over `health_url`
+    /// (a bare `health_url` is treated as an implicit single-attempt HTTP
+    /// probe for backward compatibility).
+    #[serde(default)]
+    pub probe: Option<ProbeConfig>,
+    #[serde(default)]
+    pub probe_state: ProbeState,
+    /// Set when `mode` is `Oci`: the container id and bundle directory used
+    /// to drive the runtime's `create`/`start`/`kill`/`delete` lifecycle.
+    #[serde(default)]
+    pub oci_container_id: Option<String>,
+    #[serde(default)]
+    pub oci_bundle_dir: Option<PathBuf>,
+    /// Set when `mode` is `Docker`: the `docker run` container id, used to
+    /// drive `stop`/`inspect`/`logs` instead of a bare OS pid.
+    #[serde(default)]
+    pub container_id: Option<String>,
+    /// Process-group id of the spawned process under `Direct` mode, set via
+    /// `setpgid(0, 0)` in a `pre_exec` hook before it execs. `stop` signals
+    /// the negative of this value so a `restart-policy=on-failure`
+    /// supervisor and the runtime child it forks are reaped together,
+    /// instead of orphaning the child when only the supervisor's own pid is
+    /// killed. `None` for `Docker`/`Oci`, which have no restart supervisor to
+    /// orphan.
+    #[serde(default)]
+    pub pgid: Option<i32>,
+    /// Set by `graceful_restart`: the raw fd of the listening socket that
+    /// call bound and handed to the child at the systemd-socket-activation
+    /// fd number via `LISTEN_FDS`/`LISTEN_PID`. Recorded for diagnostics
+    /// only — it refers to a fd in that (now-exited) CLI invocation's own fd
+    /// table, not this one's, so it must never be read back and `dup2`'d by
+    /// a later call. Each `graceful_restart` call binds its own fresh
+    /// `SO_REUSEPORT` socket instead.
+    #[serde(default)]
+    pub inherited_fd: Option<i32>,
+    /// The address `inherited_fd` was bound to, recorded alongside it for
+    /// diagnostics.
+    #[serde(default)]
+    pub bound_addr: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -33,6 +237,17 @@ pub struct RuntimeProcessStatus {
     pub mode: ExecutionMode,
     pub log_path: PathBuf,
     pub health: String,
+    /// OCI container state (`creating`, `created`, `running`, `stopped`) when
+    /// running under `ExecutionMode::Oci`; `None` for other modes.
+    pub container_state: Option<String>,
+    /// Most recent `runtime.crash`/`runtime.restart`/`runtime.circuit_open`
+    /// records the `clawden supervise` subprocess recorded for this
+    /// runtime, oldest first. Empty unless `restart_policy` was set.
+    pub crash_history: Vec<crate::supervisor::CrashRecord>,
+    /// Latency of the most recent probe attempt, if one ran.
+    pub probe_latency_ms: Option<u64>,
+    /// Error from the most recent probe attempt, if it failed.
+    pub probe_error: Option<String>,
 }
 
 pub struct ProcessManager {
@@ -90,11 +305,23 @@ impl ProcessManager {
         }
     }
 
-    pub fn start_direct(
+    /// Finds the first available low-level OCI runtime on PATH, preferring
+    /// `crun` (fastest startup) then `runc` then `youki`.
+    pub fn oci_runtime_available() -> Option<&'static str> {
+        ["crun", "runc", "youki"]
+            .into_iter()
+            .find(|candidate| command_on_path(candidate))
+    }
+
+    /// Runs `runtime` inside a low-level OCI container instead of a bare
+    /// process, giving it kernel-level isolation and real CPU/memory caps
+    /// without requiring the Docker daemon.
+    pub fn start_oci(
         &self,
         runtime: &str,
         executable: &Path,
         args: &[String],
+        limits: &ResourceLimits,
     ) -> Result<ProcessInfo> {
         if !executable.exists() {
             return Err(anyhow!(
@@ -103,30 +330,92 @@ impl ProcessManager {
             ));
         }
 
+        let oci_runtime = Self::oci_runtime_available()
+            .ok_or_else(|| anyhow!("no OCI runtime (crun/runc/youki) found on PATH"))?;
+
         let log_path = self.log_dir.join(format!("{runtime}.log"));
         let (runtime_args, restart_policy) = split_restart_policy(args);
         let health_url = runtime_health_url(runtime);
 
+        let container_id = format!("clawden-{runtime}");
+        let bundle_dir = self.state_dir.join("oci").join(&container_id);
+        if bundle_dir.exists() {
+            fs::remove_dir_all(&bundle_dir)?;
+        }
+        fs::create_dir_all(bundle_dir.join("rootfs"))?;
+
+        let clawden_home = clawden_root_dir()?;
+        let config = build_oci_config(executable, &runtime_args, &clawden_home, limits)?;
+        fs::write(
+            bundle_dir.join("config.json"),
+            serde_json::to_string_pretty(&config)?,
+        )?;
+
+        run_oci(
+            oci_runtime,
+            &["create", "--bundle"],
+            &bundle_dir,
+            &container_id,
+        )?;
+        run_oci(oci_runtime, &["start"], &bundle_dir, &container_id)?;
+
+        let pid = oci_container_pid(oci_runtime, &container_id)
+            .with_context(|| format!("reading pid for OCI container {container_id}"))?;
+
+        let info = ProcessInfo {
+            runtime: runtime.to_string(),
+            pid,
+            started_at_unix_ms: now_ms(),
+            mode: ExecutionMode::Oci,
+            log_path,
+            restart_policy,
+            health_url,
+            oci_container_id: Some(container_id),
+            oci_bundle_dir: Some(bundle_dir),
+            container_id: None,
+            pgid: None,
+            inherited_fd: None,
+            bound_addr: None,
+            probe: None,
+            probe_state: ProbeState::default(),
+        };
+
+        self.write_pid_file(runtime, &info)?;
+        Ok(info)
+    }
+
+    pub fn start_direct(
+        &self,
+        runtime: &str,
+        executable: &Path,
+        spec: &RuntimeLaunchSpec,
+    ) -> Result<ProcessInfo> {
+        if !executable.exists() {
+            return Err(anyhow!(
+                "runtime executable not found: {}",
+                executable.display()
+            ));
+        }
+
+        let log_path = self.log_dir.join(format!("{runtime}.log"));
+        let (runtime_args, restart_policy) = split_restart_policy_os(&spec.args);
+        let health_url = runtime_health_url(runtime);
+
         let mut command = if restart_policy.as_deref() == Some("on-failure") {
-            let script_path = self.state_dir.join(format!("{runtime}.supervisor.sh"));
             let audit_path = self.log_dir.join("audit.log");
-            let script = build_restart_supervisor_script();
-            fs::write(&script_path, script)
-                .with_context(|| format!("writing supervisor script {}", script_path.display()))?;
-            #[allow(clippy::permissions_set_readonly_false)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let mut perms = fs::metadata(&script_path)?.permissions();
-                perms.set_mode(0o755);
-                fs::set_permissions(&script_path, perms)?;
-            }
+            // The `clawden supervise` subcommand *is* this restart
+            // supervisor now (see `clawden_core::supervisor::run`), so we
+            // re-exec the currently running binary instead of generating
+            // and `sh`-ing a shell script.
+            let supervisor_exe = std::env::current_exe()
+                .context("resolving current executable path for the restart supervisor")?;
 
-            let mut cmd = Command::new("sh");
-            cmd.arg(script_path)
+            let mut cmd = Command::new(supervisor_exe);
+            cmd.arg("supervise")
+                .arg(runtime)
                 .arg(executable)
                 .arg(&log_path)
-                .arg(audit_path)
-                .arg(runtime);
+                .arg(&audit_path);
             cmd.args(&runtime_args);
             cmd.stdout(Stdio::null());
             cmd.stderr(Stdio::null());
@@ -146,9 +435,34 @@ impl ProcessManager {
             cmd
         };
 
+        if spec.env_clear {
+            command.env_clear();
+        }
+        for (key, value) in &spec.env {
+            command.env(key, value);
+        }
+        if let Some(dir) = &spec.working_dir {
+            command.current_dir(dir);
+        }
+
+        // Make the spawned process its own process-group leader so `stop`
+        // can signal the negative pgid and reap a `restart-policy=on-failure`
+        // supervisor together with the runtime child it forks, instead of
+        // orphaning that child when only the supervisor's pid is killed.
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setpgid(0, 0) == 0 {
+                    Ok(())
+                } else {
+                    Err(std::io::Error::last_os_error())
+                }
+            });
+        }
+
         let child = command
             .spawn()
             .with_context(|| format!("failed to spawn {}", executable.display()))?;
+        let pgid = child.id() as i32;
 
         let info = ProcessInfo {
             runtime: runtime.to_string(),
@@ -158,19 +472,280 @@ impl ProcessManager {
             log_path: log_path.clone(),
             restart_policy,
             health_url,
+            oci_container_id: None,
+            oci_bundle_dir: None,
+            container_id: None,
+            pgid: Some(pgid),
+            inherited_fd: None,
+            bound_addr: None,
+            probe: None,
+            probe_state: ProbeState::default(),
         };
 
         self.write_pid_file(runtime, &info)?;
         Ok(info)
     }
 
+    /// Runs `runtime` as a `docker run -d` container instead of a bare
+    /// process, applying `limits` as `--cpus`/`--memory` and the parsed
+    /// `--restart=` policy as Docker's own `--restart` flag so crash
+    /// recovery is handled by the container engine rather than the
+    /// `clawden supervise` restart supervisor `start_direct` spawns.
+    pub fn start_docker(
+        &self,
+        runtime: &str,
+        image: &str,
+        spec: &RuntimeLaunchSpec,
+        limits: &ResourceLimits,
+    ) -> Result<ProcessInfo> {
+        let log_path = self.log_dir.join(format!("{runtime}.log"));
+        let (runtime_args, restart_policy) = split_restart_policy_os(&spec.args);
+        let health_url = runtime_health_url(runtime);
+
+        let container_name = format!("clawden-{runtime}");
+        let _ = Command::new("docker")
+            .args(["rm", "-f", &container_name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+
+        let mut command = Command::new("docker");
+        command.args(["run", "-d", "--name", &container_name]);
+        command.args(["--restart", restart_policy.as_deref().unwrap_or("no")]);
+        if let Some(cores) = limits.cpu_cores {
+            command.args(["--cpus", &cores.to_string()]);
+        }
+        if let Some(memory_mb) = limits.memory_mb {
+            command.args(["--memory", &format!("{memory_mb}m")]);
+        }
+        // `docker run` never inherits the caller's environment, so
+        // `env_clear` is implicitly always true here; only `-e` entries need
+        // threading through explicitly.
+        for (key, value) in &spec.env {
+            command.arg("-e");
+            command.arg(format!(
+                "{}={}",
+                key.to_string_lossy(),
+                value.to_string_lossy()
+            ));
+        }
+        if let Some(dir) = &spec.working_dir {
+            command.args(["-w", &dir.to_string_lossy()]);
+        }
+        command.arg(image);
+        command.args(&runtime_args);
+
+        let output = command
+            .output()
+            .with_context(|| format!("failed to run docker for {runtime}"))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "docker run for {runtime} exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let pid = docker_container_pid(&container_id)
+            .with_context(|| format!("reading pid for docker container {container_id}"))?;
+
+        let info = ProcessInfo {
+            runtime: runtime.to_string(),
+            pid,
+            started_at_unix_ms: now_ms(),
+            mode: ExecutionMode::Docker,
+            log_path,
+            restart_policy,
+            health_url,
+            oci_container_id: None,
+            oci_bundle_dir: None,
+            container_id: Some(container_id),
+            pgid: None,
+            inherited_fd: None,
+            bound_addr: None,
+            probe: None,
+            probe_state: ProbeState::default(),
+        };
+
+        self.write_pid_file(runtime, &info)?;
+        Ok(info)
+    }
+
+    /// Restarts `runtime` with zero dropped connections: binds a *new*
+    /// listening socket on `bind_addr` (with `SO_REUSEPORT` so the bind
+    /// succeeds while the previous instance is still listening on the same
+    /// address) and hands it to the replacement child at the
+    /// systemd-socket-activation fd number via `LISTEN_FDS`/`LISTEN_PID`,
+    /// waits for the replacement's `health_url` to report healthy, and only
+    /// then `SIGTERM`s the previous instance — so the old and new processes
+    /// overlap on the same port instead of the old one being torn down
+    /// before the new one can bind.
+    ///
+    /// Each call always binds fresh rather than trying to reuse a fd number
+    /// recorded by an earlier call: `clawden run --graceful` is a one-shot
+    /// CLI invocation that exits right after spawning the replacement, so
+    /// any fd it opened is gone from this process's fd table by the time a
+    /// later invocation starts — a raw fd number round-tripped through the
+    /// pidfile would refer to nothing (or, worse, to whatever this new
+    /// process happens to have open at that number) rather than to a live
+    /// socket. `SO_REUSEPORT` is what actually makes the overlap-and-replace
+    /// handoff work across independent processes; real fd inheritance would
+    /// require a long-lived process (a daemon, or systemd itself) to hold
+    /// the socket across restarts, which this CLI-per-invocation model
+    /// doesn't have.
+    pub fn graceful_restart(
+        &self,
+        runtime: &str,
+        executable: &Path,
+        spec: &RuntimeLaunchSpec,
+        bind_addr: &str,
+    ) -> Result<ProcessInfo> {
+        let existing = self.read_pid_file(runtime)?;
+
+        let addr: SocketAddr = bind_addr
+            .parse()
+            .with_context(|| format!("parsing bind address {bind_addr}"))?;
+        let fd = bind_inheritable_listener(&addr)?;
+
+        let log_path = self.log_dir.join(format!("{runtime}.log"));
+        let health_url = runtime_health_url(runtime);
+
+        let stdout = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .with_context(|| format!("opening runtime log file {}", log_path.display()))?;
+        let stderr = stdout.try_clone()?;
+
+        let mut command = Command::new(executable);
+        spec.apply(&mut command);
+        command.stdout(Stdio::from(stdout));
+        command.stderr(Stdio::from(stderr));
+
+        // Same as `start_direct`: make the spawned process its own
+        // process-group leader so `stop` can signal the negative pgid
+        // recorded below instead of the pgid of this short-lived
+        // `clawden run --graceful` invocation, which has already exited by
+        // the time a later `stop`/`restart` looks the runtime up.
+        unsafe {
+            command.pre_exec(move || {
+                if libc::setpgid(0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if libc::dup2(fd, LISTEN_FD_NUMBER) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                let pid_cstr = std::ffi::CString::new(libc::getpid().to_string())
+                    .expect("pid string has no interior nul");
+                let listen_fds = std::ffi::CString::new("LISTEN_FDS").unwrap();
+                let listen_fds_value = std::ffi::CString::new("1").unwrap();
+                let listen_pid = std::ffi::CString::new("LISTEN_PID").unwrap();
+                if libc::setenv(listen_fds.as_ptr(), listen_fds_value.as_ptr(), 1) != 0
+                    || libc::setenv(listen_pid.as_ptr(), pid_cstr.as_ptr(), 1) != 0
+                {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let child = command
+            .spawn()
+            .with_context(|| format!("failed to spawn {}", executable.display()))?;
+        let pgid = child.id() as i32;
+
+        let new_info = ProcessInfo {
+            runtime: runtime.to_string(),
+            pid: child.id(),
+            started_at_unix_ms: now_ms(),
+            mode: ExecutionMode::Direct,
+            log_path,
+            restart_policy: None,
+            health_url: health_url.clone(),
+            oci_container_id: None,
+            oci_bundle_dir: None,
+            container_id: None,
+            pgid: Some(pgid),
+            inherited_fd: Some(fd),
+            bound_addr: Some(bind_addr.to_string()),
+            probe: None,
+            probe_state: ProbeState::default(),
+        };
+
+        let healthy = match &health_url {
+            Some(url) => wait_until_healthy(url, WATCH_HEALTH_TIMEOUT),
+            None => true,
+        };
+        if !healthy {
+            unsafe {
+                libc::kill(new_info.pid as i32, libc::SIGTERM);
+            }
+            anyhow::bail!(
+                "replacement instance of {runtime} failed its health check; keeping the previous instance running"
+            );
+        }
+
+        if let Some(old) = existing {
+            unsafe {
+                libc::kill(old.pid as i32, libc::SIGTERM);
+            }
+        }
+
+        self.write_pid_file(runtime, &new_info)?;
+        Ok(new_info)
+    }
+
     pub fn stop(&self, runtime: &str) -> Result<()> {
         let Some(info) = self.read_pid_file(runtime)? else {
             return Ok(());
         };
 
-        let pid = info.pid.to_string();
-        let _ = Command::new("kill").args(["-TERM", &pid]).status();
+        if let (ExecutionMode::Oci, Some(oci_runtime), Some(container_id)) = (
+            info.mode,
+            Self::oci_runtime_available(),
+            info.oci_container_id.as_ref(),
+        ) {
+            let _ = Command::new(oci_runtime)
+                .args(["kill", container_id, "TERM"])
+                .status();
+            for _ in 0..20 {
+                if !is_pid_running(info.pid) {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+            let _ = Command::new(oci_runtime)
+                .args(["delete", "--force", container_id])
+                .status();
+            self.remove_pid_file(runtime)?;
+            return Ok(());
+        }
+
+        if let (ExecutionMode::Docker, Some(container_id)) =
+            (info.mode, info.container_id.as_ref())
+        {
+            let _ = Command::new("docker")
+                .args(["stop", container_id])
+                .status();
+            let _ = Command::new("docker")
+                .args(["rm", "-f", container_id])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            self.remove_pid_file(runtime)?;
+            return Ok(());
+        }
+
+        // Signal the whole process group, not just the recorded pid: under
+        // `restart-policy=on-failure` that pid is the `clawden supervise`
+        // process, which spawns the real runtime as a child sharing its pgid —
+        // killing only the supervisor would orphan that child.
+        let pgid = info.pgid.unwrap_or(info.pid as i32);
+        let group_target = format!("-{pgid}");
+        let _ = Command::new("kill")
+            .args(["-TERM", &group_target])
+            .status();
         for _ in 0..20 {
             if !is_pid_running(info.pid) {
                 self.remove_pid_file(runtime)?;
@@ -179,7 +754,9 @@ impl ProcessManager {
             thread::sleep(Duration::from_millis(100));
         }
 
-        let _ = Command::new("kill").args(["-KILL", &pid]).status();
+        let _ = Command::new("kill")
+            .args(["-KILL", &group_target])
+            .status();
         self.remove_pid_file(runtime)?;
         Ok(())
     }
@@ -204,16 +781,61 @@ impl ProcessManager {
                 .to_string();
 
             if let Some(info) = self.read_pid_file(&runtime)? {
-                let health = if !is_pid_running(info.pid) {
-                    "stopped".to_string()
-                } else if let Some(url) = &info.health_url {
-                    if health_check_ok(url) {
-                        "healthy".to_string()
+                let audit_path = self.log_dir.join("audit.log");
+                let crash_history = crate::supervisor::read_crash_history(&audit_path, &runtime, 10);
+                let circuit_open = crash_history
+                    .last()
+                    .is_some_and(|record| record.event == "runtime.circuit_open");
+
+                let (health, probe_latency_ms, probe_error) = if circuit_open
+                    && !is_pid_running(info.pid)
+                {
+                    ("failed".to_string(), None, None)
+                } else if !is_pid_running(info.pid) {
+                    ("stopped".to_string(), None, None)
+                } else if let Some(probe_config) = effective_probe_config(&info) {
+                    let result = run_probe(
+                        &probe_config.probe,
+                        Duration::from_millis(probe_config.timeout_ms),
+                    );
+                    let mut probe_state = info.probe_state.clone();
+                    if result.success {
+                        probe_state.consecutive_successes += 1;
+                        probe_state.consecutive_failures = 0;
                     } else {
-                        "unhealthy".to_string()
+                        probe_state.consecutive_failures += 1;
+                        probe_state.consecutive_successes = 0;
                     }
+                    probe_state.last_latency_ms = Some(result.latency_ms);
+                    probe_state.last_error = result.error;
+
+                    let health = if probe_state.consecutive_failures >= probe_config.consecutive_failures
+                    {
+                        "unhealthy".to_string()
+                    } else if probe_state.consecutive_successes >= probe_config.consecutive_successes {
+                        "healthy".to_string()
+                    } else {
+                        "starting".to_string()
+                    };
+
+                    let mut updated_info = info.clone();
+                    updated_info.probe_state = probe_state.clone();
+                    self.write_pid_file(&runtime, &updated_info)?;
+
+                    (health, probe_state.last_latency_ms, probe_state.last_error)
                 } else {
-                    "unknown".to_string()
+                    match self.sample_health(&runtime, &HealthThresholds::default()) {
+                        Ok(status) => (format!("{status:?}").to_ascii_lowercase(), None, None),
+                        Err(_) => ("unknown".to_string(), None, None),
+                    }
+                };
+                let container_state = match (info.mode, &info.oci_container_id, &info.container_id) {
+                    (ExecutionMode::Oci, Some(container_id), _) => Self::oci_runtime_available()
+                        .and_then(|oci_runtime| oci_container_state(oci_runtime, container_id)),
+                    (ExecutionMode::Docker, _, Some(container_id)) => {
+                        docker_container_state(container_id)
+                    }
+                    _ => None,
                 };
                 statuses.push(RuntimeProcessStatus {
                     runtime,
@@ -222,6 +844,10 @@ impl ProcessManager {
                     mode: info.mode,
                     log_path: info.log_path,
                     health,
+                    container_state,
+                    crash_history,
+                    probe_latency_ms,
+                    probe_error,
                 });
             }
         }
@@ -231,6 +857,20 @@ impl ProcessManager {
     }
 
     pub fn tail_logs(&self, runtime: &str, lines: usize) -> Result<String> {
+        if let Some(info) = self.read_pid_file(runtime)? {
+            if let (ExecutionMode::Docker, Some(container_id)) =
+                (info.mode, info.container_id.as_ref())
+            {
+                let output = Command::new("docker")
+                    .args(["logs", "--tail", &lines.to_string(), container_id])
+                    .output()
+                    .with_context(|| format!("tailing docker logs for {container_id}"))?;
+                let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+                combined.push_str(&String::from_utf8_lossy(&output.stderr));
+                return Ok(combined);
+            }
+        }
+
         let log_path = self.log_dir.join(format!("{runtime}.log"));
         if !log_path.exists() {
             return Ok(String::new());
@@ -241,6 +881,186 @@ impl ProcessManager {
         Ok(rows[start..].join("\n"))
     }
 
+    /// Queues `message` for a running runtime by appending it to that
+    /// runtime's inbox file (`<state_dir>/<runtime>.inbox`), one JSON line
+    /// per message. This is the same mechanism a locally driven `send` would
+    /// use, so remote tunnel sessions queue messages identically to local ones.
+    pub fn send_message(&self, runtime: &str, message: &str) -> Result<()> {
+        if self.read_pid_file(runtime)?.is_none() {
+            return Err(anyhow!("{runtime} is not running"));
+        }
+        let inbox_path = self.state_dir.join(format!("{runtime}.inbox"));
+        let line = serde_json::json!({
+            "queued_at_unix_ms": now_ms(),
+            "message": message,
+        });
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&inbox_path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    /// Looks up the OS pid ClawDen has on file for `runtime`, if it's tracked
+    /// and still alive.
+    pub fn pid_for_runtime(&self, runtime: &str) -> Result<Option<u32>> {
+        Ok(self
+            .read_pid_file(runtime)?
+            .filter(|info| is_pid_running(info.pid))
+            .map(|info| info.pid))
+    }
+
+    /// Samples real CPU/RSS/queue-depth for `runtime`'s tracked pid, for use
+    /// by `ClawAdapter::metrics` implementations instead of hardcoded zeros.
+    pub fn sample_metrics(&self, runtime: &str) -> Result<crate::AgentMetrics> {
+        let pid = self
+            .pid_for_runtime(runtime)?
+            .ok_or_else(|| anyhow!("{runtime} is not running"))?;
+        let (cpu_percent, memory_mb) = sample_proc_stats(pid)?;
+        let queue_depth = sample_queue_depth(&self.log_dir, runtime);
+        Ok(crate::AgentMetrics {
+            cpu_percent,
+            memory_mb,
+            queue_depth,
+        })
+    }
+
+    /// Derives a [`crate::HealthStatus`] from a liveness check plus sampled
+    /// CPU/RSS against `thresholds`.
+    pub fn sample_health(
+        &self,
+        runtime: &str,
+        thresholds: &HealthThresholds,
+    ) -> Result<crate::HealthStatus> {
+        let Some(pid) = self.pid_for_runtime(runtime)? else {
+            return Ok(crate::HealthStatus::Unhealthy);
+        };
+        if let Some(info) = self.read_pid_file(runtime)? {
+            if let Some(url) = &info.health_url {
+                if !health_check_ok(url) {
+                    return Ok(crate::HealthStatus::Unhealthy);
+                }
+            }
+        }
+
+        let (cpu_percent, memory_mb) = sample_proc_stats(pid)?;
+        Ok(
+            if cpu_percent >= thresholds.cpu_unhealthy_percent
+                || memory_mb >= thresholds.memory_unhealthy_mb
+            {
+                crate::HealthStatus::Unhealthy
+            } else if cpu_percent >= thresholds.cpu_degraded_percent
+                || memory_mb >= thresholds.memory_degraded_mb
+            {
+                crate::HealthStatus::Degraded
+            } else {
+                crate::HealthStatus::Healthy
+            },
+        )
+    }
+
+    /// Watches `watch_paths` (typically the runtime executable plus its
+    /// config/skill directories) and performs a supervised restart whenever
+    /// a debounced burst of filesystem events settles, so iterating on an
+    /// agent doesn't require manual `stop`/`start`. Blocks the calling
+    /// thread forever; callers that want this alongside a foreground
+    /// process should run it on its own thread.
+    pub fn watch(
+        &self,
+        runtime: &str,
+        executable: &Path,
+        spec: &RuntimeLaunchSpec,
+        watch_paths: &[PathBuf],
+    ) -> Result<()> {
+        use notify::Watcher;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        })
+        .context("creating filesystem watcher")?;
+
+        for path in watch_paths {
+            watcher
+                .watch(path, notify::RecursiveMode::Recursive)
+                .with_context(|| format!("watching {}", path.display()))?;
+        }
+
+        loop {
+            let Ok(first) = rx.recv() else {
+                return Ok(());
+            };
+            if first.is_err() {
+                continue;
+            }
+            // Drain anything else that arrives within the debounce window so
+            // a multi-file save collapses into a single restart.
+            while rx.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+
+            self.supervised_restart(runtime, executable, spec)?;
+        }
+    }
+
+    /// Spawns a replacement instance of `runtime` under a temporary pidfile
+    /// slot and polls its `health_url` until healthy before tearing down the
+    /// previous instance and promoting the replacement into `runtime`'s real
+    /// pidfile slot. A replacement that never becomes healthy is stopped and
+    /// the previous instance is left running untouched.
+    fn supervised_restart(
+        &self,
+        runtime: &str,
+        executable: &Path,
+        spec: &RuntimeLaunchSpec,
+    ) -> Result<()> {
+        let staging_runtime = format!("{runtime}.next");
+        let _ = self.stop(&staging_runtime);
+
+        let info = self.start_direct(&staging_runtime, executable, spec)?;
+
+        let healthy = match &info.health_url {
+            Some(url) => wait_until_healthy(url, WATCH_HEALTH_TIMEOUT),
+            None => true,
+        };
+
+        if !healthy {
+            let _ = self.stop(&staging_runtime);
+            anyhow::bail!(
+                "replacement instance of {runtime} failed its health check; keeping the previous instance running"
+            );
+        }
+
+        let _ = self.stop(runtime);
+        self.promote_staging(&staging_runtime, runtime)?;
+        Ok(())
+    }
+
+    /// Renames the staging pidfile/log written by [`Self::supervised_restart`]
+    /// into `runtime`'s real slot.
+    fn promote_staging(&self, staging_runtime: &str, runtime: &str) -> Result<()> {
+        let Some(mut info) = self.read_pid_file(staging_runtime)? else {
+            anyhow::bail!("no pidfile found for {staging_runtime} to promote");
+        };
+
+        let staging_log = self.log_dir.join(format!("{staging_runtime}.log"));
+        let final_log = self.log_dir.join(format!("{runtime}.log"));
+        if staging_log.exists() {
+            fs::rename(&staging_log, &final_log).with_context(|| {
+                format!(
+                    "renaming {} to {}",
+                    staging_log.display(),
+                    final_log.display()
+                )
+            })?;
+        }
+
+        info.runtime = runtime.to_string();
+        info.log_path = final_log;
+        self.write_pid_file(runtime, &info)?;
+        self.remove_pid_file(staging_runtime)?;
+        Ok(())
+    }
+
     fn write_pid_file(&self, runtime: &str, info: &ProcessInfo) -> Result<()> {
         let path = self.pid_file(runtime);
         let body = serde_json::to_string_pretty(info)?;
@@ -272,6 +1092,140 @@ impl ProcessManager {
     }
 }
 
+fn command_on_path(command: &str) -> bool {
+    Command::new("which")
+        .arg(command)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Builds a minimal OCI runtime-spec `config.json` for running a single
+/// executable under `crun`/`runc`/`youki`: the host filesystem as a
+/// read-only root (no image to unpack), a bind mount for `~/.clawden` state,
+/// and a cgroup resource block derived from the requested limits.
+fn build_oci_config(
+    executable: &Path,
+    args: &[String],
+    clawden_home: &Path,
+    limits: &ResourceLimits,
+) -> Result<serde_json::Value> {
+    let mut process_args = vec![executable.display().to_string()];
+    process_args.extend(args.iter().cloned());
+
+    let mut resources = serde_json::Map::new();
+    if let Some(cores) = limits.cpu_cores {
+        resources.insert(
+            "cpu".to_string(),
+            serde_json::json!({ "quota": (cores * 100_000.0) as i64, "period": 100_000 }),
+        );
+    }
+    if let Some(memory_mb) = limits.memory_mb {
+        resources.insert(
+            "memory".to_string(),
+            serde_json::json!({ "limit": memory_mb * 1024 * 1024 }),
+        );
+    }
+
+    Ok(serde_json::json!({
+        "ociVersion": "1.0.2",
+        "process": {
+            "terminal": false,
+            "args": process_args,
+            "cwd": "/",
+            "env": ["PATH=/usr/bin:/bin"],
+        },
+        "root": { "path": "/", "readonly": true },
+        "mounts": [
+            {
+                "destination": clawden_home.display().to_string(),
+                "type": "bind",
+                "source": clawden_home.display().to_string(),
+                "options": ["bind", "rw"]
+            }
+        ],
+        "linux": {
+            "resources": resources,
+            "namespaces": [
+                { "type": "pid" },
+                { "type": "mount" }
+            ]
+        }
+    }))
+}
+
+fn run_oci(oci_runtime: &str, verb_and_flag: &[&str], bundle_dir: &Path, container_id: &str) -> Result<()> {
+    let mut command = Command::new(oci_runtime);
+    command.args(verb_and_flag);
+    if verb_and_flag.contains(&"--bundle") {
+        command.arg(bundle_dir);
+    }
+    command.arg(container_id);
+    let status = command
+        .status()
+        .with_context(|| format!("failed to run {oci_runtime} {verb_and_flag:?}"))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "{oci_runtime} {:?} {container_id} exited with {status}",
+            verb_and_flag
+        ));
+    }
+    Ok(())
+}
+
+fn oci_container_state(oci_runtime: &str, container_id: &str) -> Option<String> {
+    let output = Command::new(oci_runtime)
+        .args(["state", container_id])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return Some("stopped".to_string());
+    }
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    value
+        .get("status")
+        .and_then(|s| s.as_str())
+        .map(str::to_string)
+}
+
+fn oci_container_pid(oci_runtime: &str, container_id: &str) -> Result<u32> {
+    let output = Command::new(oci_runtime)
+        .args(["state", container_id])
+        .output()
+        .with_context(|| format!("querying state for OCI container {container_id}"))?;
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("invalid state response for {container_id}"))?;
+    value
+        .get("pid")
+        .and_then(|p| p.as_u64())
+        .map(|p| p as u32)
+        .ok_or_else(|| anyhow!("OCI container {container_id} reported no pid"))
+}
+
+fn docker_container_pid(container_id: &str) -> Result<u32> {
+    let output = Command::new("docker")
+        .args(["inspect", "--format", "{{.State.Pid}}", container_id])
+        .output()
+        .with_context(|| format!("inspecting docker container {container_id}"))?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid pid reported for docker container {container_id}"))
+}
+
+fn docker_container_state(container_id: &str) -> Option<String> {
+    let output = Command::new("docker")
+        .args(["inspect", "--format", "{{.State.Status}}", container_id])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return Some("stopped".to_string());
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 fn is_pid_running(pid: u32) -> bool {
     Command::new("kill")
         .args(["-0", &pid.to_string()])
@@ -304,6 +1258,25 @@ fn split_restart_policy(args: &[String]) -> (Vec<String>, Option<String>) {
     (filtered, restart_policy)
 }
 
+/// `OsString` counterpart of [`split_restart_policy`], used by
+/// `start_direct`/`start_docker` now that they consume a
+/// [`RuntimeLaunchSpec`] instead of `&[String]`. Non-UTF-8 args can never
+/// match `--restart=...` and are passed through unfiltered.
+fn split_restart_policy_os(args: &[OsString]) -> (Vec<OsString>, Option<String>) {
+    let mut filtered = Vec::new();
+    let mut restart_policy = None;
+
+    for arg in args {
+        if let Some(policy) = arg.to_str().and_then(|arg| arg.strip_prefix("--restart=")) {
+            restart_policy = Some(policy.to_string());
+            continue;
+        }
+        filtered.push(arg.clone());
+    }
+
+    (filtered, restart_policy)
+}
+
 fn runtime_health_url(runtime: &str) -> Option<String> {
     let runtime_key = runtime.to_ascii_uppercase().replace('-', "_");
     let url_key = format!("CLAWDEN_HEALTH_URL_{runtime_key}");
@@ -324,63 +1297,303 @@ fn runtime_health_url(runtime: &str) -> Option<String> {
 }
 
 fn health_check_ok(url: &str) -> bool {
-    Command::new("curl")
-        .args(["-fsS", "--max-time", "2", url])
+    let probe = HealthProbe::Http {
+        url: url.to_string(),
+        expected_status: default_expected_status(),
+    };
+    run_probe(&probe, Duration::from_millis(default_probe_timeout_ms())).success
+}
+
+/// Resolves the probe actually used for `runtime`: `info.probe` if set,
+/// otherwise `info.health_url` wrapped as an implicit single-attempt HTTP
+/// probe (with default interval/timeout/thresholds) so runtimes that
+/// predate native probing keep working unchanged.
+fn effective_probe_config(info: &ProcessInfo) -> Option<ProbeConfig> {
+    if let Some(probe) = &info.probe {
+        return Some(probe.clone());
+    }
+    let url = info.health_url.as_ref()?;
+    Some(ProbeConfig {
+        probe: HealthProbe::Http {
+            url: url.clone(),
+            expected_status: default_expected_status(),
+        },
+        interval_ms: default_probe_interval_ms(),
+        timeout_ms: default_probe_timeout_ms(),
+        consecutive_successes: default_probe_threshold(),
+        consecutive_failures: default_probe_threshold(),
+    })
+}
+
+/// Outcome of a single [`HealthProbe`] attempt: whether it succeeded, how
+/// long it took, and — on failure — why, for [`RuntimeProcessStatus`]
+/// diagnostics.
+struct ProbeResult {
+    success: bool,
+    latency_ms: u64,
+    error: Option<String>,
+}
+
+/// Runs `probe` once against `timeout`, dispatching to the protocol-specific
+/// prober. Replaces the old `curl`-shelled check, which couldn't distinguish
+/// "connection refused" from "wrong status" and only ever spoke HTTP.
+fn run_probe(probe: &HealthProbe, timeout: Duration) -> ProbeResult {
+    let started = SystemTime::now();
+    let (success, error) = match probe {
+        HealthProbe::Http {
+            url,
+            expected_status,
+        } => probe_http(url, expected_status, timeout),
+        HealthProbe::Tcp { addr } => probe_tcp(addr, timeout),
+        HealthProbe::Exec { command, args } => probe_exec(command, args, timeout),
+    };
+    let latency_ms = started.elapsed().map(|d| d.as_millis() as u64).unwrap_or(0);
+    ProbeResult {
+        success,
+        latency_ms,
+        error,
+    }
+}
+
+fn probe_http(url: &str, expected_status: &[u16], timeout: Duration) -> (bool, Option<String>) {
+    let client = match reqwest::blocking::Client::builder().timeout(timeout).build() {
+        Ok(client) => client,
+        Err(err) => return (false, Some(err.to_string())),
+    };
+    match client.get(url).send() {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            if expected_status.contains(&status) {
+                (true, None)
+            } else {
+                (false, Some(format!("unexpected status {status}")))
+            }
+        }
+        Err(err) => (false, Some(err.to_string())),
+    }
+}
+
+fn probe_tcp(addr: &str, timeout: Duration) -> (bool, Option<String>) {
+    let resolved = match addr.to_socket_addrs() {
+        Ok(mut addrs) => addrs.next(),
+        Err(err) => return (false, Some(err.to_string())),
+    };
+    let Some(resolved) = resolved else {
+        return (false, Some(format!("could not resolve {addr}")));
+    };
+    match std::net::TcpStream::connect_timeout(&resolved, timeout) {
+        Ok(_) => (true, None),
+        Err(err) => (false, Some(err.to_string())),
+    }
+}
+
+fn probe_exec(command: &str, args: &[String], timeout: Duration) -> (bool, Option<String>) {
+    let mut child = match Command::new(command)
+        .args(args)
         .stdout(Stdio::null())
         .stderr(Stdio::null())
-        .status()
-        .map(|status| status.success())
-        .unwrap_or(false)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => return (false, Some(err.to_string())),
+    };
+
+    let deadline = SystemTime::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                return if status.success() {
+                    (true, None)
+                } else {
+                    (false, Some(format!("exited with {status}")))
+                };
+            }
+            Ok(None) => {
+                if SystemTime::now() >= deadline {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return (false, Some(format!("timed out after {}ms", timeout.as_millis())));
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(err) => return (false, Some(err.to_string())),
+        }
+    }
+}
+
+/// Opens a TCP listening socket with `SO_REUSEADDR`/`SO_REUSEPORT` set and
+/// `FD_CLOEXEC` cleared, suitable for handing to a child process across
+/// `exec`. `SO_REUSEPORT` is what lets this bind succeed on the same address
+/// the previous instance is still actively listening on — without it,
+/// [`ProcessManager::graceful_restart`]'s overlap-then-replace handoff would
+/// fail with `EADDRINUSE` every time, since `SO_REUSEADDR` alone doesn't
+/// permit two live listeners on the same address on Linux. Returns the raw
+/// fd rather than a `std::net::TcpListener` so it isn't closed when this
+/// call returns; the caller hands it to the replacement child and then lets
+/// it leak in this (short-lived, one-shot CLI) process rather than holding
+/// it open for reuse — see `graceful_restart`'s doc comment for why fds
+/// can't be round-tripped across separate invocations.
+fn bind_inheritable_listener(addr: &SocketAddr) -> Result<i32> {
+    let domain = if addr.is_ipv6() {
+        libc::AF_INET6
+    } else {
+        libc::AF_INET
+    };
+    let fd = unsafe { libc::socket(domain, libc::SOCK_STREAM, 0) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error()).context("socket()");
+    }
+
+    let reuse: libc::c_int = 1;
+    let rc = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_REUSEADDR,
+            &reuse as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if rc != 0 {
+        unsafe { libc::close(fd) };
+        return Err(std::io::Error::last_os_error()).context("setsockopt(SO_REUSEADDR)");
+    }
+
+    let reuse_port_rc = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_REUSEPORT,
+            &reuse as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if reuse_port_rc != 0 {
+        unsafe { libc::close(fd) };
+        return Err(std::io::Error::last_os_error()).context("setsockopt(SO_REUSEPORT)");
+    }
+
+    let bind_rc = match addr {
+        SocketAddr::V4(v4) => {
+            let sin = libc::sockaddr_in {
+                sin_family: libc::AF_INET as libc::sa_family_t,
+                sin_port: v4.port().to_be(),
+                sin_addr: libc::in_addr {
+                    s_addr: u32::from_ne_bytes(v4.ip().octets()),
+                },
+                sin_zero: [0; 8],
+            };
+            unsafe {
+                libc::bind(
+                    fd,
+                    &sin as *const libc::sockaddr_in as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                )
+            }
+        }
+        SocketAddr::V6(_) => {
+            unsafe { libc::close(fd) };
+            anyhow::bail!("graceful restart only supports IPv4 bind addresses");
+        }
+    };
+    if bind_rc != 0 {
+        unsafe { libc::close(fd) };
+        return Err(std::io::Error::last_os_error()).context("bind()");
+    }
+
+    if unsafe { libc::listen(fd, 128) } != 0 {
+        unsafe { libc::close(fd) };
+        return Err(std::io::Error::last_os_error()).context("listen()");
+    }
+
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) } < 0 {
+        unsafe { libc::close(fd) };
+        return Err(std::io::Error::last_os_error()).context("clearing FD_CLOEXEC");
+    }
+
+    Ok(fd)
+}
+
+/// Polls `url` with [`health_check_ok`] until it succeeds or `timeout`
+/// elapses, used by [`ProcessManager::supervised_restart`] to gate tearing
+/// down the previous instance on the replacement actually coming up.
+fn wait_until_healthy(url: &str, timeout: Duration) -> bool {
+    let deadline = SystemTime::now() + timeout;
+    loop {
+        if health_check_ok(url) {
+            return true;
+        }
+        if SystemTime::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Reads `/proc/<pid>/stat` twice, a short interval apart, to turn the
+/// kernel's cumulative utime+stime tick counters into an instantaneous CPU
+/// percentage, and `/proc/<pid>/status` for current RSS.
+fn sample_proc_stats(pid: u32) -> Result<(f32, f32)> {
+    let ticks_per_sec = 100.0; // USER_HZ is 100 on every platform ClawDen targets.
+    let first = read_proc_ticks(pid)?;
+    thread::sleep(Duration::from_millis(100));
+    let second = read_proc_ticks(pid)?;
+
+    let tick_delta = second.saturating_sub(first) as f64;
+    let cpu_percent = ((tick_delta / ticks_per_sec) / 0.1 * 100.0) as f32;
+    let memory_mb = read_proc_rss_mb(pid)?;
+    Ok((cpu_percent, memory_mb))
+}
+
+fn read_proc_ticks(pid: u32) -> Result<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat"))
+        .with_context(|| format!("reading /proc/{pid}/stat"))?;
+    // Fields are space-separated; the command name (field 2) may itself
+    // contain spaces, so resume counting from its closing ')'.
+    let after_comm = stat
+        .rsplit_once(')')
+        .map(|(_, rest)| rest)
+        .unwrap_or(&stat);
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    // utime is field 14, stime is field 15 overall; relative to `after_comm`
+    // (which starts at field 3) that's indices 11 and 12.
+    let utime: u64 = fields.get(11).and_then(|s| s.parse().ok()).unwrap_or(0);
+    let stime: u64 = fields.get(12).and_then(|s| s.parse().ok()).unwrap_or(0);
+    Ok(utime + stime)
+}
+
+fn read_proc_rss_mb(pid: u32) -> Result<f32> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status"))
+        .with_context(|| format!("reading /proc/{pid}/status"))?;
+    for line in status.lines() {
+        if let Some(rest) = line.strip_prefix("VmRSS:") {
+            let kb: f64 = rest
+                .trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse()
+                .unwrap_or(0.0);
+            return Ok((kb / 1024.0) as f32);
+        }
+    }
+    Ok(0.0)
 }
 
-fn build_restart_supervisor_script() -> &'static str {
-    r#"#!/usr/bin/env sh
-set -u
-
-exec_path="$1"
-log_path="$2"
-audit_path="$3"
-runtime_name="$4"
-shift 4
-
-backoff=1
-child_pid=""
-
-cleanup() {
-    if [ -n "$child_pid" ]; then
-        kill -TERM "$child_pid" 2>/dev/null || true
-        sleep 2
-        kill -KILL "$child_pid" 2>/dev/null || true
-    fi
-    exit 0
-}
-
-trap cleanup INT TERM
-
-while true; do
-    "$exec_path" "$@" >>"$log_path" 2>&1 &
-    child_pid="$!"
-    wait "$child_pid"
-    exit_code="$?"
-    child_pid=""
-
-    if [ "$exit_code" -eq 0 ]; then
-        exit 0
-    fi
-
-    ts="$(date +%s)000"
-    printf "%s\truntime.crash\t%s\texit:%s\n" "$ts" "$runtime_name" "$exit_code" >>"$audit_path"
-    printf "%s\truntime.restart\t%s\tbackoff:%s\n" "$ts" "$runtime_name" "$backoff" >>"$audit_path"
-
-    sleep "$backoff"
-    if [ "$backoff" -lt 30 ]; then
-        backoff=$((backoff * 2))
-        if [ "$backoff" -gt 30 ]; then
-            backoff=30
-        fi
-    fi
-done
-"#
+/// Looks for a `queue_depth=<n>` marker on the most recent matching log line
+/// as a lightweight substitute for a runtime-specific control endpoint.
+fn sample_queue_depth(log_dir: &Path, runtime: &str) -> u32 {
+    let log_path = log_dir.join(format!("{runtime}.log"));
+    let Ok(content) = fs::read_to_string(log_path) else {
+        return 0;
+    };
+    content
+        .lines()
+        .rev()
+        .find_map(|line| line.split("queue_depth=").nth(1))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
 }
 
 fn clawden_root_dir() -> Result<PathBuf> {
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2_hmac;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const DEFAULT_ITERATIONS: u32 = 4096;
+const NONCE_BYTES: usize = 18;
+
+/// What's stored for a principal after enrollment — never the password or
+/// anything that can be replayed to derive it, per RFC 5802 §3: only the
+/// salt/iteration count used to re-derive `SaltedPassword`, and the two
+/// keys computed one-way from it.
+#[derive(Debug, Clone)]
+pub struct ScramCredentials {
+    pub salt: Vec<u8>,
+    pub iterations: u32,
+    pub stored_key: Vec<u8>,
+    pub server_key: Vec<u8>,
+}
+
+impl ScramCredentials {
+    pub fn enroll(password: &str) -> Self {
+        let mut salt = vec![0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self::enroll_with_salt(password, salt, DEFAULT_ITERATIONS)
+    }
+
+    fn enroll_with_salt(password: &str, salt: Vec<u8>, iterations: u32) -> Self {
+        let salted_password = salted_password(password.as_bytes(), &salt, iterations);
+        let client_key = hmac_sha256(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(client_key).to_vec();
+        let server_key = hmac_sha256(&salted_password, b"Server Key");
+        Self {
+            salt,
+            iterations,
+            stored_key,
+            server_key,
+        }
+    }
+}
+
+fn salted_password(password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+    let mut output = vec![0u8; 32];
+    pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut output);
+    output
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// In-memory table of enrolled principals, keyed by username. A real
+/// deployment would back this with persistent storage; swapping that in
+/// doesn't change the exchange below since it only ever touches derived
+/// keys, never the password itself.
+#[derive(Default)]
+pub struct CredentialStore {
+    principals: Mutex<HashMap<String, ScramCredentials>>,
+}
+
+impl CredentialStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enroll(&self, username: &str, password: &str) {
+        let credentials = ScramCredentials::enroll(password);
+        if let Ok(mut principals) = self.principals.lock() {
+            principals.insert(username.to_string(), credentials);
+        }
+    }
+
+    fn lookup(&self, username: &str) -> Option<ScramCredentials> {
+        self.principals
+            .lock()
+            .ok()
+            .and_then(|principals| principals.get(username).cloned())
+    }
+}
+
+/// Server-side state held between `client-first` and `client-final`: enough
+/// of the exchange transcript to reconstruct `AuthMessage` and the
+/// credentials to verify against, without trusting anything the client
+/// resends in its final message besides the proof itself.
+pub struct ServerFirst {
+    pub username: String,
+    pub message: String,
+    combined_nonce: String,
+    client_first_bare: String,
+    credentials: ScramCredentials,
+}
+
+/// Parses a SCRAM `client-first-message-bare` of the form
+/// `n=<username>,r=<client-nonce>` (no `n,,` / `p=` gs2 header — channel
+/// binding is not offered).
+fn parse_client_first(message: &str) -> Option<(String, String)> {
+    let mut username = None;
+    let mut nonce = None;
+    for field in message.split(',') {
+        if let Some(value) = field.strip_prefix("n=") {
+            username = Some(value.to_string());
+        } else if let Some(value) = field.strip_prefix("r=") {
+            nonce = Some(value.to_string());
+        }
+    }
+    Some((username?, nonce?))
+}
+
+/// Begins a SCRAM-SHA-256 exchange: looks up the principal, mixes in a
+/// server nonce, and returns the `server-first-message` to send back
+/// verbatim to the client.
+pub fn server_first(
+    credentials: &CredentialStore,
+    client_first_bare: &str,
+) -> Result<ServerFirst, String> {
+    let (username, client_nonce) =
+        parse_client_first(client_first_bare).ok_or_else(|| "malformed client-first-message".to_string())?;
+    let credentials = credentials
+        .lookup(&username)
+        .ok_or_else(|| format!("unknown principal: {username}"))?;
+
+    let mut server_nonce_bytes = vec![0u8; NONCE_BYTES];
+    rand::thread_rng().fill_bytes(&mut server_nonce_bytes);
+    let combined_nonce = format!("{client_nonce}{}", BASE64.encode(server_nonce_bytes));
+
+    let message = format!(
+        "r={combined_nonce},s={},i={}",
+        BASE64.encode(&credentials.salt),
+        credentials.iterations
+    );
+
+    Ok(ServerFirst {
+        username,
+        message,
+        combined_nonce,
+        client_first_bare: client_first_bare.to_string(),
+        credentials,
+    })
+}
+
+/// A verified SCRAM exchange's mutual-auth proof, returned to the client so
+/// it can confirm the server also knows `ServerKey` without ever sending it.
+pub struct ServerSignature(pub Vec<u8>);
+
+impl ServerSignature {
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(&self.0)
+    }
+}
+
+/// Completes the exchange: recomputes `ClientSignature` from the stored
+/// `StoredKey` and the full transcript, recovers the claimed `ClientKey` by
+/// XOR-ing it out of `client_proof`, and checks that hashing it back down
+/// reproduces `StoredKey` — the client could only have produced a proof
+/// that survives this check if it knew the password used at enrollment.
+pub fn verify_client_proof(
+    first: &ServerFirst,
+    client_final_without_proof: &str,
+    client_proof: &[u8],
+) -> Result<ServerSignature, String> {
+    let expected_nonce_field = format!("r={}", first.combined_nonce);
+    if !client_final_without_proof
+        .split(',')
+        .any(|field| field == expected_nonce_field)
+    {
+        return Err("client-final-message nonce does not match server-first-message".to_string());
+    }
+
+    let auth_message = format!(
+        "{},{},{}",
+        first.client_first_bare, first.message, client_final_without_proof
+    );
+
+    let client_signature = hmac_sha256(&first.credentials.stored_key, auth_message.as_bytes());
+    if client_proof.len() != client_signature.len() {
+        return Err("invalid client proof length".to_string());
+    }
+    let recovered_client_key = xor(client_proof, &client_signature);
+    let recomputed_stored_key = Sha256::digest(&recovered_client_key).to_vec();
+
+    // Constant-time comparison: this is the proof check that gates a
+    // successful SASL login, so a byte-at-a-time `!=` would let a
+    // network-timing attacker narrow down the stored key one byte at a time.
+    if recomputed_stored_key
+        .ct_eq(&first.credentials.stored_key)
+        .unwrap_u8()
+        == 0
+    {
+        return Err("client proof does not match stored key".to_string());
+    }
+
+    let server_signature = hmac_sha256(&first.credentials.server_key, auth_message.as_bytes());
+    Ok(ServerSignature(server_signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs a full client+server exchange using this module's own
+    /// primitives to play the client side, the way a real SCRAM client
+    /// library would — proving the server half accepts a conformant client.
+    #[test]
+    fn full_exchange_succeeds_with_correct_password() {
+        let store = CredentialStore::new();
+        store.enroll("alice", "hunter2");
+
+        let client_first_bare = "n=alice,r=clientnonce123";
+        let first = server_first(&store, client_first_bare).expect("known principal");
+
+        let credentials = ScramCredentials::enroll_with_salt(
+            "hunter2",
+            store.lookup("alice").unwrap().salt,
+            store.lookup("alice").unwrap().iterations,
+        );
+        let client_final_without_proof = format!("c=biws,r={}", first.combined_nonce);
+        let auth_message = format!(
+            "{client_first_bare},{},{client_final_without_proof}",
+            first.message
+        );
+        let client_signature = hmac_sha256(&credentials.stored_key, auth_message.as_bytes());
+        let salted_password = salted_password(b"hunter2", &credentials.salt, credentials.iterations);
+        let client_key = hmac_sha256(&salted_password, b"Client Key");
+        let client_proof = xor(&client_key, &client_signature);
+
+        let server_signature =
+            verify_client_proof(&first, &client_final_without_proof, &client_proof)
+                .expect("correct password should verify");
+
+        let expected_server_signature = hmac_sha256(&credentials.server_key, auth_message.as_bytes());
+        assert_eq!(server_signature.0, expected_server_signature);
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let store = CredentialStore::new();
+        store.enroll("alice", "hunter2");
+
+        let client_first_bare = "n=alice,r=clientnonce123";
+        let first = server_first(&store, client_first_bare).expect("known principal");
+        let client_final_without_proof = format!("c=biws,r={}", first.combined_nonce);
+
+        // Derive keys from the wrong password against alice's real salt.
+        let salted_password = salted_password(
+            b"wrong-password",
+            &store.lookup("alice").unwrap().salt,
+            store.lookup("alice").unwrap().iterations,
+        );
+        let client_key = hmac_sha256(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(&client_key).to_vec();
+        let auth_message = format!(
+            "{client_first_bare},{},{client_final_without_proof}",
+            first.message
+        );
+        let client_signature = hmac_sha256(&stored_key, auth_message.as_bytes());
+        let client_proof = xor(&client_key, &client_signature);
+
+        assert!(verify_client_proof(&first, &client_final_without_proof, &client_proof).is_err());
+    }
+
+    #[test]
+    fn unknown_principal_is_rejected() {
+        let store = CredentialStore::new();
+        assert!(server_first(&store, "n=ghost,r=abc123").is_err());
+    }
+}
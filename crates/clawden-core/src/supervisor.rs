@@ -0,0 +1,189 @@
+//! Rust-native replacement for the generated shell restart-supervisor.
+//! `ProcessManager::start_direct` spawns `clawden supervise <runtime>`
+//! (this module's [`run`]) as the child instead of a `sh` script wrapping
+//! a `while true` loop, so crash-loop detection and backoff are plain,
+//! testable Rust rather than shell arithmetic.
+
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// One line of `audit_path`'s newline-delimited JSON crash history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashRecord {
+    pub event: String,
+    pub runtime: String,
+    pub pid: Option<u32>,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub backoff_ms: Option<u64>,
+    pub restart_count: u64,
+    pub timestamp_unix_ms: u64,
+}
+
+const MAX_BACKOFF_MS: u64 = 30_000;
+const CIRCUIT_WINDOW_MS: u64 = 60_000;
+const CIRCUIT_MAX_RESTARTS: usize = 5;
+
+/// Runs `exec_path` to completion, restarting it on a non-zero exit with
+/// jittered exponential backoff (doubling up to the same 30s cap the old
+/// shell script used) until it either exits successfully or crashes more
+/// than [`CIRCUIT_MAX_RESTARTS`] times within [`CIRCUIT_WINDOW_MS`] — at
+/// which point the circuit opens: a `runtime.circuit_open` record is
+/// appended and this returns `Err` instead of restarting forever.
+pub fn run(
+    runtime_name: &str,
+    exec_path: &Path,
+    args: &[String],
+    log_path: &Path,
+    audit_path: &Path,
+) -> Result<()> {
+    let mut backoff_ms: u64 = 1_000;
+    let mut restart_count: u64 = 0;
+    let mut recent_restarts: VecDeque<u64> = VecDeque::new();
+
+    loop {
+        let stdout = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path)
+            .with_context(|| format!("opening runtime log file {}", log_path.display()))?;
+        let stderr = stdout.try_clone()?;
+
+        let mut child = Command::new(exec_path)
+            .args(args)
+            .stdout(Stdio::from(stdout))
+            .stderr(Stdio::from(stderr))
+            .spawn()
+            .with_context(|| format!("failed to spawn {}", exec_path.display()))?;
+
+        let pid = child.id();
+        let status = child
+            .wait()
+            .with_context(|| format!("waiting on {}", exec_path.display()))?;
+
+        if status.success() {
+            return Ok(());
+        }
+
+        let now = now_ms();
+        append_crash_record(
+            audit_path,
+            &CrashRecord {
+                event: "runtime.crash".to_string(),
+                runtime: runtime_name.to_string(),
+                pid: Some(pid),
+                exit_code: status.code(),
+                signal: status.signal(),
+                backoff_ms: None,
+                restart_count,
+                timestamp_unix_ms: now,
+            },
+        )?;
+
+        recent_restarts.push_back(now);
+        while recent_restarts
+            .front()
+            .is_some_and(|ts| now.saturating_sub(*ts) > CIRCUIT_WINDOW_MS)
+        {
+            recent_restarts.pop_front();
+        }
+
+        if recent_restarts.len() > CIRCUIT_MAX_RESTARTS {
+            append_crash_record(
+                audit_path,
+                &CrashRecord {
+                    event: "runtime.circuit_open".to_string(),
+                    runtime: runtime_name.to_string(),
+                    pid: Some(pid),
+                    exit_code: status.code(),
+                    signal: status.signal(),
+                    backoff_ms: None,
+                    restart_count,
+                    timestamp_unix_ms: now_ms(),
+                },
+            )?;
+            anyhow::bail!(
+                "{runtime_name} crashed {} times within {}s; circuit open",
+                recent_restarts.len(),
+                CIRCUIT_WINDOW_MS / 1_000
+            );
+        }
+
+        restart_count += 1;
+        let jittered = jittered_backoff(backoff_ms);
+        append_crash_record(
+            audit_path,
+            &CrashRecord {
+                event: "runtime.restart".to_string(),
+                runtime: runtime_name.to_string(),
+                pid: Some(pid),
+                exit_code: status.code(),
+                signal: status.signal(),
+                backoff_ms: Some(jittered),
+                restart_count,
+                timestamp_unix_ms: now_ms(),
+            },
+        )?;
+
+        thread_sleep_ms(jittered);
+        backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
+    }
+}
+
+/// `backoff/2 + rand(0..backoff/2)`, so several runtimes crashing at the
+/// same instant don't all wake up and restart in lockstep.
+fn jittered_backoff(backoff_ms: u64) -> u64 {
+    let half = backoff_ms / 2;
+    if half == 0 {
+        return backoff_ms;
+    }
+    half + rand::thread_rng().gen_range(0..half)
+}
+
+fn thread_sleep_ms(ms: u64) {
+    std::thread::sleep(Duration::from_millis(ms));
+}
+
+fn append_crash_record(audit_path: &Path, record: &CrashRecord) -> Result<()> {
+    let line = serde_json::to_string(record)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_path)
+        .with_context(|| format!("opening audit log {}", audit_path.display()))?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// Reads up to `limit` of the most recent crash/restart/circuit-open
+/// records for `runtime` out of `audit_path`'s newline-delimited JSON,
+/// silently skipping any non-JSON (tab-separated) lines from other audit
+/// writers sharing the same file.
+pub fn read_crash_history(audit_path: &Path, runtime: &str, limit: usize) -> Vec<CrashRecord> {
+    let Ok(content) = fs::read_to_string(audit_path) else {
+        return Vec::new();
+    };
+    let mut records: Vec<CrashRecord> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CrashRecord>(line).ok())
+        .filter(|record| record.runtime == runtime)
+        .collect();
+    let start = records.len().saturating_sub(limit);
+    records.split_off(start)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_millis() as u64
+}
@@ -0,0 +1,134 @@
+//! Shared protocol for `clawden tunnel`: authenticating and marshalling the
+//! same operations the local CLI exposes (`ps`, `logs`, `start`, `stop`,
+//! `send`) so they can be driven from another machine through a relay.
+//!
+//! The host prints an access token once at `clawden tunnel` startup and
+//! stores only its hash under `~/.clawden`; the client supplies the
+//! plaintext token on every `connect`, and the host verifies it against the
+//! stored hash before acting on a request.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// An operation the tunnel client can ask the host to perform, mirroring the
+/// local CLI surface (`Ps`, `Logs`, `Start`, `Stop`, `Send` to an `AgentHandle`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TunnelRequestKind {
+    Ps,
+    Logs { runtime: String, lines: usize },
+    Start { runtime: String },
+    Stop { runtime: String },
+    Send { agent_id: String, message: String },
+}
+
+/// A single request sent over the relay's websocket channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TunnelRequest {
+    pub session_id: String,
+    pub token: String,
+    pub kind: TunnelRequestKind,
+}
+
+/// The host's reply to a `TunnelRequest`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TunnelResponse {
+    pub session_id: String,
+    pub ok: bool,
+    pub message: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+impl TunnelResponse {
+    pub fn ok(session_id: &str, payload: serde_json::Value) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            ok: true,
+            message: "ok".to_string(),
+            payload,
+        }
+    }
+
+    pub fn err(session_id: &str, message: impl Into<String>) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            ok: false,
+            message: message.into(),
+            payload: serde_json::Value::Null,
+        }
+    }
+}
+
+static TOKEN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a fresh access token and its SHA-256 hash. The plaintext is
+/// shown to the operator exactly once; only the hash is persisted.
+pub fn generate_token() -> (String, String) {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_nanos();
+    let counter = TOKEN_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let seed = format!("{nanos}-{}-{counter}", std::process::id());
+    let token = hash_token(&seed);
+    let hash = hash_token(&token);
+    (token, hash)
+}
+
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn verify_token(candidate: &str, stored_hash: &str) -> bool {
+    hash_token(candidate) == stored_hash
+}
+
+fn tunnel_state_path() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME environment variable is not set".to_string())?;
+    Ok(PathBuf::from(home).join(".clawden").join("tunnel.token.hash"))
+}
+
+/// Persists the token hash (never the plaintext) under `~/.clawden`.
+pub fn store_token_hash(hash: &str) -> Result<(), String> {
+    let path = tunnel_state_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("creating {}: {e}", parent.display()))?;
+    }
+    fs::write(&path, hash).map_err(|e| format!("writing {}: {e}", path.display()))
+}
+
+/// Loads the previously stored token hash, if any.
+pub fn load_token_hash() -> Result<Option<String>, String> {
+    let path = tunnel_state_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(&path)
+        .map(|s| Some(s.trim().to_string()))
+        .map_err(|e| format!("reading {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_token_verifies_against_its_own_hash() {
+        let (token, hash) = generate_token();
+        assert!(verify_token(&token, &hash));
+        assert!(!verify_token("wrong-token", &hash));
+    }
+
+    #[test]
+    fn hash_is_deterministic() {
+        assert_eq!(hash_token("same-input"), hash_token("same-input"));
+        assert_ne!(hash_token("a"), hash_token("b"));
+    }
+}
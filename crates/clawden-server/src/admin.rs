@@ -0,0 +1,144 @@
+//! A self-contained REST surface over [`crate::manager::LifecycleManager`].
+//!
+//! `api.rs`'s handlers layer in cluster forwarding, runtime pooling, and
+//! telemetry side effects on top of the manager — useful for the agent-facing
+//! surface, but more than an operator driving the fleet directly needs. These
+//! handlers skip that layering (they still save to the store, append audit
+//! rows, and record task history), and unlike `api.rs` (which collapses most
+//! manager errors to `400 Bad Request`) map each
+//! [`LifecycleError`] to the status code an operator would expect: `404` for
+//! an unknown agent, `409` for a transition or state conflict, `503` when no
+//! agent is eligible to route to, and `502` when the adapter call itself
+//! failed.
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use clawden_core::{append_audit, AgentRecord};
+
+use crate::api::{AppState, RegisterAgentRequest, SendTaskRequest, TaskSendResponse};
+use crate::manager::LifecycleError;
+
+fn lifecycle_error_response(err: LifecycleError) -> (StatusCode, String) {
+    let status = match &err {
+        LifecycleError::AgentNotFound(_) => StatusCode::NOT_FOUND,
+        LifecycleError::InvalidTransition { .. } | LifecycleError::AgentNotRunning(_) => {
+            StatusCode::CONFLICT
+        }
+        LifecycleError::NoEligibleAgent { .. } => StatusCode::SERVICE_UNAVAILABLE,
+        LifecycleError::NoAdapter(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        LifecycleError::AdapterStart(_)
+        | LifecycleError::AdapterStop(_)
+        | LifecycleError::SendFailed(_) => StatusCode::BAD_GATEWAY,
+    };
+    (status, err.to_string())
+}
+
+pub async fn admin_register_agent(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterAgentRequest>,
+) -> (StatusCode, Json<AgentRecord>) {
+    let mut manager = state.manager.write().await;
+    let record = manager.register_agent(request.name, request.runtime, request.capabilities);
+    state.store.save_agent(&record);
+    append_audit(&state.audit, "admin", "agent.register", &record.id);
+    (StatusCode::CREATED, Json(record))
+}
+
+pub async fn admin_list_agents(State(state): State<AppState>) -> Json<Vec<AgentRecord>> {
+    let manager = state.manager.read().await;
+    Json(manager.list_agents())
+}
+
+pub async fn admin_start_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let mut manager = state.manager.write().await;
+    let record = manager
+        .start_agent(&agent_id)
+        .await
+        .map_err(lifecycle_error_response)?;
+    state.store.save_agent(&record);
+    append_audit(&state.audit, "admin", "agent.start", &agent_id);
+    Ok(Json(record))
+}
+
+pub async fn admin_stop_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let mut manager = state.manager.write().await;
+    let record = manager
+        .stop_agent(&agent_id)
+        .await
+        .map_err(lifecycle_error_response)?;
+    state.store.save_agent(&record);
+    append_audit(&state.audit, "admin", "agent.stop", &agent_id);
+    Ok(Json(record))
+}
+
+/// Refreshes health for the whole fleet (the manager has no cheaper
+/// single-agent probe) and returns just `agent_id`'s record, 404ing if it
+/// doesn't exist rather than silently returning someone else's state.
+pub async fn admin_refresh_health(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let mut manager = state.manager.write().await;
+    let record = manager
+        .refresh_health()
+        .await
+        .into_iter()
+        .find(|agent| agent.id == agent_id)
+        .ok_or_else(|| lifecycle_error_response(LifecycleError::AgentNotFound(agent_id.clone())))?;
+    append_audit(&state.audit, "admin", "agent.refresh-health", &agent_id);
+    Ok(Json(record))
+}
+
+pub async fn admin_route_task(
+    State(state): State<AppState>,
+    Json(request): Json<SendTaskRequest>,
+) -> Result<Json<TaskSendResponse>, (StatusCode, String)> {
+    let task_id = {
+        let mut tasks = state.tasks.write().await;
+        tasks.create(request.message.clone(), current_unix_ms()).id
+    };
+
+    let mut manager = state.manager.write().await;
+    let dispatched = manager
+        .route_and_send(
+            &request.required_capabilities,
+            request.message,
+            request.agent_id.clone(),
+            request.session_key.clone(),
+        )
+        .await;
+    drop(manager);
+
+    {
+        let mut tasks = state.tasks.write().await;
+        match &dispatched {
+            Ok((agent, response)) => {
+                tasks.mark_running(&task_id, agent.id.clone());
+                tasks.mark_succeeded(&task_id, response.content.clone(), current_unix_ms());
+            }
+            Err(err) => tasks.mark_failed(&task_id, err.to_string(), current_unix_ms()),
+        }
+    }
+
+    let (agent, response) = dispatched.map_err(lifecycle_error_response)?;
+    append_audit(&state.audit, "admin", "task.route", &agent.id);
+    Ok(Json(TaskSendResponse {
+        agent,
+        content: response.content,
+        task_id,
+    }))
+}
+
+fn current_unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_millis() as u64
+}
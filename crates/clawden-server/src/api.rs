@@ -1,24 +1,131 @@
 use std::sync::Arc;
 
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
-use axum::Json;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::{Extension, Json};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clawden_core::sasl::CredentialStore;
 use clawden_core::{
-    append_audit, AgentRecord, AgentState, AuditEvent, AuditLog, BindChannelRequest,
-    BindingConflict, ChannelConfigRequest, ChannelStore, ChannelTypeSummary, ClawRuntime,
-    DiscoveredEndpoint, DiscoveryMethod, DiscoveryService, LifecycleManager, MatrixRow,
-    RuntimeMetadata, SwarmCoordinator, SwarmMember, SwarmRole,
+    append_audit, append_audit_correlated, AgentRecord, AgentState, AuditPage, AuditQuery,
+    AuditSelector, AuditStore, BindChannelRequest, BindingConflict, ChannelConfigRequest,
+    ChannelStore, ChannelTypeSummary, ClawRuntime, DiscoveredEndpoint, DiscoveryMethod,
+    DiscoveryService, LifecycleManager, MatrixRow, RestartPolicy, RuntimeMetadata,
+    SwarmCoordinator, SwarmMember, SwarmRole,
 };
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use tracing::Instrument;
+
+use crate::auth::{authenticated_principal, PendingExchanges, SessionStore};
+use crate::batch::{BatchItemResult, OneOrMany};
+use crate::cluster::Broadcasting;
+use crate::correlation::CorrelationId;
+use crate::dataspace::{Dataspace, Fact};
+use crate::pool::{RuntimePool, RuntimePoolStatus};
+use crate::proxy::{RelayOperation, RelayRegistry, RelayRequest, RelayResponse};
+use crate::scheduler::{
+    CatchUpPolicy, OverlapPolicy, ScheduleEntry, ScheduleSpec, ScheduleTarget, Scheduler,
+};
+use crate::store::Store;
+use crate::tasks::{TaskRecord, TaskStore};
+use crate::telemetry::TelemetryRegistry;
+use crate::tls::{CertificateAuthority, IssuedCertificate, TlsConfig};
+
+/// How long a `send_task`/`restart_agent`/`agent_logs` request waits for a
+/// tunneled agent to reply over its relay connection before giving up.
+const RELAY_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 #[derive(Clone)]
 pub struct AppState {
     pub manager: Arc<RwLock<LifecycleManager>>,
-    pub audit: Arc<AuditLog>,
+    pub audit: Arc<dyn AuditStore>,
     pub discovery: Arc<RwLock<DiscoveryService>>,
     pub swarm: Arc<RwLock<SwarmCoordinator>>,
     pub channels: Arc<RwLock<ChannelStore>>,
+    pub dataspace: Arc<Dataspace>,
+    pub credentials: Arc<CredentialStore>,
+    pub sessions: Arc<SessionStore>,
+    pub pending_exchanges: Arc<PendingExchanges>,
+    pub cluster: Arc<Broadcasting>,
+    pub telemetry: Arc<TelemetryRegistry>,
+    pub relay: Arc<RelayRegistry>,
+    pub pool: Arc<RuntimePool>,
+    pub scheduler: Arc<RwLock<Scheduler>>,
+    pub ca: Arc<CertificateAuthority>,
+    pub tls: Arc<TlsConfig>,
+    pub store: Arc<dyn Store>,
+    pub tasks: Arc<RwLock<TaskStore>>,
+}
+
+/// Resolves the caller's SCRAM session from `Authorization: Bearer <token>`,
+/// auditing and rejecting with 401 on failure instead of letting the
+/// handler run as the old trust-everything model did.
+async fn require_principal(
+    state: &AppState,
+    headers: &HeaderMap,
+    action: &str,
+) -> Result<String, (StatusCode, String)> {
+    match authenticated_principal(&state.sessions, headers) {
+        Ok(principal) => Ok(principal),
+        Err(reason) => {
+            append_audit(&state.audit, "anonymous", action, &reason);
+            Err((StatusCode::UNAUTHORIZED, reason))
+        }
+    }
+}
+
+/// Rejects a direct `agent_id` target whose presented fingerprint doesn't
+/// match what [`crate::tls::CertificateAuthority`] issued it. A no-op when
+/// `state.tls.require_client_cert` is unset, or when `agent_id` is `None` —
+/// a pool-routed call has no specific agent to check yet.
+fn require_valid_certificate(
+    state: &AppState,
+    agent_id: Option<&str>,
+    presented_fingerprint: Option<&str>,
+) -> Result<(), (StatusCode, String)> {
+    if !state.tls.require_client_cert {
+        return Ok(());
+    }
+    let Some(agent_id) = agent_id else {
+        return Ok(());
+    };
+    let presented = presented_fingerprint.unwrap_or_default();
+    if state.ca.verify_fingerprint(agent_id, presented) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::UNAUTHORIZED,
+            format!("agent {agent_id} presented an invalid or missing client certificate"),
+        ))
+    }
+}
+
+/// Publishes the current state of `agent_id` as an [`Fact::AgentState`], for
+/// handlers that transition an agent outside the periodic health monitor
+/// (e.g. an explicit start/stop/restart should reach subscribers immediately
+/// rather than waiting for the next tick).
+fn publish_agent_state(dataspace: &Dataspace, agent: &AgentRecord) {
+    dataspace.publish(Fact::AgentState {
+        agent_id: agent.id.clone(),
+        runtime: format!("{:?}", agent.runtime),
+        state: format!("{:?}", agent.state),
+    });
+}
+
+/// Re-runs conflict detection after a binding mutation and publishes every
+/// conflict so dashboards subscribed to `{ "kind": "binding_conflict" }`
+/// converge without re-polling `/channels/bindings/conflicts`.
+fn publish_binding_conflicts(dataspace: &Dataspace, channels: &ChannelStore) {
+    for conflict in channels.detect_conflicts() {
+        dataspace.publish(Fact::BindingConflict {
+            channel_type: conflict.channel_type,
+            bot_token_hash: conflict.bot_token_hash,
+            instance_ids: conflict.instance_ids,
+        });
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,78 +136,180 @@ pub struct RegisterAgentRequest {
     pub capabilities: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendTaskRequest {
     pub message: String,
     #[serde(default)]
     pub required_capabilities: Vec<String>,
     pub agent_id: Option<String>,
+    /// Caller-supplied conversation identifier for sticky routing — only
+    /// consulted when the fleet's [`crate::routing::RoutingStrategy`] is a
+    /// [`crate::routing::StickyStrategy`]; ignored otherwise.
+    #[serde(default)]
+    pub session_key: Option<String>,
+    /// Fingerprint of the client certificate [`crate::tls::CertificateAuthority`]
+    /// issued the caller. Only enforced against `agent_id`'s enrolled
+    /// fingerprint when [`crate::tls::TlsConfig::require_client_cert`] is set
+    /// and `agent_id` is given directly — a pool-routed call has no specific
+    /// agent to check against until after selection.
+    #[serde(default)]
+    pub client_fingerprint: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FleetStatusResponse {
     pub total_agents: usize,
     pub running_agents: usize,
     pub degraded_agents: usize,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TaskSendResponse {
     pub agent: AgentRecord,
     pub content: String,
+    /// Id of the [`TaskRecord`] this dispatch created — pass it to
+    /// `GET /tasks/:id` to poll status after the fact, since `send_task`
+    /// itself blocks until the adapter call returns.
+    pub task_id: String,
 }
 
 pub async fn register_agent(
     State(state): State<AppState>,
-    Json(request): Json<RegisterAgentRequest>,
-) -> (StatusCode, Json<AgentRecord>) {
+    headers: HeaderMap,
+    Json(request): Json<OneOrMany<RegisterAgentRequest>>,
+) -> Result<(StatusCode, Json<Vec<BatchItemResult<AgentRecord>>>), (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "agent.register").await?;
     let mut manager = state.manager.write().await;
-    let record = manager.register_agent(request.name, request.runtime, request.capabilities);
-    append_audit(&state.audit, "api", "agent.register", &record.id);
-    (StatusCode::CREATED, Json(record))
+    let results = request
+        .into_vec()
+        .into_iter()
+        .map(|item| {
+            let record = manager.register_agent(item.name, item.runtime, item.capabilities);
+            state.store.save_agent(&record);
+            append_audit(&state.audit, &principal, "agent.register", &record.id);
+            BatchItemResult::ok(record)
+        })
+        .collect();
+    Ok((StatusCode::CREATED, Json(results)))
 }
 
 pub async fn list_agents(State(state): State<AppState>) -> Json<Vec<AgentRecord>> {
-    let manager = state.manager.read().await;
-    Json(manager.list_agents())
+    let local = {
+        let manager = state.manager.read().await;
+        manager.list_agents()
+    };
+    let agents = state.cluster.gather_agents("/agents", local).await;
+    Json(agents)
 }
 
 pub async fn start_agent(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(agent_id): Path<String>,
 ) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "agent.start").await?;
+    let record = start_agent_pooled(&state, &agent_id).await?;
+    state.store.save_agent(&record);
+    append_audit(&state.audit, &principal, "agent.start", &agent_id);
+    publish_agent_state(&state.dataspace, &record);
+    state
+        .telemetry
+        .record_log(&agent_id, "info", "agent started", current_unix_ms());
+    Ok(Json(record))
+}
+
+/// Starts `agent_id`, handing it a warm [`crate::pool::RuntimePool`] instance
+/// when one is ready for its runtime instead of paying full adapter-start
+/// cost on this request.
+async fn start_agent_pooled(
+    state: &AppState,
+    agent_id: &str,
+) -> Result<AgentRecord, (StatusCode, String)> {
     let mut manager = state.manager.write().await;
-    let record = manager
-        .start_agent(&agent_id)
+    let runtime = manager
+        .list_agents()
+        .into_iter()
+        .find(|a| a.id == agent_id)
+        .map(|a| a.runtime)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("agent {agent_id} not found")))?;
+
+    if let Some(handle) = state.pool.checkout(&runtime).await {
+        return manager
+            .start_agent_with_handle(agent_id, handle)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()));
+    }
+
+    manager
+        .start_agent(agent_id)
         .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
-    append_audit(&state.audit, "api", "agent.start", &agent_id);
-    Ok(Json(record))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
 }
 
 pub async fn stop_agent(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(agent_id): Path<String>,
 ) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "agent.stop").await?;
     let mut manager = state.manager.write().await;
     let record = manager
         .stop_agent(&agent_id)
         .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
-    append_audit(&state.audit, "api", "agent.stop", &agent_id);
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    state.store.save_agent(&record);
+    append_audit(&state.audit, &principal, "agent.stop", &agent_id);
+    publish_agent_state(&state.dataspace, &record);
+    state
+        .telemetry
+        .record_log(&agent_id, "info", "agent stopped", current_unix_ms());
+    state.telemetry.remove(&agent_id);
     Ok(Json(record))
 }
 
+/// `POST /agents/:id/enroll` — issues `agent_id` a client certificate and
+/// records its fingerprint on the [`AgentRecord`], so later `send_task`
+/// calls can be checked against it when `require_client_cert` is set.
+pub async fn enroll_agent(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+) -> Result<Json<IssuedCertificate>, (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "agent.enroll").await?;
+    let issued = state.ca.enroll(&agent_id);
+    let mut manager = state.manager.write().await;
+    let record = manager
+        .set_certificate_fingerprint(&agent_id, issued.fingerprint.clone())
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+    state.store.save_agent(&record);
+    append_audit(&state.audit, &principal, "agent.enroll", &agent_id);
+    Ok(Json(issued))
+}
+
+/// `GET /ca` — the server's root certificate, for an agent to pin before
+/// trusting the control channel it enrolls against.
+pub async fn get_ca_certificate(State(state): State<AppState>) -> String {
+    state.ca.ca_certificate_pem().to_string()
+}
+
+fn current_unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_millis() as u64
+}
+
 pub async fn health_summary(State(state): State<AppState>) -> Json<Vec<AgentRecord>> {
     let mut manager = state.manager.write().await;
     Json(manager.refresh_health().await)
 }
 
 pub async fn fleet_status(State(state): State<AppState>) -> Json<FleetStatusResponse> {
-    let manager = state.manager.read().await;
-    let agents = manager.list_agents();
+    let agents = {
+        let manager = state.manager.read().await;
+        manager.list_agents()
+    };
 
-    Json(FleetStatusResponse {
+    let mut status = FleetStatusResponse {
         total_agents: agents.len(),
         running_agents: agents
             .iter()
@@ -110,33 +319,184 @@ pub async fn fleet_status(State(state): State<AppState>) -> Json<FleetStatusResp
             .iter()
             .filter(|agent| agent.state == AgentState::Degraded)
             .count(),
-    })
+    };
+
+    for node in state.cluster.metadata.peers() {
+        if let Ok(remote) = state
+            .cluster
+            .client
+            .get_json::<FleetStatusResponse>(node, "/fleet/status")
+            .await
+        {
+            status.total_agents += remote.total_agents;
+            status.running_agents += remote.running_agents;
+            status.degraded_agents += remote.degraded_agents;
+        }
+    }
+
+    Json(status)
+}
+
+/// `GET /metrics` — Prometheus text exposition of the fleet's counters,
+/// gauges, and adapter-latency histograms. See [`crate::metrics::Metrics`].
+pub async fn prometheus_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let manager = state.manager.read().await;
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        manager.render_prometheus(),
+    )
 }
 
 pub async fn send_task(
     State(state): State<AppState>,
     Json(request): Json<SendTaskRequest>,
 ) -> Result<Json<TaskSendResponse>, (StatusCode, String)> {
+    if let Some(tunnel) = request.agent_id.as_deref().and_then(|id| state.relay.get(id)) {
+        return relay_send_task(&state, tunnel, &request).await;
+    }
+
+    if let Some(node) = request
+        .agent_id
+        .as_deref()
+        .and_then(|agent_id| state.cluster.metadata.owning_node_for_agent(agent_id))
+    {
+        let response = state
+            .cluster
+            .client
+            .post_json::<SendTaskRequest, TaskSendResponse>(node, "/task/send", &request)
+            .await
+            .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+        return Ok(Json(response));
+    }
+
+    require_valid_certificate(&state, request.agent_id.as_deref(), request.client_fingerprint.as_deref())?;
+
+    let task_id = {
+        let mut tasks = state.tasks.write().await;
+        tasks.create(request.message.clone(), current_unix_ms()).id
+    };
+
     let mut manager = state.manager.write().await;
-    let (agent, response) = manager
+    let dispatched = manager
         .route_and_send(
             &request.required_capabilities,
             request.message,
             request.agent_id.clone(),
+            request.session_key.clone(),
         )
-        .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        .await;
+    drop(manager);
+
+    record_task_outcome(&state, &task_id, &dispatched).await;
+
+    let (agent, response) = dispatched.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
 
     append_audit(&state.audit, "api", "task.send", &agent.id);
 
     Ok(Json(TaskSendResponse {
         agent,
         content: response.content,
+        task_id,
     }))
 }
 
-pub async fn audit_log(State(state): State<AppState>) -> Json<Vec<AuditEvent>> {
-    Json(state.audit.list())
+/// Marks `task_id` `Running` then `Succeeded`, or `Failed`, once a local
+/// dispatch from [`send_task`] resolves. [`relay_send_task`] does not call
+/// this; it marks its task inline.
+async fn record_task_outcome(
+    state: &AppState,
+    task_id: &str,
+    dispatched: &Result<(AgentRecord, clawden_core::AgentResponse), crate::manager::LifecycleError>,
+) {
+    let mut tasks = state.tasks.write().await;
+    match dispatched {
+        Ok((agent, response)) => {
+            tasks.mark_running(task_id, agent.id.clone());
+            tasks.mark_succeeded(task_id, response.content.clone(), current_unix_ms());
+        }
+        Err(err) => {
+            tasks.mark_failed(task_id, err.to_string(), current_unix_ms());
+        }
+    }
+}
+
+// --- Task history endpoints ---
+
+pub async fn list_tasks(State(state): State<AppState>) -> Json<Vec<TaskRecord>> {
+    let tasks = state.tasks.read().await;
+    Json(tasks.list())
+}
+
+pub async fn get_task(
+    State(state): State<AppState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskRecord>, (StatusCode, String)> {
+    let tasks = state.tasks.read().await;
+    tasks
+        .get(&task_id)
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("task {task_id} not found")))
+}
+
+pub async fn agent_tasks(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Json<Vec<TaskRecord>> {
+    let tasks = state.tasks.read().await;
+    Json(tasks.list_for_agent(&agent_id))
+}
+
+// Query-string shape for `GET /audit`, modeled on IRC CHATHISTORY-style
+/// selectors. `before`/`after`/`between_start`+`between_end` pick the
+/// selector variant (defaulting to `Latest` when none are given); `actor`,
+/// `action`, and `target` are optional equality filters.
+#[derive(Debug, Deserialize)]
+pub struct AuditHistoryParams {
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+    pub between_start: Option<u64>,
+    pub between_end: Option<u64>,
+    #[serde(default = "default_audit_limit")]
+    pub limit: usize,
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub correlation_id: Option<String>,
+}
+
+fn default_audit_limit() -> usize {
+    50
+}
+
+impl From<AuditHistoryParams> for AuditQuery {
+    fn from(params: AuditHistoryParams) -> Self {
+        let selector = match (params.before, params.after, params.between_start, params.between_end) {
+            (Some(ts), None, None, None) => AuditSelector::Before { ts, limit: params.limit },
+            (None, Some(ts), None, None) => AuditSelector::After { ts, limit: params.limit },
+            (None, None, Some(start), Some(end)) => AuditSelector::Between {
+                start,
+                end,
+                limit: params.limit,
+            },
+            _ => AuditSelector::Latest {
+                limit: params.limit,
+            },
+        };
+        AuditQuery {
+            selector,
+            actor: params.actor,
+            action: params.action,
+            target: params.target,
+            correlation_id: params.correlation_id,
+        }
+    }
+}
+
+pub async fn audit_log(
+    State(state): State<AppState>,
+    Query(params): Query<AuditHistoryParams>,
+) -> Json<AuditPage> {
+    Json(state.audit.query(&params.into()))
 }
 
 // --- Discovery endpoints ---
@@ -159,12 +519,14 @@ pub async fn register_endpoint(
         _ => DiscoveryMethod::Manual,
     };
     let mut discovery = state.discovery.write().await;
-    let key = discovery.register_endpoint(DiscoveredEndpoint {
+    let endpoint = DiscoveredEndpoint {
         host: req.host,
         port: req.port,
         method,
         runtime_hint: req.runtime_hint,
-    });
+    };
+    let key = discovery.register_endpoint(endpoint.clone());
+    state.store.save_discovered_endpoint(&key, &endpoint);
     append_audit(&state.audit, "api", "discovery.register", &key);
     (StatusCode::CREATED, Json(serde_json::json!({ "key": key })))
 }
@@ -222,6 +584,7 @@ pub async fn create_team(
     let mut swarm = state.swarm.write().await;
     let team = swarm.create_team(req.name.clone(), members);
     let response = serde_json::to_value(team).unwrap_or_default();
+    state.store.save_swarm_team(&req.name, &response);
     append_audit(&state.audit, "api", "swarm.create_team", &req.name);
     (StatusCode::CREATED, Json(response))
 }
@@ -231,7 +594,7 @@ pub async fn list_teams(State(state): State<AppState>) -> Json<serde_json::Value
     Json(serde_json::to_value(swarm.list_teams()).unwrap_or_default())
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FanOutRequest {
     pub team_name: String,
     pub task_description: String,
@@ -242,16 +605,45 @@ pub async fn fan_out_task(
     State(state): State<AppState>,
     Json(req): Json<FanOutRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if let Some(node) = state.cluster.metadata.owning_node_for_team(&req.team_name) {
+        let response = state
+            .cluster
+            .client
+            .post_json::<FanOutRequest, serde_json::Value>(node, "/swarm/fan-out", &req)
+            .await
+            .map_err(|e| (StatusCode::BAD_GATEWAY, e))?;
+        return Ok(Json(response));
+    }
+
+    let task_id = {
+        let mut tasks = state.tasks.write().await;
+        tasks
+            .create(req.task_description.clone(), current_unix_ms())
+            .id
+    };
+
     let mut swarm = state.swarm.write().await;
-    let tasks = swarm
-        .fan_out(
-            &req.team_name,
-            &req.task_description,
-            req.subtask_descriptions,
-        )
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let dispatched = swarm.fan_out(
+        &req.team_name,
+        &req.task_description,
+        req.subtask_descriptions,
+    );
+    drop(swarm);
 
-    let value = serde_json::to_value(&tasks).unwrap_or_default();
+    let mut tasks = state.tasks.write().await;
+    match &dispatched {
+        Ok(subtasks) => {
+            let result = serde_json::to_string(subtasks).unwrap_or_default();
+            tasks.mark_succeeded(&task_id, result, current_unix_ms());
+        }
+        Err(err) => {
+            tasks.mark_failed(&task_id, err.clone(), current_unix_ms());
+        }
+    }
+    drop(tasks);
+
+    let subtasks = dispatched.map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let value = serde_json::to_value(&subtasks).unwrap_or_default();
     append_audit(&state.audit, "api", "swarm.fan_out", &req.team_name);
     Ok(Json(value))
 }
@@ -261,6 +653,14 @@ pub async fn list_swarm_tasks(State(state): State<AppState>) -> Json<serde_json:
     Json(serde_json::to_value(swarm.list_tasks(None)).unwrap_or_default())
 }
 
+// --- Cluster endpoints ---
+
+pub async fn list_cluster_nodes(
+    State(state): State<AppState>,
+) -> Json<Vec<crate::cluster::ClusterNodeStatus>> {
+    Json(state.cluster.node_statuses().await)
+}
+
 // --- Runtime endpoints (spec 017/021) ---
 
 pub async fn list_runtimes(State(state): State<AppState>) -> Json<Vec<RuntimeMetadata>> {
@@ -268,6 +668,12 @@ pub async fn list_runtimes(State(state): State<AppState>) -> Json<Vec<RuntimeMet
     Json(manager.list_runtime_metadata())
 }
 
+/// `GET /runtimes/pool` — per-runtime warm/creating counts from the
+/// [`crate::pool::RuntimePool`], for observing prewarm coverage.
+pub async fn runtime_pool_status(State(state): State<AppState>) -> Json<Vec<RuntimePoolStatus>> {
+    Json(state.pool.status().await)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DeployRuntimeRequest {
     pub instance_name: String,
@@ -290,8 +696,25 @@ pub struct DeployStatusResponse {
 
 pub async fn deploy_runtime(
     State(state): State<AppState>,
+    Extension(CorrelationId(correlation_id)): Extension<CorrelationId>,
     Path(runtime_name): Path<String>,
     Json(request): Json<DeployRuntimeRequest>,
+) -> Result<Json<DeployStatusResponse>, (StatusCode, String)> {
+    // register -> start -> assign channels is a causal chain of sub-steps
+    // that each append their own audit row; this span + the shared
+    // correlation id are what let `GET /audit?correlation_id=...` pull the
+    // whole deploy back out together instead of disconnected rows.
+    let span = tracing::info_span!("deploy_runtime", %correlation_id, %runtime_name);
+    deploy_runtime_inner(state, correlation_id, runtime_name, request)
+        .instrument(span)
+        .await
+}
+
+async fn deploy_runtime_inner(
+    state: AppState,
+    correlation_id: String,
+    runtime_name: String,
+    request: DeployRuntimeRequest,
 ) -> Result<Json<DeployStatusResponse>, (StatusCode, String)> {
     // Validate runtime name matches path
     let runtime_str = format!("{:?}", request.runtime).to_lowercase();
@@ -306,24 +729,57 @@ pub async fn deploy_runtime(
     let mut manager = state.manager.write().await;
     let record = manager.register_agent(
         request.instance_name.clone(),
-        request.runtime,
+        request.runtime.clone(),
         request.capabilities,
     );
-
-    // Start the agent (install + start)
     let agent_id = record.id.clone();
-    let started = manager.start_agent(&agent_id).await;
+    tracing::info!(step = "register", %agent_id, "deploy step completed");
+    append_audit_correlated(
+        &state.audit,
+        "api",
+        "runtime.deploy.register",
+        &agent_id,
+        Some(&correlation_id),
+    );
 
-    append_audit(&state.audit, "api", "runtime.deploy", &agent_id);
+    // Start the agent, handing it a warm pool instance when one is ready
+    // instead of paying full adapter-start cost on this request.
+    let started = if let Some(handle) = state.pool.checkout(&request.runtime).await {
+        manager.start_agent_with_handle(&agent_id, handle)
+    } else {
+        manager.start_agent(&agent_id).await
+    };
+
+    append_audit_correlated(
+        &state.audit,
+        "api",
+        "runtime.deploy",
+        &agent_id,
+        Some(&correlation_id),
+    );
 
     match started {
         Ok(agent) => {
+            tracing::info!(step = "start", %agent_id, "deploy step completed");
             // Assign channels
             if !request.channels.is_empty() {
                 let mut channels = state.channels.write().await;
                 for ch in &request.channels {
                     channels.assign_channel(&agent_id, ch);
                 }
+                tracing::info!(
+                    step = "assign_channels",
+                    %agent_id,
+                    channel_count = request.channels.len(),
+                    "deploy step completed"
+                );
+                append_audit_correlated(
+                    &state.audit,
+                    "api",
+                    "runtime.deploy.assign_channels",
+                    &agent_id,
+                    Some(&correlation_id),
+                );
             }
 
             Ok(Json(DeployStatusResponse {
@@ -332,7 +788,7 @@ pub async fn deploy_runtime(
                 completed: true,
             }))
         }
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e)),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
 }
 
@@ -353,6 +809,7 @@ pub async fn deploy_status(
         AgentState::Running => "running",
         AgentState::Stopped => "stopped",
         AgentState::Degraded => "degraded",
+        AgentState::Failed => "failed",
     };
 
     Ok(Json(DeployStatusResponse {
@@ -373,11 +830,10 @@ pub async fn agent_metrics_history(
         .find(|a| a.id == agent_id)
         .ok_or_else(|| (StatusCode::NOT_FOUND, "agent not found".to_string()))?;
 
-    // Return stub metrics history
-    Ok(Json(serde_json::json!({
-        "data_points": [],
-        "message": "metrics history collection not yet implemented"
-    })))
+    // Polling fallback over the same bounded history `/agents/:id/metrics/stream`
+    // tails live — drains `telemetry`'s ring buffer instead of the old stub.
+    let (data_points, _receiver) = state.telemetry.subscribe_metrics(&agent_id, 0);
+    Ok(Json(serde_json::json!({ "data_points": data_points })))
 }
 
 // --- Channel endpoints (spec 018/021) ---
@@ -407,22 +863,27 @@ pub async fn get_channel_config(
 pub async fn upsert_channel_config(
     State(state): State<AppState>,
     Path(_channel_type): Path<String>,
-    Json(req): Json<ChannelConfigRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    Json(request): Json<OneOrMany<ChannelConfigRequest>>,
+) -> (StatusCode, Json<Vec<BatchItemResult<clawden_core::ChannelInstanceConfig>>>) {
     let mut channels = state.channels.write().await;
-    let config = channels
-        .upsert_config(req)
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
-    append_audit(
-        &state.audit,
-        "api",
-        "channel.configure",
-        &config.instance_name,
-    );
-    Ok((
-        StatusCode::OK,
-        Json(serde_json::to_value(config).unwrap_or_default()),
-    ))
+    let results = request
+        .into_vec()
+        .into_iter()
+        .map(|req| match channels.upsert_config(req) {
+            Ok(config) => {
+                state.store.save_channel_config(&config);
+                append_audit(
+                    &state.audit,
+                    "api",
+                    "channel.configure",
+                    &config.instance_name,
+                );
+                BatchItemResult::ok(config)
+            }
+            Err(e) => BatchItemResult::err(e),
+        })
+        .collect();
+    (StatusCode::OK, Json(results))
 }
 
 pub async fn delete_channel_config(
@@ -431,6 +892,7 @@ pub async fn delete_channel_config(
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let mut channels = state.channels.write().await;
     if channels.delete_config(&channel_type) {
+        state.store.delete_channel_config(&channel_type);
         append_audit(&state.audit, "api", "channel.delete", &channel_type);
         Ok(Json(serde_json::json!({ "deleted": channel_type })))
     } else {
@@ -516,17 +978,30 @@ pub async fn list_bindings(
 
 pub async fn create_binding(
     State(state): State<AppState>,
-    Json(req): Json<BindChannelRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), (StatusCode, String)> {
+    headers: HeaderMap,
+    Json(request): Json<OneOrMany<BindChannelRequest>>,
+) -> Result<(StatusCode, Json<Vec<BatchItemResult<clawden_core::ChannelBinding>>>), (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "channel.bind").await?;
     let mut channels = state.channels.write().await;
-    let binding = channels
-        .bind(req.instance_id.clone(), &req.channel_type, &req.bot_token)
-        .map_err(|e| (StatusCode::CONFLICT, e))?;
-    append_audit(&state.audit, "api", "channel.bind", &req.instance_id);
-    Ok((
-        StatusCode::CREATED,
-        Json(serde_json::to_value(binding).unwrap_or_default()),
-    ))
+    let results = request
+        .into_vec()
+        .into_iter()
+        .map(|req| {
+            match channels.bind(req.instance_id.clone(), &req.channel_type, &req.bot_token) {
+                Ok(binding) => {
+                    state.store.save_channel_binding(
+                        &format!("{}/{}", binding.channel_type, binding.bot_token_hash),
+                        &binding,
+                    );
+                    append_audit(&state.audit, &principal, "channel.bind", &req.instance_id);
+                    BatchItemResult::ok(binding)
+                }
+                Err(e) => BatchItemResult::err(e),
+            }
+        })
+        .collect();
+    publish_binding_conflicts(&state.dataspace, &channels);
+    Ok((StatusCode::CREATED, Json(results)))
 }
 
 pub async fn delete_binding(
@@ -537,7 +1012,12 @@ pub async fn delete_binding(
     let binding = channels
         .unbind(binding_id)
         .map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    state.store.save_channel_binding(
+        &format!("{}/{}", binding.channel_type, binding.bot_token_hash),
+        &binding,
+    );
     append_audit(&state.audit, "api", "channel.unbind", &binding.instance_id);
+    publish_binding_conflicts(&state.dataspace, &channels);
     Ok(Json(serde_json::to_value(binding).unwrap_or_default()))
 }
 
@@ -546,6 +1026,159 @@ pub async fn binding_conflicts(State(state): State<AppState>) -> Json<Vec<Bindin
     Json(channels.detect_conflicts())
 }
 
+// --- SCRAM-SHA-256 auth endpoints ---
+
+#[derive(Debug, Deserialize)]
+pub struct ScramStartRequest {
+    /// `client-first-message-bare`, e.g. `n=alice,r=<client-nonce>`.
+    pub client_first_bare: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScramStartResponse {
+    pub handshake_id: String,
+    /// `server-first-message`, to be echoed back into the client's
+    /// `AuthMessage` alongside `client_first_bare`.
+    pub message: String,
+}
+
+pub async fn scram_start(
+    State(state): State<AppState>,
+    Json(request): Json<ScramStartRequest>,
+) -> Result<Json<ScramStartResponse>, (StatusCode, String)> {
+    let first = clawden_core::sasl::server_first(&state.credentials, &request.client_first_bare)
+        .map_err(|e| (StatusCode::UNAUTHORIZED, e))?;
+    let message = first.message.clone();
+    let handshake_id = state.pending_exchanges.insert(first);
+    Ok(Json(ScramStartResponse {
+        handshake_id,
+        message,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScramFinishRequest {
+    pub handshake_id: String,
+    /// `client-final-message-without-proof`, e.g. `c=biws,r=<combined-nonce>`.
+    pub client_final_without_proof: String,
+    /// Base64-encoded `ClientProof`.
+    pub client_proof: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScramFinishResponse {
+    pub token: String,
+    /// Base64-encoded `ServerSignature`, for the client to confirm the
+    /// server also knows `ServerKey` (mutual auth).
+    pub server_signature: String,
+}
+
+pub async fn scram_finish(
+    State(state): State<AppState>,
+    Json(request): Json<ScramFinishRequest>,
+) -> Result<Json<ScramFinishResponse>, (StatusCode, String)> {
+    let Some(first) = state.pending_exchanges.take(&request.handshake_id) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "unknown or expired handshake_id".to_string(),
+        ));
+    };
+    let username = first.username.clone();
+
+    let client_proof = BASE64
+        .decode(&request.client_proof)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("invalid client_proof: {e}")))?;
+
+    match clawden_core::sasl::verify_client_proof(
+        &first,
+        &request.client_final_without_proof,
+        &client_proof,
+    ) {
+        Ok(server_signature) => {
+            let token = state.sessions.issue(&username);
+            append_audit(&state.audit, &username, "auth.success", "scram");
+            Ok(Json(ScramFinishResponse {
+                token,
+                server_signature: server_signature.to_base64(),
+            }))
+        }
+        Err(reason) => {
+            append_audit(&state.audit, &username, "auth.failure", "scram");
+            Err((StatusCode::UNAUTHORIZED, reason))
+        }
+    }
+}
+
+// --- Dataspace subscription endpoint (reactive fleet/channel state) ---
+
+/// Upgrades `GET /subscribe` to a WebSocket. The client's first text frame
+/// is the interest pattern (e.g. `{ "kind": "agent_state", "state": "Degraded" }`);
+/// everything after that is driven by [`Dataspace::subscribe`].
+pub async fn subscribe_dataspace(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_dataspace_socket(socket, state.dataspace))
+}
+
+async fn handle_dataspace_socket(mut socket: WebSocket, dataspace: Arc<Dataspace>) {
+    let pattern = loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                Ok(pattern) => break pattern,
+                Err(e) => {
+                    let _ = socket
+                        .send(Message::Text(format!("invalid interest pattern: {e}").into()))
+                        .await;
+                    return;
+                }
+            },
+            Some(Ok(Message::Close(_))) | None => return,
+            Some(Ok(_)) => continue,
+            Some(Err(_)) => return,
+        }
+    };
+
+    let (subscription_id, snapshot, mut deltas) = dataspace.subscribe(pattern);
+
+    for fact in snapshot {
+        if send_fact(&mut socket, &fact).await.is_err() {
+            dataspace.unsubscribe(subscription_id);
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            fact = deltas.recv() => {
+                match fact {
+                    Some(fact) => {
+                        if send_fact(&mut socket, &fact).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+            frame = socket.recv() => {
+                match frame {
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    _ => continue,
+                }
+            }
+        }
+    }
+
+    dataspace.unsubscribe(subscription_id);
+}
+
+async fn send_fact(socket: &mut WebSocket, fact: &Fact) -> Result<(), ()> {
+    let Ok(payload) = serde_json::to_string(fact) else {
+        return Err(());
+    };
+    socket.send(Message::Text(payload.into())).await.map_err(|_| ())
+}
+
 /// Full channel support matrix from adapter metadata
 pub async fn channel_support_matrix(State(state): State<AppState>) -> Json<serde_json::Value> {
     let manager = state.manager.read().await;
@@ -574,23 +1207,118 @@ pub async fn restart_agent(
     State(state): State<AppState>,
     Path(agent_id): Path<String>,
 ) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    if let Some(tunnel) = state.relay.get(&agent_id) {
+        return relay_restart_agent(&state, tunnel, &agent_id).await;
+    }
+
     let mut manager = state.manager.write().await;
     // Stop then start
     let _ = manager.stop_agent(&agent_id).await;
     let record = manager
         .start_agent(&agent_id)
         .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
     append_audit(&state.audit, "api", "agent.restart", &agent_id);
+    publish_agent_state(&state.dataspace, &record);
+    Ok(Json(record))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigureRestartRequest {
+    pub policy: RestartPolicy,
+}
+
+/// Sets how the background monitor's [`LifecycleManager::recover_degraded`]
+/// supervises a degraded agent: restart it, leave it alone, or retry
+/// forever. See [`RestartPolicy`] for what each option means.
+pub async fn configure_restart(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+    Json(request): Json<ConfigureRestartRequest>,
+) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "agent.configure_restart").await?;
+    let mut manager = state.manager.write().await;
+    let record = manager
+        .configure_restart(&agent_id, request.policy)
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+    append_audit(&state.audit, &principal, "agent.configure_restart", &agent_id);
     Ok(Json(record))
 }
 
+// --- Scheduler endpoints ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateScheduleEntryRequest {
+    #[serde(default)]
+    pub required_capabilities: Vec<String>,
+    pub payload: String,
+    pub spec: ScheduleSpec,
+    #[serde(default)]
+    pub target: Option<ScheduleTarget>,
+    #[serde(default)]
+    pub catch_up_policy: CatchUpPolicy,
+    #[serde(default)]
+    pub overlap_policy: OverlapPolicy,
+    #[serde(default)]
+    pub max_runs: Option<u32>,
+}
+
+pub async fn list_schedule_entries(State(state): State<AppState>) -> Json<Vec<ScheduleEntry>> {
+    let scheduler = state.scheduler.read().await;
+    Json(scheduler.list_entries())
+}
+
+pub async fn create_schedule_entry(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateScheduleEntryRequest>,
+) -> Result<(StatusCode, Json<ScheduleEntry>), (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "scheduler.add").await?;
+    let mut scheduler = state.scheduler.write().await;
+    let entry = scheduler
+        .add_entry(
+            request.required_capabilities,
+            request.payload,
+            request.spec,
+            request.target,
+            request.catch_up_policy,
+            request.overlap_policy,
+            request.max_runs,
+            current_unix_ms(),
+        )
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    append_audit(&state.audit, &principal, "scheduler.add", &entry.id);
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+pub async fn delete_schedule_entry(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(entry_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let principal = require_principal(&state, &headers, "scheduler.remove").await?;
+    let mut scheduler = state.scheduler.write().await;
+    if !scheduler.remove_entry(&entry_id) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("schedule entry {entry_id} not found"),
+        ));
+    }
+    append_audit(&state.audit, &principal, "scheduler.remove", &entry_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // --- Log streaming endpoint (spec 021) ---
 
 pub async fn agent_logs(
     State(state): State<AppState>,
     Path(agent_id): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if let Some(tunnel) = state.relay.get(&agent_id) {
+        return relay_agent_logs(&state, tunnel, &agent_id).await;
+    }
+
     let manager = state.manager.read().await;
     let agents = manager.list_agents();
     let agent = agents
@@ -598,18 +1326,305 @@ pub async fn agent_logs(
         .find(|a| a.id == agent_id)
         .ok_or_else(|| (StatusCode::NOT_FOUND, format!("agent {agent_id} not found")))?;
 
-    // Return stub log entries — in production this would stream via SSE
+    // Polling fallback over the same ring buffer `/agents/:id/logs/stream`
+    // tails live via WebSocket, for callers that'd rather not hold a socket open.
+    let (logs, _receiver) = state.telemetry.subscribe_logs(&agent_id, 0);
     Ok(Json(serde_json::json!({
         "agent_id": agent.id,
         "runtime": format!("{:?}", agent.runtime),
-        "logs": [
-            { "timestamp": "2026-02-27T00:00:00Z", "level": "info", "message": format!("{} started", agent.name) },
-            { "timestamp": "2026-02-27T00:00:01Z", "level": "info", "message": "Ready to accept connections" }
-        ],
-        "note": "SSE streaming not yet implemented — polling fallback"
+        "logs": logs,
     })))
 }
 
+// --- Live telemetry streaming (WebSocket) ---
+
+#[derive(Debug, Deserialize)]
+pub struct TelemetryStreamParams {
+    /// Sequence number to replay from; `0` (the default) replays everything
+    /// still in the backlog before the socket starts tailing live.
+    #[serde(default)]
+    pub since: u64,
+}
+
+pub async fn agent_logs_stream(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(params): Query<TelemetryStreamParams>,
+    ws: WebSocketUpgrade,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_known_agent(&state, &agent_id).await?;
+    Ok(ws.on_upgrade(move |socket| stream_agent_logs(socket, state, agent_id, params.since)))
+}
+
+async fn stream_agent_logs(mut socket: WebSocket, state: AppState, agent_id: String, since: u64) {
+    let (backlog, mut receiver) = state.telemetry.subscribe_logs(&agent_id, since);
+    for line in backlog {
+        if send_json(&mut socket, &line).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        if !agent_is_running(&state, &agent_id).await {
+            let _ = socket.send(Message::Close(None)).await;
+            return;
+        }
+        tokio::select! {
+            line = receiver.recv() => match line {
+                Ok(line) => {
+                    if send_json(&mut socket, &line).await.is_err() {
+                        return;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            },
+            frame = socket.recv() => match frame {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+                _ => continue,
+            },
+            _ = tokio::time::sleep(std::time::Duration::from_secs(3)) => continue,
+        }
+    }
+}
+
+pub async fn agent_metrics_stream(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(params): Query<TelemetryStreamParams>,
+    ws: WebSocketUpgrade,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    require_known_agent(&state, &agent_id).await?;
+    Ok(ws.on_upgrade(move |socket| stream_agent_metrics(socket, state, agent_id, params.since)))
+}
+
+async fn stream_agent_metrics(mut socket: WebSocket, state: AppState, agent_id: String, since: u64) {
+    let (backlog, mut receiver) = state.telemetry.subscribe_metrics(&agent_id, since);
+    for point in backlog {
+        if send_json(&mut socket, &point).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        if !agent_is_running(&state, &agent_id).await {
+            let _ = socket.send(Message::Close(None)).await;
+            return;
+        }
+        tokio::select! {
+            point = receiver.recv() => match point {
+                Ok(point) => {
+                    if send_json(&mut socket, &point).await.is_err() {
+                        return;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            },
+            frame = socket.recv() => match frame {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => return,
+                _ => continue,
+            },
+            _ = tokio::time::sleep(std::time::Duration::from_secs(3)) => continue,
+        }
+    }
+}
+
+async fn require_known_agent(state: &AppState, agent_id: &str) -> Result<(), (StatusCode, String)> {
+    let manager = state.manager.read().await;
+    if manager.list_agents().iter().any(|a| a.id == agent_id) {
+        Ok(())
+    } else {
+        Err((StatusCode::NOT_FOUND, format!("agent {agent_id} not found")))
+    }
+}
+
+/// Whether `agent_id` is still tracked and not [`AgentState::Stopped`] —
+/// once it stops, both stream handlers close the socket instead of tailing
+/// a channel nothing will ever write to again.
+async fn agent_is_running(state: &AppState, agent_id: &str) -> bool {
+    let manager = state.manager.read().await;
+    manager
+        .list_agents()
+        .iter()
+        .any(|a| a.id == agent_id && a.state != AgentState::Stopped)
+}
+
+async fn send_json<T: Serialize>(socket: &mut WebSocket, value: &T) -> Result<(), ()> {
+    let Ok(payload) = serde_json::to_string(value) else {
+        return Err(());
+    };
+    socket.send(Message::Text(payload.into())).await.map_err(|_| ())
+}
+
+// --- Reverse-tunnel relay for agents behind NAT/firewalls ---
+
+async fn relay_send_task(
+    state: &AppState,
+    tunnel: std::sync::Arc<crate::proxy::TunnelHandle>,
+    request: &SendTaskRequest,
+) -> Result<Json<TaskSendResponse>, (StatusCode, String)> {
+    let agent_id = request
+        .agent_id
+        .clone()
+        .expect("relay_send_task only called when agent_id resolved a tunnel");
+
+    let task_id = {
+        let mut tasks = state.tasks.write().await;
+        let record = tasks.create(request.message.clone(), current_unix_ms());
+        tasks.mark_running(&record.id, agent_id.clone());
+        record.id
+    };
+
+    let response = tunnel
+        .dispatch(
+            RelayOperation::SendTask {
+                message: request.message.clone(),
+                required_capabilities: request.required_capabilities.clone(),
+            },
+            RELAY_REQUEST_TIMEOUT,
+        )
+        .await
+        .map_err(|e| (StatusCode::GATEWAY_TIMEOUT, e))?;
+    if !response.ok {
+        let mut tasks = state.tasks.write().await;
+        tasks.mark_failed(&task_id, response.message.clone(), current_unix_ms());
+        return Err((StatusCode::BAD_GATEWAY, response.message));
+    }
+
+    let manager = state.manager.read().await;
+    let agent = manager
+        .list_agents()
+        .into_iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("agent {agent_id} not found")))?;
+    drop(manager);
+
+    append_audit(&state.audit, "api", "task.send", &agent_id);
+    let content = response
+        .payload
+        .get("content")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut tasks = state.tasks.write().await;
+    tasks.mark_succeeded(&task_id, content.clone(), current_unix_ms());
+    drop(tasks);
+
+    Ok(Json(TaskSendResponse {
+        agent,
+        content,
+        task_id,
+    }))
+}
+
+async fn relay_restart_agent(
+    state: &AppState,
+    tunnel: std::sync::Arc<crate::proxy::TunnelHandle>,
+    agent_id: &str,
+) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let response = tunnel
+        .dispatch(RelayOperation::RestartAgent, RELAY_REQUEST_TIMEOUT)
+        .await
+        .map_err(|e| (StatusCode::GATEWAY_TIMEOUT, e))?;
+    if !response.ok {
+        return Err((StatusCode::BAD_GATEWAY, response.message));
+    }
+
+    let manager = state.manager.read().await;
+    let agent = manager
+        .list_agents()
+        .into_iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("agent {agent_id} not found")))?;
+    drop(manager);
+
+    append_audit(&state.audit, "api", "agent.restart", agent_id);
+    publish_agent_state(&state.dataspace, &agent);
+    Ok(Json(agent))
+}
+
+async fn relay_agent_logs(
+    state: &AppState,
+    tunnel: std::sync::Arc<crate::proxy::TunnelHandle>,
+    agent_id: &str,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let response = tunnel
+        .dispatch(RelayOperation::FetchLogs { since: 0 }, RELAY_REQUEST_TIMEOUT)
+        .await
+        .map_err(|e| (StatusCode::GATEWAY_TIMEOUT, e))?;
+    if !response.ok {
+        return Err((StatusCode::BAD_GATEWAY, response.message));
+    }
+
+    Ok(Json(response.payload))
+}
+
+/// `GET /relay/connect` — an agent behind a NAT/firewall holds this
+/// WebSocket open and is driven through it instead of a local lifecycle
+/// manager handle. The first frame must be a JSON `{"agent_id": "..."}`
+/// registration; every frame after that is a [`RelayResponse`] answering
+/// whichever [`RelayRequest`] the server most recently pushed down.
+pub async fn relay_connect(State(state): State<AppState>, ws: WebSocketUpgrade) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_relay_socket(socket, state))
+}
+
+#[derive(Debug, Deserialize)]
+struct RelayRegisterMessage {
+    agent_id: String,
+}
+
+async fn handle_relay_socket(mut socket: WebSocket, state: AppState) {
+    let agent_id = loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => match serde_json::from_str::<RelayRegisterMessage>(&text) {
+                Ok(register) => break register.agent_id,
+                Err(e) => {
+                    let _ = socket
+                        .send(Message::Text(format!("invalid relay registration: {e}").into()))
+                        .await;
+                    return;
+                }
+            },
+            Some(Ok(Message::Close(_))) | None => return,
+            Some(Ok(_)) => continue,
+            Some(Err(_)) => return,
+        }
+    };
+
+    let (outbound_tx, mut outbound_rx) = tokio::sync::mpsc::unbounded_channel::<RelayRequest>();
+    state.relay.register(&agent_id, outbound_tx);
+
+    loop {
+        tokio::select! {
+            outgoing = outbound_rx.recv() => {
+                match outgoing {
+                    Some(request) => {
+                        if send_json(&mut socket, &request).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
+            }
+            frame = socket.recv() => {
+                match frame {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(response) = serde_json::from_str::<RelayResponse>(&text) {
+                            state.relay.resolve(&agent_id, response);
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                }
+            }
+        }
+    }
+
+    state.relay.unregister(&agent_id);
+}
+
 // --- Channel proxy status endpoint (spec 018) ---
 
 pub async fn proxy_status_endpoint(
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::http::HeaderMap;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clawden_core::sasl::ServerFirst;
+use rand::RngCore;
+
+/// Bearer tokens issued once a SCRAM exchange completes, mapping each back
+/// to the principal it authenticated so gated handlers can attribute the
+/// audit trail to a verified owner instead of the anonymous caller.
+#[derive(Default)]
+pub struct SessionStore {
+    tokens: Mutex<HashMap<String, String>>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn issue(&self, principal: &str) -> String {
+        let mut bytes = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = BASE64.encode(bytes);
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(token.clone(), principal.to_string());
+        }
+        token
+    }
+
+    pub fn principal_for(&self, token: &str) -> Option<String> {
+        self.tokens
+            .lock()
+            .ok()
+            .and_then(|tokens| tokens.get(token).cloned())
+    }
+}
+
+/// In-flight SCRAM exchanges between `server-first` and `server-final`,
+/// keyed by a handshake id handed to the client alongside the
+/// server-first-message. Holding this server-side (rather than trusting the
+/// client to echo back the salt/iterations/stored credentials) is what
+/// keeps the verification in [`clawden_core::sasl::verify_client_proof`]
+/// honest.
+#[derive(Default)]
+pub struct PendingExchanges {
+    exchanges: Mutex<HashMap<String, ServerFirst>>,
+}
+
+impl PendingExchanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, first: ServerFirst) -> String {
+        let mut id_bytes = vec![0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id_bytes);
+        let handshake_id = BASE64.encode(id_bytes);
+        if let Ok(mut exchanges) = self.exchanges.lock() {
+            exchanges.insert(handshake_id.clone(), first);
+        }
+        handshake_id
+    }
+
+    pub fn take(&self, handshake_id: &str) -> Option<ServerFirst> {
+        self.exchanges
+            .lock()
+            .ok()
+            .and_then(|mut exchanges| exchanges.remove(handshake_id))
+    }
+}
+
+/// Extracts the bearer token from `Authorization: Bearer <token>` and
+/// resolves it to the principal that authenticated it via SCRAM, for
+/// handlers that require a verified caller instead of the old
+/// trust-everything model.
+pub fn authenticated_principal(
+    sessions: &SessionStore,
+    headers: &HeaderMap,
+) -> Result<String, String> {
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| "missing Authorization: Bearer <token> header".to_string())?;
+
+    sessions
+        .principal_for(token)
+        .ok_or_else(|| "unknown or expired session token".to_string())
+}
@@ -0,0 +1,66 @@
+//! Lets a request body be either a single item or an array of them, so
+//! registration/binding endpoints can process a whole batch through the same
+//! route instead of needing a separate bulk endpoint per resource.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(item) => vec![item],
+            OneOrMany::Many(items) => items,
+        }
+    }
+}
+
+/// Per-item outcome of a batch request — callers always get one entry per
+/// item they sent, in order, rather than the whole request failing because
+/// one item in the middle was invalid.
+#[derive(Debug, Serialize)]
+pub struct BatchItemResult<T> {
+    pub ok: bool,
+    pub value: Option<T>,
+    pub error: Option<String>,
+}
+
+impl<T> BatchItemResult<T> {
+    pub fn ok(value: T) -> Self {
+        Self {
+            ok: true,
+            value: Some(value),
+            error: None,
+        }
+    }
+
+    pub fn err(error: String) -> Self {
+        Self {
+            ok: false,
+            value: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_a_single_item() {
+        let parsed: OneOrMany<u32> = serde_json::from_str("5").expect("should parse");
+        assert_eq!(parsed.into_vec(), vec![5]);
+    }
+
+    #[test]
+    fn deserializes_an_array() {
+        let parsed: OneOrMany<u32> = serde_json::from_str("[1, 2, 3]").expect("should parse");
+        assert_eq!(parsed.into_vec(), vec![1, 2, 3]);
+    }
+}
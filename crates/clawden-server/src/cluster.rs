@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::manager::AgentRecord;
+
+/// One other `clawden-server` process this node can forward work to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterNode {
+    pub node_id: String,
+    pub base_url: String,
+}
+
+/// Read-only mapping from agent/team ownership to the node that runs them.
+/// Loaded once at startup from `CLAWDEN_NODE_ID`/`CLAWDEN_CLUSTER_PEERS` and
+/// never mutated afterward, the same "durable config object independent of
+/// the rest of the logic" shape as `CredentialStore`/`AdapterRegistry`.
+#[derive(Debug, Clone, Default)]
+pub struct ClusterMetadata {
+    pub local_node_id: String,
+    nodes: Vec<ClusterNode>,
+    agent_owners: HashMap<String, String>,
+    team_owners: HashMap<String, String>,
+}
+
+impl ClusterMetadata {
+    /// A cluster of exactly one node — everything is local, no peers to
+    /// forward to. This is what `main` falls back to when
+    /// `CLAWDEN_CLUSTER_PEERS` isn't set, so single-node deployments are
+    /// unaffected by cluster support existing.
+    pub fn single_node(local_node_id: impl Into<String>) -> Self {
+        Self {
+            local_node_id: local_node_id.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Parses `node_id=base_url` pairs separated by `,`, e.g.
+    /// `CLAWDEN_CLUSTER_PEERS=node-b=http://10.0.0.2:8080,node-c=http://10.0.0.3:8080`.
+    pub fn from_peer_list(local_node_id: impl Into<String>, peers: &str) -> Self {
+        let nodes = peers
+            .split(',')
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(node_id, base_url)| ClusterNode {
+                node_id: node_id.trim().to_string(),
+                base_url: base_url.trim().trim_end_matches('/').to_string(),
+            })
+            .collect();
+        Self {
+            local_node_id: local_node_id.into(),
+            nodes,
+            agent_owners: HashMap::new(),
+            team_owners: HashMap::new(),
+        }
+    }
+
+    pub fn record_agent_owner(&mut self, agent_id: impl Into<String>, node_id: impl Into<String>) {
+        self.agent_owners.insert(agent_id.into(), node_id.into());
+    }
+
+    pub fn record_team_owner(&mut self, team_name: impl Into<String>, node_id: impl Into<String>) {
+        self.team_owners.insert(team_name.into(), node_id.into());
+    }
+
+    /// The peer that owns `agent_id`, or `None` if it's owned locally (or
+    /// unknown, in which case the caller should treat it as local and let
+    /// the usual "not found" error surface).
+    pub fn owning_node_for_agent(&self, agent_id: &str) -> Option<&ClusterNode> {
+        self.node_for_id(self.agent_owners.get(agent_id)?)
+    }
+
+    pub fn owning_node_for_team(&self, team_name: &str) -> Option<&ClusterNode> {
+        self.node_for_id(self.team_owners.get(team_name)?)
+    }
+
+    fn node_for_id(&self, node_id: &str) -> Option<&ClusterNode> {
+        if node_id == self.local_node_id {
+            return None;
+        }
+        self.nodes.iter().find(|node| node.node_id == node_id)
+    }
+
+    pub fn peers(&self) -> &[ClusterNode] {
+        &self.nodes
+    }
+}
+
+/// Thin reqwest wrapper for talking to peer nodes. Keeps one `Client` alive
+/// for the life of the process so peer requests reuse pooled connections,
+/// instead of paying a new-connection cost on every forward.
+#[derive(Clone)]
+pub struct ClusterClient {
+    http: Client,
+}
+
+impl Default for ClusterClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClusterClient {
+    pub fn new() -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        node: &ClusterNode,
+        path: &str,
+    ) -> Result<T, String> {
+        let url = format!("{}{}", node.base_url, path);
+        self.http
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("request to node {} failed: {e}", node.node_id))?
+            .json::<T>()
+            .await
+            .map_err(|e| format!("invalid response from node {}: {e}", node.node_id))
+    }
+
+    pub async fn post_json<B, T>(&self, node: &ClusterNode, path: &str, body: &B) -> Result<T, String>
+    where
+        B: Serialize + ?Sized,
+        T: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", node.base_url, path);
+        self.http
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("request to node {} failed: {e}", node.node_id))?
+            .json::<T>()
+            .await
+            .map_err(|e| format!("invalid response from node {}: {e}", node.node_id))
+    }
+}
+
+/// Forwards an operation to the peer owning its target, and fans aggregate
+/// reads (`list_agents`, `fleet_status`) out across every peer, merging each
+/// response with this node's own local view. This is what lets a fleet span
+/// many hosts while every handler keeps treating the fleet as one list.
+pub struct Broadcasting {
+    pub metadata: ClusterMetadata,
+    pub client: ClusterClient,
+}
+
+impl Broadcasting {
+    pub fn new(metadata: ClusterMetadata) -> Self {
+        Self {
+            metadata,
+            client: ClusterClient::new(),
+        }
+    }
+
+    /// Fetches `GET path` from every peer and appends whatever comes back to
+    /// `local`. A peer that fails to respond is silently dropped — a partial
+    /// fleet-wide view beats failing the whole aggregate over one bad node.
+    pub async fn gather_agents(&self, path: &str, mut local: Vec<AgentRecord>) -> Vec<AgentRecord> {
+        for node in self.metadata.peers() {
+            if let Ok(mut remote) = self.client.get_json::<Vec<AgentRecord>>(node, path).await {
+                local.append(&mut remote);
+            }
+        }
+        local
+    }
+
+    pub fn is_clustered(&self) -> bool {
+        !self.metadata.peers().is_empty()
+    }
+
+    /// Peer health, for the `/cluster/nodes` endpoint. A peer that doesn't
+    /// answer `/health` is reported unhealthy rather than dropped, since an
+    /// operator asking "what does my cluster look like" wants to see the
+    /// dead node, not have it vanish from the list.
+    pub async fn node_statuses(&self) -> Vec<ClusterNodeStatus> {
+        let mut statuses = Vec::with_capacity(self.metadata.peers().len());
+        for node in self.metadata.peers() {
+            let healthy = self
+                .client
+                .get_json::<serde_json::Value>(node, "/health")
+                .await
+                .is_ok();
+            statuses.push(ClusterNodeStatus {
+                node_id: node.node_id.clone(),
+                base_url: node.base_url.clone(),
+                healthy,
+            });
+        }
+        statuses
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterNodeStatus {
+    pub node_id: String,
+    pub base_url: String,
+    pub healthy: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_node_has_no_peers_and_owns_everything() {
+        let metadata = ClusterMetadata::single_node("node-a");
+        assert!(metadata.peers().is_empty());
+        assert!(metadata.owning_node_for_agent("agent-1").is_none());
+    }
+
+    #[test]
+    fn parses_peer_list_and_resolves_ownership() {
+        let mut metadata =
+            ClusterMetadata::from_peer_list("node-a", "node-b=http://10.0.0.2:8080/");
+        metadata.record_agent_owner("agent-1", "node-b");
+        metadata.record_agent_owner("agent-2", "node-a");
+
+        let owner = metadata
+            .owning_node_for_agent("agent-1")
+            .expect("agent-1 is owned by node-b");
+        assert_eq!(owner.node_id, "node-b");
+        assert_eq!(owner.base_url, "http://10.0.0.2:8080");
+
+        assert!(metadata.owning_node_for_agent("agent-2").is_none());
+        assert!(metadata.owning_node_for_agent("agent-3").is_none());
+    }
+}
@@ -0,0 +1,52 @@
+//! Per-request correlation ids, so a client driving a multi-step operation
+//! across several HTTP calls (register an agent, start it, assign its
+//! channels) can tag each call with the same id and pull the whole causal
+//! chain back out of `GET /audit?correlation_id=...` instead of getting
+//! disconnected rows with no way to tie them together.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::extract::Request;
+use axum::http::HeaderName;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+
+pub static CORRELATION_ID_HEADER: HeaderName = HeaderName::from_static("x-correlation-id");
+
+/// The resolved correlation id for the in-flight request, stashed as a
+/// request extension by [`correlation_middleware`] so handlers can pull it
+/// out with `Extension<CorrelationId>`.
+#[derive(Debug, Clone)]
+pub struct CorrelationId(pub String);
+
+static NEXT_GENERATED_ID: AtomicU64 = AtomicU64::new(1);
+
+fn generate_correlation_id() -> String {
+    let now_unix_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_millis();
+    let sequence = NEXT_GENERATED_ID.fetch_add(1, Ordering::Relaxed);
+    format!("{now_unix_ms:x}-{sequence:x}")
+}
+
+/// Resolves this request's correlation id — the caller's `X-Correlation-Id`
+/// header if it sent one, so a multi-call client operation can tie its
+/// requests together, or a freshly generated one otherwise — then opens a
+/// `correlation_id`-tagged span around the rest of the request so every
+/// `tracing` event downstream (including the structured log line
+/// `tracing_subscriber::fmt::layer()` already emits per span) inherits it.
+pub async fn correlation_middleware(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(generate_correlation_id);
+
+    request.extensions_mut().insert(CorrelationId(id.clone()));
+
+    let span = tracing::info_span!("request", correlation_id = %id);
+    next.run(request).instrument(span).await
+}
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+/// A single observable fact about fleet or channel state. Facts are keyed
+/// (see [`Fact::key`]) so that publishing a newer fact for the same subject
+/// replaces the older one in the snapshot rather than accumulating forever.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Fact {
+    AgentState {
+        agent_id: String,
+        runtime: String,
+        state: String,
+    },
+    ChannelConnection {
+        agent_id: String,
+        channel: String,
+        status: String,
+    },
+    BindingConflict {
+        channel_type: String,
+        bot_token_hash: String,
+        instance_ids: Vec<String>,
+    },
+}
+
+impl Fact {
+    fn key(&self) -> String {
+        match self {
+            Fact::AgentState { agent_id, .. } => format!("agent_state:{agent_id}"),
+            Fact::ChannelConnection {
+                agent_id, channel, ..
+            } => format!("channel_connection:{agent_id}:{channel}"),
+            Fact::BindingConflict {
+                channel_type,
+                bot_token_hash,
+                ..
+            } => format!("binding_conflict:{channel_type}:{bot_token_hash}"),
+        }
+    }
+
+    /// Tests this fact against a subscriber's interest pattern, e.g.
+    /// `{ "kind": "agent_state", "runtime": "*", "state": "degraded" }`.
+    /// A `"*"` pattern value, or a key the pattern doesn't mention, matches
+    /// anything; every other key must match the fact's value exactly.
+    fn matches(&self, pattern: &Value) -> bool {
+        let Ok(fact_value) = serde_json::to_value(self) else {
+            return false;
+        };
+        let (Some(fact_map), Some(pattern_map)) = (fact_value.as_object(), pattern.as_object())
+        else {
+            return false;
+        };
+        pattern_map.iter().all(|(key, want)| {
+            want == "*" || fact_map.get(key).map(|have| have == want).unwrap_or(false)
+        })
+    }
+}
+
+struct Subscription {
+    id: u64,
+    pattern: Value,
+    sender: mpsc::UnboundedSender<Fact>,
+}
+
+/// Publish/subscribe fact store behind `AppState`. Mutation sites in the API
+/// handlers call [`Dataspace::publish`] whenever a tracked fact changes;
+/// dashboards open a WebSocket, assert an interest pattern, and receive the
+/// current matching facts once (the replay snapshot) followed by deltas.
+#[derive(Default)]
+pub struct Dataspace {
+    facts: Mutex<HashMap<String, Fact>>,
+    subscriptions: Mutex<Vec<Subscription>>,
+    next_subscription_id: AtomicU64,
+}
+
+impl Dataspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `fact` as the current value for its key and fans it out to
+    /// every subscriber whose pattern matches.
+    pub fn publish(&self, fact: Fact) {
+        let key = fact.key();
+        if let Ok(mut facts) = self.facts.lock() {
+            facts.insert(key, fact.clone());
+        }
+        if let Ok(subscriptions) = self.subscriptions.lock() {
+            for subscription in subscriptions.iter() {
+                if fact.matches(&subscription.pattern) {
+                    let _ = subscription.sender.send(fact.clone());
+                }
+            }
+        }
+    }
+
+    /// Registers interest in `pattern`, returning the subscription id (for
+    /// later [`Dataspace::unsubscribe`]), the current matching facts, and a
+    /// receiver for subsequent deltas.
+    pub fn subscribe(&self, pattern: Value) -> (u64, Vec<Fact>, mpsc::UnboundedReceiver<Fact>) {
+        let snapshot = self
+            .facts
+            .lock()
+            .map(|facts| {
+                facts
+                    .values()
+                    .filter(|fact| fact.matches(&pattern))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = self.next_subscription_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut subscriptions) = self.subscriptions.lock() {
+            subscriptions.push(Subscription {
+                id,
+                pattern,
+                sender,
+            });
+        }
+        (id, snapshot, receiver)
+    }
+
+    pub fn unsubscribe(&self, id: u64) {
+        if let Ok(mut subscriptions) = self.subscriptions.lock() {
+            subscriptions.retain(|subscription| subscription.id != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_pattern_matches_any_runtime() {
+        let pattern = serde_json::json!({ "kind": "agent_state", "runtime": "*", "state": "degraded" });
+        let fact = Fact::AgentState {
+            agent_id: "agent-1".to_string(),
+            runtime: "NullClaw".to_string(),
+            state: "degraded".to_string(),
+        };
+        assert!(fact.matches(&pattern));
+    }
+
+    #[test]
+    fn exact_pattern_rejects_mismatched_state() {
+        let pattern = serde_json::json!({ "kind": "agent_state", "state": "degraded" });
+        let fact = Fact::AgentState {
+            agent_id: "agent-1".to_string(),
+            runtime: "NullClaw".to_string(),
+            state: "running".to_string(),
+        };
+        assert!(!fact.matches(&pattern));
+    }
+
+    #[test]
+    fn subscribe_replays_current_snapshot_then_streams_deltas() {
+        let dataspace = Dataspace::new();
+        dataspace.publish(Fact::AgentState {
+            agent_id: "agent-1".to_string(),
+            runtime: "NullClaw".to_string(),
+            state: "running".to_string(),
+        });
+
+        let (id, snapshot, mut receiver) =
+            dataspace.subscribe(serde_json::json!({ "kind": "agent_state" }));
+        assert_eq!(snapshot.len(), 1);
+
+        dataspace.publish(Fact::AgentState {
+            agent_id: "agent-1".to_string(),
+            runtime: "NullClaw".to_string(),
+            state: "degraded".to_string(),
+        });
+        assert!(receiver.try_recv().is_ok());
+
+        dataspace.unsubscribe(id);
+        dataspace.publish(Fact::AgentState {
+            agent_id: "agent-1".to_string(),
+            runtime: "NullClaw".to_string(),
+            state: "running".to_string(),
+        });
+        assert!(receiver.try_recv().is_err());
+    }
+}
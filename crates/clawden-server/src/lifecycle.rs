@@ -8,11 +8,15 @@ pub enum AgentState {
     Running,
     Stopped,
     Degraded,
+    /// Terminal: the supervisor gave up after exhausting its restart
+    /// budget. Only an explicit `start_agent`/`restart_agent` call (not the
+    /// background monitor) can bring an agent out of this state.
+    Failed,
 }
 
 impl AgentState {
     pub fn can_transition_to(self, next: AgentState) -> bool {
-        use AgentState::{Degraded, Installed, Registered, Running, Stopped};
+        use AgentState::{Degraded, Failed, Installed, Registered, Running, Stopped};
 
         match (self, next) {
             (Registered, Installed) => true,
@@ -20,6 +24,8 @@ impl AgentState {
             (Running, Stopped) => true,
             (Running, Degraded) => true,
             (Degraded, Running) => true,
+            (Degraded, Failed) => true,
+            (Failed, Running) => true,
             (Stopped, Running) => true,
             _ if self == next => true,
             _ => false,
@@ -38,11 +44,14 @@ mod tests {
         assert!(AgentState::Running.can_transition_to(AgentState::Stopped));
         assert!(AgentState::Running.can_transition_to(AgentState::Degraded));
         assert!(AgentState::Degraded.can_transition_to(AgentState::Running));
+        assert!(AgentState::Degraded.can_transition_to(AgentState::Failed));
+        assert!(AgentState::Failed.can_transition_to(AgentState::Running));
     }
 
     #[test]
     fn rejects_invalid_transitions() {
         assert!(!AgentState::Registered.can_transition_to(AgentState::Running));
         assert!(!AgentState::Stopped.can_transition_to(AgentState::Installed));
+        assert!(!AgentState::Running.can_transition_to(AgentState::Failed));
     }
 }
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use clawden_core::{AuditEvent, AuditStore};
+use tracing::field::{Field, Visit};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// A `tracing_subscriber::Layer` that watches the `clawden_lifecycle` target
+/// `LifecycleManager` emits an event on (`agent_id` + `new_state` fields) for
+/// every successful `AgentState::can_transition_to`, and turns each one into
+/// a durable `AuditEvent`. This is what makes every lifecycle transition a
+/// queryable audit record without each call site remembering to invoke
+/// `append_audit` itself.
+pub struct LifecycleAuditLayer {
+    audit: Arc<dyn AuditStore>,
+}
+
+impl LifecycleAuditLayer {
+    pub fn new(audit: Arc<dyn AuditStore>) -> Self {
+        Self { audit }
+    }
+}
+
+#[derive(Default)]
+struct TransitionFields {
+    agent_id: Option<String>,
+    new_state: Option<String>,
+}
+
+impl Visit for TransitionFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "agent_id" => self.agent_id = Some(value.to_string()),
+            "new_state" => self.new_state = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let rendered = format!("{value:?}");
+        match field.name() {
+            "agent_id" => self.agent_id = Some(rendered),
+            "new_state" => self.new_state = Some(rendered),
+            _ => {}
+        }
+    }
+}
+
+/// Captures the `correlation_id` field of a `request` span (see
+/// `crate::correlation`) into its extensions on creation, so `on_event`
+/// below can look it up without re-parsing the span each time.
+#[derive(Default)]
+struct CorrelationIdField(Option<String>);
+
+impl Visit for CorrelationIdField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "correlation_id" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "correlation_id" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+impl<S> Layer<S> for LifecycleAuditLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: Context<'_, S>,
+    ) {
+        let mut correlation = CorrelationIdField::default();
+        attrs.record(&mut correlation);
+        if correlation.0.is_some() {
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(correlation);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() != "clawden_lifecycle" {
+            return;
+        }
+
+        let mut fields = TransitionFields::default();
+        event.record(&mut fields);
+
+        let (Some(agent_id), Some(new_state)) = (fields.agent_id, fields.new_state) else {
+            return;
+        };
+
+        // A transition fired from inside a `request` span (see
+        // `crate::correlation::correlation_middleware`) inherits that
+        // request's correlation id, tying it to the rest of the causal
+        // chain; one fired from a background task (the health monitor, the
+        // scheduler) has none, same as today.
+        let correlation_id = ctx.event_scope(event).and_then(|mut scope| {
+            scope.find_map(|span| {
+                span.extensions()
+                    .get::<CorrelationIdField>()
+                    .and_then(|field| field.0.clone())
+            })
+        });
+
+        let timestamp_unix_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock before UNIX_EPOCH")
+            .as_millis() as u64;
+
+        self.audit.append(AuditEvent {
+            actor: "lifecycle".to_string(),
+            action: "agent.state_change".to_string(),
+            target: format!("{agent_id}:{new_state}"),
+            timestamp_unix_ms,
+            correlation_id,
+        });
+    }
+}
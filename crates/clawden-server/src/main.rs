@@ -1,19 +1,52 @@
+mod admin;
 mod api;
+mod auth;
+mod batch;
+mod cluster;
+mod correlation;
+mod dataspace;
+mod lifecycle_audit;
+mod metrics;
+mod monitor;
+mod pool;
 mod proxy;
+mod routing;
+mod scheduler;
+mod shutdown;
+mod store;
+mod tasks;
+mod telemetry;
+mod tls;
 
 use crate::api::{
-    agent_channels, agent_logs, agent_metrics_history, audit_log, binding_conflicts,
-    channel_instances, channel_matrix, channel_support_matrix, create_binding, create_team,
-    delete_binding, delete_channel_config, deploy_runtime, deploy_status, fan_out_task,
-    fleet_status, get_channel_config, health_summary, list_agents, list_bindings, list_channels,
-    list_endpoints, list_runtimes, list_swarm_tasks, list_teams, proxy_status_endpoint,
-    register_agent, register_endpoint, restart_agent, scan_endpoints, send_task, start_agent,
-    stop_agent, test_channel, upsert_channel_config, AppState,
+    agent_channels, agent_logs, agent_logs_stream, agent_metrics_history, agent_metrics_stream,
+    agent_tasks, audit_log, binding_conflicts, channel_instances, channel_matrix,
+    channel_support_matrix, configure_restart, create_binding, create_schedule_entry, create_team,
+    delete_binding, delete_channel_config, delete_schedule_entry, deploy_runtime, deploy_status,
+    enroll_agent, fan_out_task, fleet_status, get_ca_certificate, get_channel_config, get_task,
+    health_summary, list_agents, list_bindings, list_channels, list_cluster_nodes, list_endpoints,
+    list_runtimes, list_schedule_entries, list_swarm_tasks, list_tasks, list_teams,
+    prometheus_metrics, proxy_status_endpoint, register_agent, register_endpoint, relay_connect,
+    restart_agent, runtime_pool_status, scan_endpoints, scram_finish, scram_start, send_task,
+    start_agent, stop_agent, subscribe_dataspace, test_channel, upsert_channel_config, AppState,
 };
+use crate::admin::{
+    admin_list_agents, admin_refresh_health, admin_register_agent, admin_route_task,
+    admin_start_agent, admin_stop_agent,
+};
+use crate::auth::{PendingExchanges, SessionStore};
+use crate::cluster::{Broadcasting, ClusterMetadata};
+use crate::correlation::correlation_middleware;
+use crate::dataspace::Dataspace;
+use crate::pool::RuntimePool;
+use crate::proxy::RelayRegistry;
+use crate::telemetry::TelemetryRegistry;
+use crate::tls::{CertificateAuthority, TlsConfig};
 use axum::{routing::get, Json, Router};
+use clawden_core::sasl::CredentialStore;
 use clawden_core::{
-    append_audit, AgentState, AuditEvent, AuditLog, ChannelStore, DiscoveryService,
-    LifecycleManager, SwarmCoordinator,
+    append_audit, AgentState, AuditEvent, AuditLog, AuditStore, ChannelStore, DiscoveryService,
+    LifecycleManager, SqliteAuditStore, SwarmCoordinator,
 };
 use serde::Serialize;
 use std::net::SocketAddr;
@@ -22,6 +55,7 @@ use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::info;
+use tracing_subscriber::prelude::*;
 
 #[derive(Debug, Serialize)]
 struct HealthResponse {
@@ -45,6 +79,7 @@ fn build_app(shared_state: AppState) -> Router {
         .route("/agents/{agent_id}/stop", axum::routing::post(stop_agent))
         .route("/agents/health", get(health_summary))
         .route("/fleet/status", get(fleet_status))
+        .route("/metrics", get(prometheus_metrics))
         .route("/task/send", axum::routing::post(send_task))
         .route("/audit", get(audit_log))
         .route("/discovery/endpoints", get(list_endpoints))
@@ -57,7 +92,9 @@ fn build_app(shared_state: AppState) -> Router {
         .route("/swarm/teams/create", axum::routing::post(create_team))
         .route("/swarm/fan-out", axum::routing::post(fan_out_task))
         .route("/swarm/tasks", get(list_swarm_tasks))
+        .route("/cluster/nodes", get(list_cluster_nodes))
         .route("/runtimes", get(list_runtimes))
+        .route("/runtimes/pool", get(runtime_pool_status))
         .route(
             "/runtimes/{runtime}/deploy",
             axum::routing::post(deploy_runtime),
@@ -67,11 +104,31 @@ fn build_app(shared_state: AppState) -> Router {
             "/agents/{agent_id}/restart",
             axum::routing::post(restart_agent),
         )
+        .route(
+            "/agents/{agent_id}/restart-policy",
+            axum::routing::post(configure_restart),
+        )
+        .route(
+            "/scheduler/entries",
+            get(list_schedule_entries).post(create_schedule_entry),
+        )
+        .route(
+            "/scheduler/entries/{entry_id}",
+            axum::routing::delete(delete_schedule_entry),
+        )
+        .route("/tasks", get(list_tasks))
+        .route("/tasks/{task_id}", get(get_task))
+        .route("/agents/{agent_id}/tasks", get(agent_tasks))
         .route("/agents/{agent_id}/logs", get(agent_logs))
+        .route("/agents/{agent_id}/logs/stream", get(agent_logs_stream))
         .route(
             "/agents/{agent_id}/metrics/history",
             get(agent_metrics_history),
         )
+        .route(
+            "/agents/{agent_id}/metrics/stream",
+            get(agent_metrics_stream),
+        )
         .route(
             "/agents/{agent_id}/proxy-status/{channel_type}",
             get(proxy_status_endpoint),
@@ -100,26 +157,155 @@ fn build_app(shared_state: AppState) -> Router {
             axum::routing::delete(delete_binding),
         )
         .route("/channels/bindings/conflicts", get(binding_conflicts))
+        .route("/subscribe", get(subscribe_dataspace))
+        .route("/auth/scram/start", axum::routing::post(scram_start))
+        .route("/auth/scram/finish", axum::routing::post(scram_finish))
+        .route("/relay/connect", get(relay_connect))
+        .route(
+            "/admin/agents",
+            get(admin_list_agents).post(admin_register_agent),
+        )
+        .route(
+            "/admin/agents/{agent_id}/start",
+            axum::routing::post(admin_start_agent),
+        )
+        .route(
+            "/admin/agents/{agent_id}/stop",
+            axum::routing::post(admin_stop_agent),
+        )
+        .route(
+            "/admin/agents/{agent_id}/refresh-health",
+            axum::routing::post(admin_refresh_health),
+        )
+        .route("/admin/route", axum::routing::post(admin_route_task))
+        .route("/agents/{agent_id}/enroll", axum::routing::post(enroll_agent))
+        .route("/ca", get(get_ca_certificate))
+        .layer(axum::middleware::from_fn(correlation_middleware))
         .with_state(shared_state)
 }
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter("info")
-        .with_target(false)
-        .compact()
+    let audit_store: Arc<dyn AuditStore> = match std::env::var("CLAWDEN_AUDIT_DB") {
+        Ok(path) => match SqliteAuditStore::open(std::path::Path::new(&path)) {
+            Ok(store) => Arc::new(store),
+            Err(_) => Arc::new(AuditLog::default()),
+        },
+        Err(_) => Arc::new(AuditLog::default()),
+    };
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new("info"))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .compact(),
+        )
+        .with(crate::lifecycle_audit::LifecycleAuditLayer::new(
+            audit_store.clone(),
+        ))
         .init();
 
-    let audit_store = Arc::new(AuditLog::default());
-    let registry = clawden_adapters::builtin_registry();
-    let manager = LifecycleManager::new(registry.adapters_map());
+    let store: Arc<dyn crate::store::Store> = match std::env::var("CLAWDEN_DB_PATH") {
+        Ok(path) => match crate::store::SqliteStore::open(std::path::Path::new(&path)) {
+            Ok(store) => {
+                info!(%path, "loaded persistent store");
+                Arc::new(store)
+            }
+            Err(err) => {
+                info!(%path, %err, "failed to open persistent store, falling back to in-memory");
+                Arc::new(crate::store::MemoryStore::new())
+            }
+        },
+        Err(_) => Arc::new(crate::store::MemoryStore::new()),
+    };
+
+    let registry = Arc::new(clawden_adapters::builtin_registry());
+    let mut manager = LifecycleManager::new(registry.adapters_map());
+    let restored_agents = store.load_agents();
+    let restored_agent_count = restored_agents.len();
+    for agent in restored_agents {
+        manager.restore_agent(agent);
+    }
+
+    let mut channels = ChannelStore::new();
+    for config in store.load_channel_configs() {
+        channels.restore_config(config);
+    }
+    for binding in store.load_channel_bindings() {
+        channels.restore_binding(binding);
+    }
+
+    let mut discovery = DiscoveryService::new();
+    for endpoint in store.load_discovered_endpoints() {
+        discovery.restore_endpoint(endpoint);
+    }
+
+    let mut swarm = SwarmCoordinator::new();
+    for team in store.load_swarm_teams() {
+        swarm.restore_team(team);
+    }
+
+    if restored_agent_count > 0 {
+        info!(
+            restored_agent_count,
+            "reloaded fleet state from the persistent store"
+        );
+    }
+
+    let credentials = Arc::new(CredentialStore::new());
+    if let (Ok(username), Ok(password)) = (
+        std::env::var("CLAWDEN_ADMIN_USERNAME"),
+        std::env::var("CLAWDEN_ADMIN_PASSWORD"),
+    ) {
+        credentials.enroll(&username, &password);
+        info!(%username, "enrolled SCRAM principal from environment");
+    } else {
+        info!("no CLAWDEN_ADMIN_USERNAME/CLAWDEN_ADMIN_PASSWORD set — no principals enrolled yet");
+    }
+
+    let node_id = std::env::var("CLAWDEN_NODE_ID").unwrap_or_else(|_| "local".to_string());
+    let cluster_metadata = match std::env::var("CLAWDEN_CLUSTER_PEERS") {
+        Ok(peers) if !peers.trim().is_empty() => {
+            ClusterMetadata::from_peer_list(node_id.clone(), &peers)
+        }
+        _ => ClusterMetadata::single_node(node_id.clone()),
+    };
+    if cluster_metadata.peers().is_empty() {
+        info!(%node_id, "running as a single-node cluster — no CLAWDEN_CLUSTER_PEERS set");
+    } else {
+        info!(%node_id, peer_count = cluster_metadata.peers().len(), "joined cluster");
+    }
+
+    let runtime_pool_size = std::env::var("CLAWDEN_RUNTIME_POOL_SIZE")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let pool = RuntimePool::new(registry.clone(), runtime_pool_size);
+    pool.prewarm().await;
+    if runtime_pool_size > 0 {
+        info!(runtime_pool_size, "prewarmed runtime pool");
+    }
+
     let shared_state = AppState {
         manager: Arc::new(RwLock::new(manager)),
         audit: audit_store.clone(),
-        discovery: Arc::new(RwLock::new(DiscoveryService::new())),
-        swarm: Arc::new(RwLock::new(SwarmCoordinator::new())),
-        channels: Arc::new(RwLock::new(ChannelStore::new())),
+        discovery: Arc::new(RwLock::new(discovery)),
+        swarm: Arc::new(RwLock::new(swarm)),
+        channels: Arc::new(RwLock::new(channels)),
+        dataspace: Arc::new(Dataspace::new()),
+        credentials,
+        sessions: Arc::new(SessionStore::new()),
+        pending_exchanges: Arc::new(PendingExchanges::new()),
+        cluster: Arc::new(Broadcasting::new(cluster_metadata)),
+        telemetry: Arc::new(TelemetryRegistry::new()),
+        relay: Arc::new(RelayRegistry::new()),
+        pool,
+        scheduler: Arc::new(RwLock::new(crate::scheduler::Scheduler::new())),
+        ca: Arc::new(CertificateAuthority::new()),
+        tls: Arc::new(TlsConfig::from_env()),
+        store,
+        tasks: Arc::new(RwLock::new(crate::tasks::TaskStore::new())),
     };
 
     let health_interval_ms = std::env::var("CLAWDEN_HEALTH_INTERVAL_MS")
@@ -130,30 +316,153 @@ async fn main() {
         .ok()
         .and_then(|value| value.parse::<u64>().ok())
         .unwrap_or(1_000);
+    let degraded_failure_threshold = std::env::var("CLAWDEN_DEGRADED_FAILURE_THRESHOLD")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(1);
+    let channel_recovery_threshold = std::env::var("CLAWDEN_CHANNEL_RECOVERY_THRESHOLD")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(2);
+
+    let shutdown = Arc::new(crate::shutdown::ShutdownCoordinator::new());
+    let mut monitor_shutdown = shutdown.subscribe();
 
     let monitor_manager = shared_state.manager.clone();
     let monitor_audit = shared_state.audit.clone();
+    let monitor_dataspace = shared_state.dataspace.clone();
+    let monitor_channels = shared_state.channels.clone();
+    let monitor_telemetry = shared_state.telemetry.clone();
+    let channel_monitor = Arc::new(crate::monitor::ChannelMonitor::new());
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_millis(health_interval_ms));
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = monitor_shutdown.changed() => {
+                    info!("health monitor stopping for shutdown");
+                    return;
+                }
+            }
             let mut manager = monitor_manager.write().await;
             manager
-                .refresh_health_with_base_backoff_ms(recovery_base_backoff_ms)
+                .refresh_health_with_thresholds(recovery_base_backoff_ms, degraded_failure_threshold)
                 .await;
             let recovered = manager.recover_degraded().await;
+            let agents = manager.list_agents();
+
+            let tick_unix_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock before UNIX_EPOCH")
+                .as_millis() as u64;
+            for agent in &agents {
+                if let Some(metrics) = manager.sample_metrics(&agent.id).await {
+                    monitor_telemetry.record_metrics(&agent.id, metrics, tick_unix_ms);
+                }
+            }
             drop(manager);
 
+            for agent in &agents {
+                monitor_dataspace.publish(crate::dataspace::Fact::AgentState {
+                    agent_id: agent.id.clone(),
+                    runtime: format!("{:?}", agent.runtime),
+                    state: format!("{:?}", agent.state),
+                });
+            }
+
+            let now_unix_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock before UNIX_EPOCH")
+                .as_millis() as u64;
+            let mut channels = monitor_channels.write().await;
+            channel_monitor.tick(
+                &mut channels,
+                &agents,
+                &monitor_audit,
+                recovery_base_backoff_ms,
+                channel_recovery_threshold,
+                now_unix_ms,
+            );
+            for agent in &agents {
+                for config in channels.get_agent_channels(&agent.id) {
+                    monitor_dataspace.publish(crate::dataspace::Fact::ChannelConnection {
+                        agent_id: agent.id.clone(),
+                        channel: config.instance_name.clone(),
+                        status: format!(
+                            "{:?}",
+                            channels.get_connection_status(&agent.id, &config.instance_name)
+                        ),
+                    });
+                }
+            }
+            drop(channels);
+
             append_audit(&monitor_audit, "api", "health.tick", "fleet");
             info!(
                 checked_agents = recovered.len(),
                 interval_ms = health_interval_ms,
                 recovery_base_backoff_ms,
+                degraded_failure_threshold,
+                channel_recovery_threshold,
                 "health monitor tick"
             );
         }
     });
 
+    let scheduler_interval_ms = std::env::var("CLAWDEN_SCHEDULER_INTERVAL_MS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(1_000);
+
+    let mut scheduler_shutdown = shutdown.subscribe();
+    let scheduler_manager = shared_state.manager.clone();
+    let scheduler_swarm = shared_state.swarm.clone();
+    let scheduler_handle = shared_state.scheduler.clone();
+    let scheduler_audit = shared_state.audit.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(scheduler_interval_ms));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = scheduler_shutdown.changed() => {
+                    info!("scheduler stopping for shutdown");
+                    return;
+                }
+            }
+            let now_unix_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock before UNIX_EPOCH")
+                .as_millis() as u64;
+            let mut manager = scheduler_manager.write().await;
+            let mut swarm = scheduler_swarm.write().await;
+            let mut scheduler = scheduler_handle.write().await;
+            let fired = scheduler.tick(&mut manager, &mut swarm, now_unix_ms).await;
+            drop(scheduler);
+            drop(swarm);
+            drop(manager);
+
+            for result in &fired {
+                let outcome = match &result.response {
+                    Ok(_) => "ok",
+                    Err(_) => "retry",
+                };
+                append_audit(
+                    &scheduler_audit,
+                    "api",
+                    "scheduler.fire",
+                    &format!("{} {outcome}", result.entry_id),
+                );
+            }
+        }
+    });
+
+    let shutdown_manager = shared_state.manager.clone();
+    let shutdown_audit = shared_state.audit.clone();
+    let shutdown_stop_agents = std::env::var("CLAWDEN_SHUTDOWN_STOP_AGENTS")
+        .ok()
+        .and_then(|value| value.parse::<bool>().ok())
+        .unwrap_or(true);
+
     let app = build_app(shared_state);
     let port = std::env::var("CLAWDEN_SERVER_PORT")
         .ok()
@@ -169,6 +478,7 @@ async fn main() {
             .duration_since(UNIX_EPOCH)
             .expect("system clock before UNIX_EPOCH")
             .as_millis() as u64,
+        correlation_id: None,
     };
     audit_store.append(startup_event);
     if let Some(last) = audit_store.list().last() {
@@ -205,8 +515,29 @@ async fn main() {
         .expect("failed to bind TCP listener");
 
     axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown.signal())
         .await
         .expect("server failed unexpectedly");
+
+    info!("shutdown signal received, draining fleet");
+
+    if shutdown_stop_agents {
+        let mut manager = shutdown_manager.write().await;
+        for agent in manager.list_agents() {
+            if agent.state != AgentState::Running {
+                continue;
+            }
+            if let Err(e) = manager.stop_agent(&agent.id).await {
+                info!(agent_id = %agent.id, error = %e, "failed to stop agent during shutdown");
+            }
+        }
+    } else {
+        info!("CLAWDEN_SHUTDOWN_STOP_AGENTS=false — leaving agents running across restart");
+    }
+
+    append_audit(&shutdown_audit, "system", "server.stop", "clawden-server");
+    shutdown_audit.flush();
+    info!("clawden server stopped");
 }
 
 #[cfg(test)]
@@ -217,14 +548,27 @@ mod tests {
     use tower::util::ServiceExt;
 
     fn test_state() -> AppState {
-        let registry = clawden_adapters::builtin_registry();
+        let registry = Arc::new(clawden_adapters::builtin_registry());
         let manager = LifecycleManager::new(registry.adapters_map());
         AppState {
             manager: Arc::new(RwLock::new(manager)),
-            audit: Arc::new(AuditLog::default()),
+            audit: Arc::new(AuditLog::default()) as Arc<dyn AuditStore>,
             discovery: Arc::new(RwLock::new(DiscoveryService::new())),
             swarm: Arc::new(RwLock::new(SwarmCoordinator::new())),
             channels: Arc::new(RwLock::new(ChannelStore::new())),
+            dataspace: Arc::new(crate::dataspace::Dataspace::new()),
+            credentials: Arc::new(CredentialStore::new()),
+            sessions: Arc::new(SessionStore::new()),
+            pending_exchanges: Arc::new(PendingExchanges::new()),
+            cluster: Arc::new(Broadcasting::new(ClusterMetadata::single_node("local"))),
+            telemetry: Arc::new(TelemetryRegistry::new()),
+            relay: Arc::new(RelayRegistry::new()),
+            pool: RuntimePool::new(registry, 0),
+            scheduler: Arc::new(RwLock::new(crate::scheduler::Scheduler::new())),
+            ca: Arc::new(CertificateAuthority::new()),
+            tls: Arc::new(TlsConfig::from_env()),
+            store: Arc::new(crate::store::MemoryStore::new()),
+            tasks: Arc::new(RwLock::new(crate::tasks::TaskStore::new())),
         }
     }
 
@@ -236,9 +580,12 @@ mod tests {
             "/agents",
             "/agents/health",
             "/fleet/status",
+            "/metrics",
             "/runtimes",
             "/channels",
             "/audit",
+            "/admin/agents",
+            "/ca",
         ];
 
         for endpoint in endpoints {
@@ -1,17 +1,119 @@
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 use clawden_adapters::AdapterRegistry;
 use clawden_core::{
     AgentConfig, AgentHandle, AgentMessage, AgentResponse, ClawRuntime, HealthStatus,
 };
-use serde::Serialize;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::audit::{AuditEvent, AuditLog};
 use crate::lifecycle::AgentState;
+use crate::metrics::{AdapterOp, Metrics};
+use crate::routing::{RoundRobinStrategy, RoutingStrategy};
+
+/// Structured errors for [`LifecycleManager`]'s agent-facing methods.
+/// Replaces the `Result<_, String>` these used to return so callers can
+/// match on an error category (e.g. to pick an HTTP status code) instead of
+/// string-matching a message. `Display` is worded to match the plain
+/// strings these methods returned before, so existing logs/audit entries
+/// that interpolate `{e}` read the same.
+#[derive(Debug, Error, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LifecycleError {
+    #[error("agent {0} not found")]
+    AgentNotFound(String),
+    #[error("no adapter registered for runtime {0:?}")]
+    NoAdapter(ClawRuntime),
+    #[error("invalid state transition from {from:?} to {to:?}")]
+    InvalidTransition { from: AgentState, to: AgentState },
+    #[error("agent {0} is not running")]
+    AgentNotRunning(String),
+    #[error("no running agent matches required capabilities")]
+    NoEligibleAgent { required: Vec<String> },
+    #[error("failed to start agent: {0}")]
+    AdapterStart(String),
+    #[error("failed to stop agent: {0}")]
+    AdapterStop(String),
+    #[error("send failed: {0}")]
+    SendFailed(String),
+}
+
+impl LifecycleError {
+    /// The snake_case label [`Metrics::record_routing_failure`] groups this
+    /// error under — matches the `kind` tag `#[serde(...)]` already gives
+    /// this enum, so a metric's `reason` and an audited error's `kind` read
+    /// the same.
+    fn metric_reason(&self) -> &'static str {
+        match self {
+            LifecycleError::AgentNotFound(_) => "agent_not_found",
+            LifecycleError::NoAdapter(_) => "no_adapter",
+            LifecycleError::InvalidTransition { .. } => "invalid_transition",
+            LifecycleError::AgentNotRunning(_) => "agent_not_running",
+            LifecycleError::NoEligibleAgent { .. } => "no_eligible_agent",
+            LifecycleError::AdapterStart(_) => "adapter_start",
+            LifecycleError::AdapterStop(_) => "adapter_stop",
+            LifecycleError::SendFailed(_) => "send_failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartPolicy {
+    /// The supervisor never auto-restarts this agent; a degraded agent sits
+    /// in [`AgentState::Degraded`] until an operator intervenes.
+    Never,
+    /// Auto-restart on a failed health check, up to [`MAX_RESTART_ATTEMPTS`]
+    /// consecutive failures, after which the agent moves to
+    /// [`AgentState::Failed`] and retries stop.
+    OnFailure,
+    /// Like `OnFailure`, but never gives up — the attempt cutoff is ignored
+    /// and the agent keeps retrying with the same backoff schedule forever.
+    Always,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy::OnFailure
+    }
+}
+
+/// Consecutive-failure counter the supervisor uses to back off restart
+/// attempts for a crashed agent. Kept out of [`AgentRecord`] since it's
+/// internal scheduling state, not something API clients read or set —
+/// mirrors how `handles` is tracked alongside `agents` rather than on the
+/// record itself.
+#[derive(Debug, Default)]
+struct Backoff {
+    consecutive_failures: u32,
+}
 
-#[derive(Debug, Clone, Serialize)]
+impl Backoff {
+    /// Records one more failed restart attempt and returns the delay
+    /// (`base * 2^(failures - 1)`, capped, plus up to 20% jitter) before the next
+    /// attempt should run.
+    fn record_failure(&mut self, base_ms: u64) -> u64 {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let delay = backoff_ms(base_ms, self.consecutive_failures);
+        delay.saturating_add(jitter_ms(delay))
+    }
+}
+
+/// Consecutive failed restart attempts after which an [`AgentState::Degraded`]
+/// agent on [`RestartPolicy::OnFailure`] is given up on and moved to
+/// [`AgentState::Failed`].
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+
+/// Base delay fed to [`Backoff::record_failure`] for restart attempts,
+/// separate from the health-probe backoff passed into `refresh_health_*`.
+const RESTART_BASE_BACKOFF_MS: u64 = 2_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentRecord {
     pub id: String,
     pub name: String,
@@ -23,14 +125,26 @@ pub struct AgentRecord {
     pub consecutive_health_failures: u32,
     pub last_health_check_unix_ms: Option<u64>,
     pub next_recovery_attempt_unix_ms: Option<u64>,
+    pub restart_policy: RestartPolicy,
+    pub restart_count: u64,
+    pub last_restart_unix_ms: Option<u64>,
+    /// Fingerprint of the client certificate [`crate::tls::CertificateAuthority`]
+    /// last issued this agent via `POST /agents/:id/enroll`. `None` until
+    /// enrolled — the `send_task` handler only enforces a fingerprint match
+    /// when the server's [`crate::tls::TlsConfig::require_client_cert`] is set
+    /// and the request names this agent directly; `route_and_send` does not
+    /// check it.
+    pub certificate_fingerprint: Option<String>,
 }
 
 pub struct LifecycleManager {
     adapters: AdapterRegistry,
     agents: HashMap<String, AgentRecord>,
     handles: HashMap<String, AgentHandle>,
+    restart_backoff: HashMap<String, Backoff>,
     next_id: AtomicU64,
-    round_robin_index: usize,
+    routing_strategy: Box<dyn RoutingStrategy>,
+    metrics: Arc<Metrics>,
 }
 
 impl LifecycleManager {
@@ -39,11 +153,27 @@ impl LifecycleManager {
             adapters,
             agents: HashMap::new(),
             handles: HashMap::new(),
+            restart_backoff: HashMap::new(),
             next_id: AtomicU64::new(1),
-            round_robin_index: 0,
+            routing_strategy: Box::new(RoundRobinStrategy::default()),
+            metrics: Arc::new(Metrics::new()),
         }
     }
 
+    /// Swaps the dispatch policy [`Self::route_and_send`] uses to pick an
+    /// agent when the caller doesn't name one, so a deployment can move from
+    /// round-robin to e.g. [`crate::routing::LeastLoadedStrategy`] without a
+    /// code change — just a different value passed in at startup.
+    pub fn set_routing_strategy(&mut self, strategy: Box<dyn RoutingStrategy>) {
+        self.routing_strategy = strategy;
+    }
+
+    /// Prometheus text exposition of every counter, gauge, and histogram
+    /// tracked for this fleet, for `GET /metrics` to serve verbatim.
+    pub fn render_prometheus(&self) -> String {
+        self.metrics.render(&self.list_agents())
+    }
+
     pub fn register_agent(
         &mut self,
         name: String,
@@ -62,36 +192,92 @@ impl LifecycleManager {
             consecutive_health_failures: 0,
             last_health_check_unix_ms: None,
             next_recovery_attempt_unix_ms: None,
+            restart_policy: RestartPolicy::default(),
+            restart_count: 0,
+            last_restart_unix_ms: None,
+            certificate_fingerprint: None,
         };
         self.agents.insert(id, record.clone());
         record
     }
 
+    /// Reinserts an `AgentRecord` loaded from [`crate::store::Store`] on
+    /// boot, bypassing `register_agent`'s id generation since the record
+    /// already has one. Advances `next_id` past the restored id's numeric
+    /// suffix so a freshly registered agent never collides with it.
+    pub fn restore_agent(&mut self, record: AgentRecord) {
+        if let Some(suffix) = record.id.strip_prefix("agent-") {
+            if let Ok(n) = suffix.parse::<u64>() {
+                self.next_id.fetch_max(n + 1, Ordering::Relaxed);
+            }
+        }
+        self.agents.insert(record.id.clone(), record);
+    }
+
+    /// Sets the auto-restart policy the supervisor applies to a degraded
+    /// agent, clearing any accumulated backoff so the next failure starts
+    /// counting fresh under the new policy.
+    pub fn configure_restart(
+        &mut self,
+        agent_id: &str,
+        policy: RestartPolicy,
+    ) -> Result<AgentRecord, LifecycleError> {
+        let Some(record) = self.agents.get_mut(agent_id) else {
+            return Err(LifecycleError::AgentNotFound(agent_id.to_string()));
+        };
+        record.restart_policy = policy;
+        self.restart_backoff.remove(agent_id);
+        Ok(record.clone())
+    }
+
+    /// Records the fingerprint of the client certificate
+    /// [`crate::tls::CertificateAuthority::enroll`] just issued `agent_id`,
+    /// called from the `POST /agents/:id/enroll` handler right after issuance.
+    pub fn set_certificate_fingerprint(
+        &mut self,
+        agent_id: &str,
+        fingerprint: String,
+    ) -> Result<AgentRecord, LifecycleError> {
+        let Some(record) = self.agents.get_mut(agent_id) else {
+            return Err(LifecycleError::AgentNotFound(agent_id.to_string()));
+        };
+        record.certificate_fingerprint = Some(fingerprint);
+        Ok(record.clone())
+    }
+
     pub fn list_agents(&self) -> Vec<AgentRecord> {
         let mut agents: Vec<_> = self.agents.values().cloned().collect();
         agents.sort_by(|a, b| a.id.cmp(&b.id));
         agents
     }
 
-    pub async fn start_agent(&mut self, agent_id: &str) -> Result<AgentRecord, String> {
+    /// Samples live CPU/RSS/queue-depth for a running agent via its adapter,
+    /// for use by the telemetry streaming endpoints. Returns `None` for an
+    /// unknown or not-running agent rather than an error — callers treat
+    /// "nothing to sample yet" as routine, not exceptional.
+    pub async fn sample_metrics(&self, agent_id: &str) -> Option<clawden_core::AgentMetrics> {
+        let record = self.agents.get(agent_id)?;
+        let handle = self.handles.get(agent_id)?;
+        let adapter = self.adapters.get(&record.runtime)?;
+        adapter.metrics(handle).await.ok()
+    }
+
+    pub async fn start_agent(&mut self, agent_id: &str) -> Result<AgentRecord, LifecycleError> {
         let Some(record) = self.agents.get_mut(agent_id) else {
-            return Err(format!("agent {agent_id} not found"));
+            return Err(LifecycleError::AgentNotFound(agent_id.to_string()));
         };
 
         let Some(adapter) = self.adapters.get(&record.runtime) else {
-            return Err(format!(
-                "no adapter registered for runtime {:?}",
-                record.runtime
-            ));
+            return Err(LifecycleError::NoAdapter(record.runtime.clone()));
         };
 
         if !record.state.can_transition_to(AgentState::Running)
             && record.state != AgentState::Registered
         {
-            return Err(format!(
-                "invalid state transition from {:?} to running",
-                record.state
-            ));
+            return Err(LifecycleError::InvalidTransition {
+                from: record.state,
+                to: AgentState::Running,
+            });
         }
 
         let config = AgentConfig {
@@ -100,44 +286,75 @@ impl LifecycleManager {
             model: None,
         };
 
-        let handle = adapter
-            .start(&config)
-            .await
-            .map_err(|e| format!("failed to start agent: {e}"))?;
+        let started_at = Instant::now();
+        let handle = adapter.start(&config).await;
+        self.metrics
+            .record_adapter_latency(AdapterOp::Start, &record.runtime, started_at.elapsed());
+        let handle = handle.map_err(|e| LifecycleError::AdapterStart(e.to_string()))?;
+
+        record.state = AgentState::Running;
+        record.health = HealthStatus::Unknown;
+        emit_transition(agent_id, AgentState::Running);
+        self.handles.insert(agent_id.to_string(), handle);
+        Ok(record.clone())
+    }
+
+    /// Same transition and bookkeeping as [`Self::start_agent`], but skips
+    /// `adapter.start` entirely in favor of a handle a caller already has in
+    /// hand — a warm instance checked out of `crate::pool::RuntimePool`.
+    pub fn start_agent_with_handle(
+        &mut self,
+        agent_id: &str,
+        handle: AgentHandle,
+    ) -> Result<AgentRecord, LifecycleError> {
+        let Some(record) = self.agents.get_mut(agent_id) else {
+            return Err(LifecycleError::AgentNotFound(agent_id.to_string()));
+        };
+
+        if !record.state.can_transition_to(AgentState::Running)
+            && record.state != AgentState::Registered
+        {
+            return Err(LifecycleError::InvalidTransition {
+                from: record.state,
+                to: AgentState::Running,
+            });
+        }
 
         record.state = AgentState::Running;
         record.health = HealthStatus::Unknown;
+        emit_transition(agent_id, AgentState::Running);
         self.handles.insert(agent_id.to_string(), handle);
         Ok(record.clone())
     }
 
-    pub async fn stop_agent(&mut self, agent_id: &str) -> Result<AgentRecord, String> {
+    pub async fn stop_agent(&mut self, agent_id: &str) -> Result<AgentRecord, LifecycleError> {
         let Some(record) = self.agents.get_mut(agent_id) else {
-            return Err(format!("agent {agent_id} not found"));
+            return Err(LifecycleError::AgentNotFound(agent_id.to_string()));
         };
 
         let Some(handle) = self.handles.get(agent_id) else {
             if record.state.can_transition_to(AgentState::Stopped) {
                 record.state = AgentState::Stopped;
+                emit_transition(agent_id, AgentState::Stopped);
             }
             return Ok(record.clone());
         };
 
         let Some(adapter) = self.adapters.get(&record.runtime) else {
-            return Err(format!(
-                "no adapter registered for runtime {:?}",
-                record.runtime
-            ));
+            return Err(LifecycleError::NoAdapter(record.runtime.clone()));
         };
 
-        adapter
-            .stop(handle)
-            .await
-            .map_err(|e| format!("failed to stop agent: {e}"))?;
+        let runtime = record.runtime.clone();
+        let stopped_at = Instant::now();
+        let stop_result = adapter.stop(handle).await;
+        self.metrics
+            .record_adapter_latency(AdapterOp::Stop, &runtime, stopped_at.elapsed());
+        stop_result.map_err(|e| LifecycleError::AdapterStop(e.to_string()))?;
 
         self.handles.remove(agent_id);
         if record.state.can_transition_to(AgentState::Stopped) {
             record.state = AgentState::Stopped;
+            emit_transition(agent_id, AgentState::Stopped);
         }
         Ok(record.clone())
     }
@@ -150,6 +367,20 @@ impl LifecycleManager {
         &mut self,
         base_backoff_ms: u64,
     ) -> Vec<AgentRecord> {
+        self.refresh_health_with_thresholds(base_backoff_ms, 1).await
+    }
+
+    /// Same probe loop as [`Self::refresh_health_with_base_backoff_ms`], but
+    /// an agent only falls into [`AgentState::Degraded`] once
+    /// `degraded_failure_threshold` consecutive probes have failed, rather
+    /// than on the first one. This lets the background monitor tolerate a
+    /// transient blip without flapping an agent's state.
+    pub async fn refresh_health_with_thresholds(
+        &mut self,
+        base_backoff_ms: u64,
+        degraded_failure_threshold: u32,
+    ) -> Vec<AgentRecord> {
+        let threshold = degraded_failure_threshold.max(1);
         let now = current_unix_ms();
         let ids: Vec<String> = self.agents.keys().cloned().collect();
         for id in ids {
@@ -165,7 +396,12 @@ impl LifecycleManager {
                 record.health = HealthStatus::Unknown;
                 continue;
             };
-            match adapter.health(handle).await {
+            let runtime = record.runtime.clone();
+            let probed_at = Instant::now();
+            let health_result = adapter.health(handle).await;
+            self.metrics
+                .record_adapter_latency(AdapterOp::Health, &runtime, probed_at.elapsed());
+            match health_result {
                 Ok(health) => {
                     record.health = health;
                     record.consecutive_health_failures = 0;
@@ -177,8 +413,11 @@ impl LifecycleManager {
                         record.consecutive_health_failures.saturating_add(1);
                     record.next_recovery_attempt_unix_ms =
                         Some(now + backoff_ms(base_backoff_ms, record.consecutive_health_failures));
-                    if record.state.can_transition_to(AgentState::Degraded) {
+                    if record.consecutive_health_failures >= threshold
+                        && record.state.can_transition_to(AgentState::Degraded)
+                    {
                         record.state = AgentState::Degraded;
+                        emit_transition(&id, AgentState::Degraded);
                     }
                 }
             }
@@ -196,6 +435,9 @@ impl LifecycleManager {
                 if record.state != AgentState::Degraded {
                     return None;
                 }
+                if record.restart_policy == RestartPolicy::Never {
+                    return None;
+                }
                 let due = record
                     .next_recovery_attempt_unix_ms
                     .map(|at| now >= at)
@@ -237,13 +479,28 @@ impl LifecycleManager {
                     Ok(()) => {
                         if record.state.can_transition_to(AgentState::Running) {
                             record.state = AgentState::Running;
+                            emit_transition(&id, AgentState::Running);
                         }
                         record.health = HealthStatus::Unknown;
                         record.consecutive_health_failures = 0;
                         record.next_recovery_attempt_unix_ms = None;
+                        record.restart_count = record.restart_count.saturating_add(1);
+                        record.last_restart_unix_ms = Some(now);
+                        self.restart_backoff.remove(&id);
                     }
                     Err(_) => {
                         record.health = HealthStatus::Degraded;
+                        let backoff = self.restart_backoff.entry(id.clone()).or_default();
+                        let delay = backoff.record_failure(RESTART_BASE_BACKOFF_MS);
+                        record.next_recovery_attempt_unix_ms = Some(now + delay);
+
+                        if backoff.consecutive_failures >= MAX_RESTART_ATTEMPTS
+                            && record.restart_policy != RestartPolicy::Always
+                            && record.state.can_transition_to(AgentState::Failed)
+                        {
+                            record.state = AgentState::Failed;
+                            emit_transition(&id, AgentState::Failed);
+                        }
                     }
                 }
             }
@@ -257,32 +514,48 @@ impl LifecycleManager {
         required_capabilities: &[String],
         message: String,
         target_agent_id: Option<String>,
-    ) -> Result<(AgentRecord, AgentResponse), String> {
+        session_key: Option<String>,
+    ) -> Result<(AgentRecord, AgentResponse), LifecycleError> {
+        let result = self
+            .route_and_send_inner(required_capabilities, message, target_agent_id, session_key)
+            .await;
+        if let Err(ref err) = result {
+            self.metrics.record_routing_failure(err.metric_reason());
+        }
+        result
+    }
+
+    async fn route_and_send_inner(
+        &mut self,
+        required_capabilities: &[String],
+        message: String,
+        target_agent_id: Option<String>,
+        session_key: Option<String>,
+    ) -> Result<(AgentRecord, AgentResponse), LifecycleError> {
         let selected_id = if let Some(id) = target_agent_id {
             id
         } else {
-            self.select_agent(required_capabilities)?
+            self.select_agent(required_capabilities, session_key.as_deref())?
         };
 
         let Some(record) = self.agents.get_mut(&selected_id) else {
-            return Err(format!("agent {selected_id} not found"));
+            return Err(LifecycleError::AgentNotFound(selected_id));
         };
 
         if record.state != AgentState::Running {
-            return Err(format!("agent {} is not running", record.id));
+            return Err(LifecycleError::AgentNotRunning(record.id.clone()));
         }
 
         let Some(handle) = self.handles.get(&selected_id) else {
-            return Err(format!("agent {} has no active handle", record.id));
+            return Err(LifecycleError::AgentNotRunning(record.id.clone()));
         };
 
         let Some(adapter) = self.adapters.get(&record.runtime) else {
-            return Err(format!(
-                "no adapter registered for runtime {:?}",
-                record.runtime
-            ));
+            return Err(LifecycleError::NoAdapter(record.runtime.clone()));
         };
 
+        let runtime = record.runtime.clone();
+        let sent_at = Instant::now();
         let response = adapter
             .send(
                 handle,
@@ -291,14 +564,21 @@ impl LifecycleManager {
                     content: message,
                 },
             )
-            .await
-            .map_err(|e| format!("send failed: {e}"))?;
+            .await;
+        self.metrics
+            .record_adapter_latency(AdapterOp::Send, &runtime, sent_at.elapsed());
+        let response = response.map_err(|e| LifecycleError::SendFailed(e.to_string()))?;
 
         record.task_count += 1;
+        self.metrics.record_task(&record.id);
         Ok((record.clone(), response))
     }
 
-    fn select_agent(&mut self, required_capabilities: &[String]) -> Result<String, String> {
+    fn select_agent(
+        &self,
+        required_capabilities: &[String],
+        session_key: Option<&str>,
+    ) -> Result<String, LifecycleError> {
         let eligible: Vec<&AgentRecord> = self
             .agents
             .values()
@@ -311,31 +591,29 @@ impl LifecycleManager {
             .collect();
 
         if eligible.is_empty() {
-            return Err("no running agent matches required capabilities".to_string());
+            return Err(LifecycleError::NoEligibleAgent {
+                required: required_capabilities.to_vec(),
+            });
         }
 
-        let mut ranked: Vec<&AgentRecord> = eligible;
-        ranked.sort_by_key(|agent| {
-            (
-                agent.task_count,
-                runtime_cost_tier(&agent.runtime),
-                agent.id.clone(),
-            )
-        });
-
-        let best_score = (ranked[0].task_count, runtime_cost_tier(&ranked[0].runtime));
-        let best_group: Vec<&AgentRecord> = ranked
-            .iter()
-            .copied()
-            .filter(|agent| (agent.task_count, runtime_cost_tier(&agent.runtime)) == best_score)
-            .collect();
-
-        let idx = self.round_robin_index % best_group.len();
-        self.round_robin_index = self.round_robin_index.wrapping_add(1);
-        Ok(best_group[idx].id.clone())
+        Ok(self.routing_strategy.select(&eligible, session_key))
     }
 }
 
+/// Emits a structured `agent_id` + `new_state` event on the
+/// `clawden_lifecycle` target for every successful `can_transition_to`. A
+/// `tracing_subscriber::Layer` watches this target and turns each event into
+/// a durable `AuditEvent`, so a state change is recorded without the caller
+/// remembering to call `append_audit` itself.
+fn emit_transition(agent_id: &str, new_state: AgentState) {
+    tracing::info!(
+        target: "clawden_lifecycle",
+        agent_id,
+        new_state = ?new_state,
+        "lifecycle transition"
+    );
+}
+
 fn current_unix_ms() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -350,12 +628,14 @@ fn backoff_ms(base_ms: u64, failures: u32) -> u64 {
     capped.min(300_000)
 }
 
-fn runtime_cost_tier(runtime: &ClawRuntime) -> u8 {
-    match runtime {
-        ClawRuntime::NullClaw | ClawRuntime::PicoClaw | ClawRuntime::MicroClaw => 1,
-        ClawRuntime::ZeroClaw | ClawRuntime::NanoClaw | ClawRuntime::MimiClaw => 2,
-        ClawRuntime::OpenClaw | ClawRuntime::IronClaw => 3,
+/// Up to 20% of `delay_ms`, so a fleet of agents that degrade at the same
+/// moment don't all retry in lockstep and re-trip the same failure together.
+fn jitter_ms(delay_ms: u64) -> u64 {
+    if delay_ms == 0 {
+        return 0;
     }
+    let max_jitter = delay_ms / 5;
+    rand::thread_rng().gen_range(0..=max_jitter)
 }
 
 pub fn append_audit(audit: &Arc<AuditLog>, action: &str, target: &str) {
@@ -376,7 +656,8 @@ pub fn append_audit(audit: &Arc<AuditLog>, action: &str, target: &str) {
 mod tests {
     use clawden_adapters::builtin_registry;
 
-    use super::LifecycleManager;
+    use super::{LifecycleManager, RestartPolicy};
+    use crate::lifecycle::AgentState;
     use clawden_core::ClawRuntime;
 
     #[test]
@@ -392,4 +673,45 @@ mod tests {
         assert_eq!(listed.len(), 1);
         assert_eq!(listed[0].name, "alpha");
     }
+
+    #[test]
+    fn new_agents_default_to_on_failure_restart_policy() {
+        let mut manager = LifecycleManager::new(builtin_registry());
+        let record = manager.register_agent(
+            "alpha".to_string(),
+            ClawRuntime::ZeroClaw,
+            vec!["chat".to_string()],
+        );
+        assert_eq!(record.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(record.restart_count, 0);
+    }
+
+    #[tokio::test]
+    async fn never_policy_leaves_degraded_agent_untouched() {
+        let mut manager = LifecycleManager::new(builtin_registry());
+        let registered = manager.register_agent(
+            "alpha".to_string(),
+            ClawRuntime::ZeroClaw,
+            vec!["chat".to_string()],
+        );
+        manager
+            .configure_restart(&registered.id, RestartPolicy::Never)
+            .expect("agent exists");
+
+        // Force the agent into Degraded without a live handle, mirroring
+        // what `refresh_health_with_thresholds` does on a failed probe.
+        {
+            let agents = &mut manager.agents;
+            let record = agents.get_mut(&registered.id).expect("agent exists");
+            record.state = AgentState::Degraded;
+        }
+
+        let recovered = manager.recover_degraded().await;
+        let agent = recovered
+            .into_iter()
+            .find(|a| a.id == registered.id)
+            .expect("agent exists");
+        assert_eq!(agent.state, AgentState::Degraded);
+        assert_eq!(agent.restart_count, 0);
+    }
 }
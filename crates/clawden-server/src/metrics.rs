@@ -0,0 +1,303 @@
+//! Prometheus-style operational metrics for the fleet.
+//!
+//! [`Metrics`] is a plain counter/histogram registry that
+//! [`crate::manager::LifecycleManager`] writes into as it runs — a task
+//! routed, a routing failure, an adapter call's latency. Agent state and
+//! health are gauges but aren't tracked incrementally; like `/fleet/status`,
+//! [`Metrics::render`] derives them live from the current [`AgentRecord`]
+//! list each time it's called, so a gauge can never drift from reality.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use clawden_core::{ClawRuntime, HealthStatus};
+
+use crate::lifecycle::AgentState;
+use crate::manager::AgentRecord;
+
+/// Every [`AgentState`] variant, in the order `clawlab_agent_state` gauges
+/// are emitted for each agent.
+const KNOWN_STATES: &[AgentState] = &[
+    AgentState::Registered,
+    AgentState::Installed,
+    AgentState::Running,
+    AgentState::Stopped,
+    AgentState::Degraded,
+    AgentState::Failed,
+];
+
+/// Upper bucket bounds (milliseconds) for adapter-latency histograms. These
+/// are in-process adapter calls, not network round-trips, so the range is
+/// narrower than Prometheus's own HTTP-oriented defaults.
+const LATENCY_BUCKETS_MS: &[f64] = &[
+    1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0,
+];
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// One count per bucket in `LATENCY_BUCKETS_MS`, plus a trailing `+Inf`
+    /// bucket — each slot holds the count of observations that landed in
+    /// *that* bucket, not a running cumulative total; `render` cumulates them.
+    bucket_counts: Vec<u64>,
+    sum_ms: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value_ms: f64) {
+        if self.bucket_counts.is_empty() {
+            self.bucket_counts = vec![0; LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let idx = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| value_ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.bucket_counts[idx] += 1;
+        self.sum_ms += value_ms;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, label_pairs: &str) {
+        let mut cumulative = 0u64;
+        for (i, bound) in LATENCY_BUCKETS_MS.iter().enumerate() {
+            cumulative += self.bucket_counts.get(i).copied().unwrap_or(0);
+            let _ = writeln!(out, "{name}_bucket{{{label_pairs}le=\"{bound}\"}} {cumulative}");
+        }
+        cumulative += self.bucket_counts.last().copied().unwrap_or(0);
+        let _ = writeln!(out, "{name}_bucket{{{label_pairs}le=\"+Inf\"}} {cumulative}");
+        let trimmed = label_pairs.trim_end_matches(',');
+        let _ = writeln!(out, "{name}_sum{{{trimmed}}} {}", self.sum_ms);
+        let _ = writeln!(out, "{name}_count{{{trimmed}}} {}", self.count);
+    }
+}
+
+/// The adapter call an observed latency came from, i.e. which `ClawAdapter`
+/// method was timed around its `.await`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdapterOp {
+    Start,
+    Stop,
+    Send,
+    Health,
+}
+
+impl AdapterOp {
+    fn as_str(self) -> &'static str {
+        match self {
+            AdapterOp::Start => "start",
+            AdapterOp::Stop => "stop",
+            AdapterOp::Send => "send",
+            AdapterOp::Health => "health",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    agent_task_total: Mutex<HashMap<String, u64>>,
+    routing_failures: Mutex<HashMap<&'static str, u64>>,
+    adapter_latency: Mutex<HashMap<(AdapterOp, ClawRuntime), Histogram>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increments `clawlab_agent_task_total` for `agent_id`. Called once per
+    /// successful dispatch in [`crate::manager::LifecycleManager::route_and_send`].
+    pub fn record_task(&self, agent_id: &str) {
+        let mut tasks = self.agent_task_total.lock().expect("metrics mutex poisoned");
+        *tasks.entry(agent_id.to_string()).or_insert(0) += 1;
+    }
+
+    /// Increments `clawlab_routing_failures_total{reason}`. `reason` should
+    /// be the [`crate::manager::LifecycleError`] variant's snake_case name.
+    pub fn record_routing_failure(&self, reason: &'static str) {
+        let mut failures = self.routing_failures.lock().expect("metrics mutex poisoned");
+        *failures.entry(reason).or_insert(0) += 1;
+    }
+
+    /// Records one observation of `op`'s latency against `runtime`'s
+    /// histogram. Callers time around the adapter call's `.await` with
+    /// [`std::time::Instant::elapsed`].
+    pub fn record_adapter_latency(&self, op: AdapterOp, runtime: &ClawRuntime, elapsed: Duration) {
+        let mut latencies = self.adapter_latency.lock().expect("metrics mutex poisoned");
+        latencies
+            .entry((op, runtime.clone()))
+            .or_default()
+            .observe(elapsed.as_secs_f64() * 1_000.0);
+    }
+
+    /// Renders every counter and histogram, plus a live snapshot of
+    /// `agents`' state/health gauges, as Prometheus text exposition.
+    pub fn render(&self, agents: &[AgentRecord]) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP clawlab_agent_state Whether an agent is currently in a given lifecycle state (1) or not (0).\n\
+             # TYPE clawlab_agent_state gauge"
+        );
+        for agent in agents {
+            for state in KNOWN_STATES {
+                let value = if agent.state == *state { 1 } else { 0 };
+                let _ = writeln!(
+                    out,
+                    "clawlab_agent_state{{agent_id=\"{}\",runtime=\"{:?}\",state=\"{state:?}\"}} {value}",
+                    agent.id, agent.runtime
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP clawlab_runtime_agents Agents per runtime currently in a given lifecycle state.\n\
+             # TYPE clawlab_runtime_agents gauge"
+        );
+        let mut runtime_state_counts: HashMap<(String, String), u64> = HashMap::new();
+        for agent in agents {
+            *runtime_state_counts
+                .entry((format!("{:?}", agent.runtime), format!("{:?}", agent.state)))
+                .or_insert(0) += 1;
+        }
+        let mut runtime_state_keys: Vec<_> = runtime_state_counts.keys().cloned().collect();
+        runtime_state_keys.sort();
+        for key @ (ref runtime, ref state) in &runtime_state_keys {
+            let count = runtime_state_counts[key];
+            let _ = writeln!(
+                out,
+                "clawlab_runtime_agents{{runtime=\"{runtime}\",state=\"{state}\"}} {count}"
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP clawlab_agent_health Adapter-reported health: 1 healthy, 0.5 degraded, 0 unhealthy, -1 unknown.\n\
+             # TYPE clawlab_agent_health gauge"
+        );
+        for agent in agents {
+            let _ = writeln!(
+                out,
+                "clawlab_agent_health{{agent_id=\"{}\",runtime=\"{:?}\"}} {}",
+                agent.id,
+                agent.runtime,
+                health_gauge_value(&agent.health)
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP clawlab_agent_task_total Tasks routed to an agent via route_and_send.\n\
+             # TYPE clawlab_agent_task_total counter"
+        );
+        {
+            let tasks = self.agent_task_total.lock().expect("metrics mutex poisoned");
+            let mut ids: Vec<_> = tasks.keys().cloned().collect();
+            ids.sort();
+            for id in ids {
+                let _ = writeln!(out, "clawlab_agent_task_total{{agent_id=\"{id}\"}} {}", tasks[&id]);
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP clawlab_routing_failures_total Routing failures from route_and_send, by reason.\n\
+             # TYPE clawlab_routing_failures_total counter"
+        );
+        {
+            let failures = self.routing_failures.lock().expect("metrics mutex poisoned");
+            let mut reasons: Vec<_> = failures.keys().copied().collect();
+            reasons.sort_unstable();
+            for reason in reasons {
+                let _ = writeln!(
+                    out,
+                    "clawlab_routing_failures_total{{reason=\"{reason}\"}} {}",
+                    failures[reason]
+                );
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP clawlab_adapter_latency_ms Adapter call latency in milliseconds, by operation and runtime.\n\
+             # TYPE clawlab_adapter_latency_ms histogram"
+        );
+        {
+            let latencies = self.adapter_latency.lock().expect("metrics mutex poisoned");
+            let mut keys: Vec<_> = latencies.keys().cloned().collect();
+            keys.sort_by(|a, b| {
+                (a.0.as_str(), format!("{:?}", a.1)).cmp(&(b.0.as_str(), format!("{:?}", b.1)))
+            });
+            for key @ (op, ref runtime) in &keys {
+                let histogram = &latencies[key];
+                let label_pairs = format!("operation=\"{}\",runtime=\"{runtime:?}\",", op.as_str());
+                histogram.render(&mut out, "clawlab_adapter_latency_ms", &label_pairs);
+            }
+        }
+
+        out
+    }
+}
+
+fn health_gauge_value(health: &HealthStatus) -> f64 {
+    match health {
+        HealthStatus::Healthy => 1.0,
+        HealthStatus::Degraded => 0.5,
+        HealthStatus::Unhealthy => 0.0,
+        HealthStatus::Unknown => -1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_counter_accumulates_per_agent() {
+        let metrics = Metrics::new();
+        metrics.record_task("agent-1");
+        metrics.record_task("agent-1");
+        metrics.record_task("agent-2");
+
+        let rendered = metrics.render(&[]);
+        assert!(rendered.contains("clawlab_agent_task_total{agent_id=\"agent-1\"} 2"));
+        assert!(rendered.contains("clawlab_agent_task_total{agent_id=\"agent-2\"} 1"));
+    }
+
+    #[test]
+    fn routing_failures_are_grouped_by_reason() {
+        let metrics = Metrics::new();
+        metrics.record_routing_failure("no_eligible_agent");
+        metrics.record_routing_failure("no_eligible_agent");
+        metrics.record_routing_failure("agent_not_running");
+
+        let rendered = metrics.render(&[]);
+        assert!(rendered.contains("clawlab_routing_failures_total{reason=\"no_eligible_agent\"} 2"));
+        assert!(rendered.contains("clawlab_routing_failures_total{reason=\"agent_not_running\"} 1"));
+    }
+
+    #[test]
+    fn latency_histogram_buckets_are_cumulative() {
+        let metrics = Metrics::new();
+        metrics.record_adapter_latency(AdapterOp::Start, &ClawRuntime::ZeroClaw, Duration::from_millis(2));
+        metrics.record_adapter_latency(AdapterOp::Start, &ClawRuntime::ZeroClaw, Duration::from_millis(40));
+
+        let rendered = metrics.render(&[]);
+        assert!(rendered.contains(
+            "clawlab_adapter_latency_ms_bucket{operation=\"start\",runtime=\"ZeroClaw\",le=\"1\"} 0"
+        ));
+        assert!(rendered.contains(
+            "clawlab_adapter_latency_ms_bucket{operation=\"start\",runtime=\"ZeroClaw\",le=\"5\"} 1"
+        ));
+        assert!(rendered.contains(
+            "clawlab_adapter_latency_ms_bucket{operation=\"start\",runtime=\"ZeroClaw\",le=\"50\"} 2"
+        ));
+        assert!(rendered.contains(
+            "clawlab_adapter_latency_ms_bucket{operation=\"start\",runtime=\"ZeroClaw\",le=\"+Inf\"} 2"
+        ));
+        assert!(rendered.contains("clawlab_adapter_latency_ms_count{operation=\"start\",runtime=\"ZeroClaw\"} 2"));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use clawden_core::{AuditEvent, AuditStore, ChannelConnectionStatus, ChannelStore};
+
+use crate::lifecycle::AgentState;
+use crate::manager::AgentRecord;
+
+/// Per-(agent, channel) reconnection bookkeeping: how many probes in a row
+/// have succeeded or failed, and when the next attempt is due. Mirrors the
+/// shape of `AgentRecord`'s health-backoff fields in [`crate::manager`], just
+/// scoped to a channel binding instead of an agent.
+#[derive(Default)]
+struct ReconnectState {
+    consecutive_successes: u32,
+    consecutive_failures: u32,
+    next_attempt_unix_ms: u64,
+}
+
+/// Drives `ChannelConnectionStatus` for every agent/channel pair the fleet
+/// has bound. Until this existed, `ChannelStore::connection_status` was
+/// written by nobody, so the matrix only ever reported whatever default
+/// `get_connection_status` fell back to.
+#[derive(Default)]
+pub struct ChannelMonitor {
+    states: Mutex<HashMap<(String, String), ReconnectState>>,
+}
+
+impl ChannelMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Probes the channels assigned to each agent and updates `channels`
+    /// accordingly. A pair is considered reachable when its agent is
+    /// [`AgentState::Running`]; reaching that state `recovery_threshold`
+    /// probes in a row is what flips a cell from `Disconnected` back to
+    /// `Connected`, and each failed attempt pushes the next retry out with
+    /// the same exponential backoff `LifecycleManager` uses for agent
+    /// recovery. Every status change is appended to `audit`.
+    pub fn tick(
+        &self,
+        channels: &mut ChannelStore,
+        agents: &[AgentRecord],
+        audit: &dyn AuditStore,
+        base_backoff_ms: u64,
+        recovery_threshold: u32,
+        now_unix_ms: u64,
+    ) {
+        let recovery_threshold = recovery_threshold.max(1);
+        let pairs: Vec<(String, bool, String)> = agents
+            .iter()
+            .flat_map(|agent| {
+                let reachable = agent.state == AgentState::Running;
+                channels
+                    .get_agent_channels(&agent.id)
+                    .into_iter()
+                    .map(move |config| (agent.id.clone(), reachable, config.instance_name.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let mut states = self.states.lock().expect("channel monitor mutex poisoned");
+        for (agent_id, reachable, channel_name) in pairs {
+            let key = (agent_id.clone(), channel_name.clone());
+            let previous = channels.get_connection_status(&agent_id, &channel_name);
+            let state = states.entry(key).or_default();
+
+            if state.next_attempt_unix_ms > now_unix_ms {
+                continue;
+            }
+
+            let next = if reachable {
+                state.consecutive_successes = state.consecutive_successes.saturating_add(1);
+                state.consecutive_failures = 0;
+                if previous == ChannelConnectionStatus::Connected
+                    || state.consecutive_successes >= recovery_threshold
+                {
+                    state.next_attempt_unix_ms = now_unix_ms;
+                    ChannelConnectionStatus::Connected
+                } else {
+                    state.next_attempt_unix_ms =
+                        now_unix_ms + backoff_ms(base_backoff_ms, state.consecutive_successes);
+                    previous.clone()
+                }
+            } else {
+                state.consecutive_successes = 0;
+                state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                state.next_attempt_unix_ms =
+                    now_unix_ms + backoff_ms(base_backoff_ms, state.consecutive_failures);
+                ChannelConnectionStatus::Disconnected
+            };
+
+            if next != previous {
+                channels.set_connection_status(&agent_id, &channel_name, next);
+                audit.append(AuditEvent {
+                    actor: "monitor".to_string(),
+                    action: "channel.reconnect".to_string(),
+                    target: format!("{agent_id}/{channel_name}"),
+                    timestamp_unix_ms: now_unix_ms,
+                    correlation_id: None,
+                });
+            }
+        }
+    }
+}
+
+fn backoff_ms(base_ms: u64, attempts: u32) -> u64 {
+    let exponent = attempts.saturating_sub(1).min(6);
+    let multiplier = 1_u64 << exponent;
+    base_ms.saturating_mul(multiplier).min(300_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clawden_core::{AuditLog, ClawRuntime};
+
+    fn running_agent(id: &str) -> AgentRecord {
+        AgentRecord {
+            id: id.to_string(),
+            name: id.to_string(),
+            runtime: ClawRuntime::NullClaw,
+            capabilities: Vec::new(),
+            state: AgentState::Running,
+            task_count: 0,
+            health: clawden_core::HealthStatus::Healthy,
+            consecutive_health_failures: 0,
+            last_health_check_unix_ms: None,
+            next_recovery_attempt_unix_ms: None,
+            restart_policy: Default::default(),
+            restart_count: 0,
+            last_restart_unix_ms: None,
+            certificate_fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn flips_to_connected_after_recovery_threshold_successes() {
+        let mut channels = ChannelStore::new();
+        channels
+            .upsert_config(clawden_core::ChannelConfigRequest {
+                instance_name: "slack-main".to_string(),
+                channel_type: "slack".to_string(),
+                credentials: Default::default(),
+                options: Default::default(),
+            })
+            .expect("valid channel type");
+        channels.assign_channel("agent-1", "slack-main");
+
+        let monitor = ChannelMonitor::new();
+        let audit = AuditLog::default();
+        let agents = vec![running_agent("agent-1")];
+
+        monitor.tick(&mut channels, &agents, &audit, 10, 2, 0);
+        assert_eq!(
+            channels.get_connection_status("agent-1", "slack-main"),
+            ChannelConnectionStatus::Disconnected
+        );
+
+        monitor.tick(&mut channels, &agents, &audit, 10, 2, 100);
+        assert_eq!(
+            channels.get_connection_status("agent-1", "slack-main"),
+            ChannelConnectionStatus::Connected
+        );
+    }
+
+    #[test]
+    fn unreachable_agent_marks_channel_disconnected() {
+        let mut channels = ChannelStore::new();
+        channels
+            .upsert_config(clawden_core::ChannelConfigRequest {
+                instance_name: "slack-main".to_string(),
+                channel_type: "slack".to_string(),
+                credentials: Default::default(),
+                options: Default::default(),
+            })
+            .expect("valid channel type");
+        channels.assign_channel("agent-1", "slack-main");
+        channels.set_connection_status(
+            "agent-1",
+            "slack-main",
+            ChannelConnectionStatus::Connected,
+        );
+
+        let mut agent = running_agent("agent-1");
+        agent.state = AgentState::Degraded;
+
+        let monitor = ChannelMonitor::new();
+        let audit = AuditLog::default();
+        monitor.tick(&mut channels, &[agent], &audit, 10, 2, 0);
+
+        assert_eq!(
+            channels.get_connection_status("agent-1", "slack-main"),
+            ChannelConnectionStatus::Disconnected
+        );
+        assert_eq!(audit.list().len(), 1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Pre-warmed runtime pool that keeps idle [`AgentHandle`]s ready per
+//! runtime type, the same VM-pool-prewarming technique: instead of paying
+//! full adapter `start` cost on the `deploy_runtime`/`start_agent` critical
+//! path, hand the caller an already-initialized instance and backfill the
+//! pool in the background.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use clawden_adapters::AdapterRegistry;
+use clawden_core::{AgentConfig, AgentHandle, ClawRuntime};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+/// Per-runtime warm/creating counts, as surfaced by `GET /runtimes/pool`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimePoolStatus {
+    pub runtime: String,
+    pub warm: usize,
+    pub creating: usize,
+    pub target: usize,
+}
+
+#[derive(Default)]
+struct RuntimeSlot {
+    warm: VecDeque<AgentHandle>,
+    creating: usize,
+}
+
+static PLACEHOLDER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Keeps `target_size` ready instances per registered runtime, created
+/// concurrently at startup and backfilled one-at-a-time after each
+/// checkout. `adapters` is shared with [`crate::manager::LifecycleManager`]
+/// so a pool entry and a manager-started instance come from the same
+/// adapter implementations.
+pub struct RuntimePool {
+    adapters: Arc<AdapterRegistry>,
+    target_size: usize,
+    slots: Mutex<HashMap<ClawRuntime, RuntimeSlot>>,
+}
+
+impl RuntimePool {
+    pub fn new(adapters: Arc<AdapterRegistry>, target_size: usize) -> Arc<Self> {
+        Arc::new(Self {
+            adapters,
+            target_size,
+            slots: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Fills every registered runtime up to `target_size` concurrently. Call
+    /// once at startup; a `target_size` of `0` disables prewarming entirely.
+    pub async fn prewarm(self: &Arc<Self>) {
+        if self.target_size == 0 {
+            return;
+        }
+        let runtimes = self.adapters.list();
+        let fills = runtimes
+            .into_iter()
+            .map(|runtime| self.backfill(runtime, self.target_size));
+        futures::future::join_all(fills).await;
+    }
+
+    /// Takes a warm instance for `runtime`, if one is ready, and kicks off a
+    /// background backfill of one replacement. Returns `None` on a pool miss
+    /// (runtime not pooled, or pool momentarily empty) so the caller falls
+    /// back to creating one inline.
+    pub async fn checkout(self: &Arc<Self>, runtime: &ClawRuntime) -> Option<AgentHandle> {
+        if self.target_size == 0 {
+            return None;
+        }
+        let handle = {
+            let mut slots = self.slots.lock().await;
+            slots.entry(runtime.clone()).or_default().warm.pop_front()
+        }?;
+
+        let pool = self.clone();
+        let runtime = runtime.clone();
+        tokio::spawn(async move { pool.backfill(runtime, 1).await });
+        Some(handle)
+    }
+
+    /// Creates up to `count` instances of `runtime`, one after another, and pushes
+    /// them onto the warm queue, stopping short if the queue is already at
+    /// `target_size` or the adapter has gone missing.
+    async fn backfill(self: &Arc<Self>, runtime: ClawRuntime, count: usize) {
+        let Some(adapter) = self.adapters.get(&runtime) else {
+            return;
+        };
+
+        let to_create = {
+            let mut slots = self.slots.lock().await;
+            let slot = slots.entry(runtime.clone()).or_default();
+            let room = self.target_size.saturating_sub(slot.warm.len() + slot.creating);
+            let to_create = room.min(count);
+            slot.creating += to_create;
+            to_create
+        };
+
+        for _ in 0..to_create {
+            let placeholder = PLACEHOLDER_COUNTER.fetch_add(1, Ordering::Relaxed);
+            let config = AgentConfig {
+                name: format!("pool-{runtime:?}-{placeholder}").to_lowercase(),
+                runtime: runtime.clone(),
+                model: None,
+            };
+            let created = adapter.start(&config).await;
+
+            let mut slots = self.slots.lock().await;
+            let slot = slots.entry(runtime.clone()).or_default();
+            slot.creating = slot.creating.saturating_sub(1);
+            if let Ok(handle) = created {
+                slot.warm.push_back(handle);
+            }
+        }
+    }
+
+    pub async fn status(&self) -> Vec<RuntimePoolStatus> {
+        let slots = self.slots.lock().await;
+        let mut runtimes: Vec<ClawRuntime> = self.adapters.list();
+        for runtime in slots.keys() {
+            if !runtimes.contains(runtime) {
+                runtimes.push(runtime.clone());
+            }
+        }
+        runtimes.sort_by_key(|runtime| format!("{runtime:?}"));
+
+        runtimes
+            .into_iter()
+            .map(|runtime| {
+                let slot = slots.get(&runtime);
+                RuntimePoolStatus {
+                    runtime: format!("{runtime:?}"),
+                    warm: slot.map(|s| s.warm.len()).unwrap_or(0),
+                    creating: slot.map(|s| s.creating).unwrap_or(0),
+                    target: self.target_size,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clawden_adapters::builtin_registry;
+
+    #[tokio::test]
+    async fn prewarm_fills_every_registered_runtime_to_target() {
+        let pool = RuntimePool::new(Arc::new(builtin_registry()), 2);
+        pool.prewarm().await;
+
+        let status = pool.status().await;
+        assert!(!status.is_empty());
+        for entry in status {
+            assert_eq!(entry.warm, 2, "runtime {} was not fully prewarmed", entry.runtime);
+            assert_eq!(entry.creating, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn checkout_drains_a_warm_instance_and_backfills() {
+        let pool = RuntimePool::new(Arc::new(builtin_registry()), 1);
+        pool.prewarm().await;
+        let runtime = pool.adapters.list()[0].clone();
+
+        let handle = pool.checkout(&runtime).await;
+        assert!(handle.is_some());
+
+        // Backfill is spawned in the background; give it a chance to run.
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let status = pool.status().await;
+        let entry = status.iter().find(|s| s.runtime == format!("{runtime:?}")).unwrap();
+        assert_eq!(entry.warm, 1);
+    }
+
+    #[tokio::test]
+    async fn zero_target_size_disables_pooling() {
+        let pool = RuntimePool::new(Arc::new(builtin_registry()), 0);
+        pool.prewarm().await;
+        let runtime = pool.adapters.list()[0].clone();
+        assert!(pool.checkout(&runtime).await.is_none());
+    }
+}
@@ -0,0 +1,246 @@
+//! Reverse-tunnel relay for agents that can't accept inbound connections.
+//!
+//! An agent behind a NAT/firewall opens an outbound WebSocket to
+//! `/relay/connect` and registers itself under its agent id. From then on,
+//! `send_task`/`restart_agent`/`agent_logs` for that agent don't call the
+//! in-process [`crate::manager::LifecycleManager`] directly — they look the
+//! agent id up in the [`RelayRegistry`], push a [`RelayRequest`] down the
+//! open connection, and wait (with a timeout) for the matching
+//! [`RelayResponse`] to come back, correlated by `correlation_id`. This is
+//! the same accept-then-forward shape as [`crate::cluster::Broadcasting`],
+//! just relaying to a held-open agent connection instead of a peer node.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+/// An operation the server asks a tunneled agent to perform, mirroring the
+/// handlers that would otherwise run against the local lifecycle manager.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RelayOperation {
+    SendTask {
+        message: String,
+        #[serde(default)]
+        required_capabilities: Vec<String>,
+    },
+    RestartAgent,
+    FetchLogs {
+        #[serde(default)]
+        since: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayRequest {
+    pub correlation_id: String,
+    pub operation: RelayOperation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayResponse {
+    pub correlation_id: String,
+    pub ok: bool,
+    pub message: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+impl RelayResponse {
+    pub fn ok(correlation_id: &str, payload: serde_json::Value) -> Self {
+        Self {
+            correlation_id: correlation_id.to_string(),
+            ok: true,
+            message: "ok".to_string(),
+            payload,
+        }
+    }
+
+    pub fn err(correlation_id: &str, message: impl Into<String>) -> Self {
+        Self {
+            correlation_id: correlation_id.to_string(),
+            ok: false,
+            message: message.into(),
+            payload: serde_json::Value::Null,
+        }
+    }
+}
+
+static CORRELATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+fn next_correlation_id() -> String {
+    format!("relay-{}", CORRELATION_COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// One agent's open relay connection: a channel to push requests down it,
+/// and the in-flight requests awaiting a correlated response.
+pub struct TunnelHandle {
+    outbound: mpsc::UnboundedSender<RelayRequest>,
+    pending: Mutex<HashMap<String, oneshot::Sender<RelayResponse>>>,
+}
+
+impl TunnelHandle {
+    fn new(outbound: mpsc::UnboundedSender<RelayRequest>) -> Self {
+        Self {
+            outbound,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sends `operation` down the tunnel and waits up to `timeout` for the
+    /// agent's correlated reply.
+    pub async fn dispatch(
+        &self,
+        operation: RelayOperation,
+        timeout: Duration,
+    ) -> Result<RelayResponse, String> {
+        let correlation_id = next_correlation_id();
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("tunnel pending mutex poisoned")
+            .insert(correlation_id.clone(), sender);
+
+        if self
+            .outbound
+            .send(RelayRequest {
+                correlation_id: correlation_id.clone(),
+                operation,
+            })
+            .is_err()
+        {
+            self.pending
+                .lock()
+                .expect("tunnel pending mutex poisoned")
+                .remove(&correlation_id);
+            return Err("relay connection closed".to_string());
+        }
+
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err("relay connection closed before replying".to_string()),
+            Err(_) => {
+                self.pending
+                    .lock()
+                    .expect("tunnel pending mutex poisoned")
+                    .remove(&correlation_id);
+                Err(format!("relay request timed out after {timeout:?}"))
+            }
+        }
+    }
+
+    /// Resolves a pending request once the agent's reply arrives over the
+    /// socket. A response with no matching (already-timed-out, or unknown)
+    /// correlation id is dropped silently.
+    fn resolve(&self, response: RelayResponse) {
+        if let Some(sender) = self
+            .pending
+            .lock()
+            .expect("tunnel pending mutex poisoned")
+            .remove(&response.correlation_id)
+        {
+            let _ = sender.send(response);
+        }
+    }
+}
+
+/// Live tunnel handles keyed by agent id. `DashMap` over `Mutex<HashMap<_>>`
+/// here because every request handler (`send_task`, `restart_agent`,
+/// `agent_logs`) does an independent lookup-and-use under concurrent load,
+/// rather than the read/write-locked-for-the-whole-request pattern
+/// `AppState`'s other collections use.
+#[derive(Default)]
+pub struct RelayRegistry {
+    tunnels: DashMap<String, std::sync::Arc<TunnelHandle>>,
+}
+
+impl RelayRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &self,
+        agent_id: &str,
+        outbound: mpsc::UnboundedSender<RelayRequest>,
+    ) -> std::sync::Arc<TunnelHandle> {
+        let handle = std::sync::Arc::new(TunnelHandle::new(outbound));
+        self.tunnels.insert(agent_id.to_string(), handle.clone());
+        handle
+    }
+
+    pub fn unregister(&self, agent_id: &str) {
+        self.tunnels.remove(agent_id);
+    }
+
+    pub fn get(&self, agent_id: &str) -> Option<std::sync::Arc<TunnelHandle>> {
+        self.tunnels.get(agent_id).map(|entry| entry.value().clone())
+    }
+
+    pub fn resolve(&self, agent_id: &str, response: RelayResponse) {
+        if let Some(handle) = self.get(agent_id) {
+            handle.resolve(response);
+        }
+    }
+
+    pub fn is_tunneled(&self, agent_id: &str) -> bool {
+        self.tunnels.contains_key(agent_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn dispatch_resolves_once_the_matching_response_arrives() {
+        let registry = RelayRegistry::new();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        registry.register("agent-1", sender);
+
+        let handle = registry.get("agent-1").expect("tunnel just registered");
+        let dispatched = tokio::spawn(async move {
+            handle
+                .dispatch(RelayOperation::RestartAgent, Duration::from_secs(1))
+                .await
+        });
+
+        let request = receiver.recv().await.expect("request should be sent");
+        registry.resolve(
+            "agent-1",
+            RelayResponse::ok(&request.correlation_id, serde_json::json!({"restarted": true})),
+        );
+
+        let response = dispatched.await.expect("task should not panic").expect("dispatch should succeed");
+        assert!(response.ok);
+    }
+
+    #[tokio::test]
+    async fn dispatch_times_out_when_no_response_arrives() {
+        let registry = RelayRegistry::new();
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        registry.register("agent-1", sender);
+
+        let handle = registry.get("agent-1").expect("tunnel just registered");
+        let result = handle
+            .dispatch(RelayOperation::RestartAgent, Duration::from_millis(20))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn unregister_removes_the_tunnel() {
+        let registry = RelayRegistry::new();
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        registry.register("agent-1", sender);
+        assert!(registry.is_tunneled("agent-1"));
+
+        registry.unregister("agent-1");
+        assert!(!registry.is_tunneled("agent-1"));
+    }
+}
@@ -0,0 +1,239 @@
+//! Pluggable dispatch policies for [`crate::manager::LifecycleManager::route_and_send`].
+//!
+//! `select_agent` used to hardcode one scheme — least task count, tie-broken
+//! by runtime cost tier, tie-broken again by round-robin. That scheme still
+//! exists as [`RoundRobinStrategy`], the default, but a deployment can now
+//! swap in [`LeastLoadedStrategy`], [`WeightedRandomStrategy`], or
+//! [`StickyStrategy`] via [`crate::manager::LifecycleManager::set_routing_strategy`]
+//! without touching `route_and_send`'s call sites.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use clawden_core::ClawRuntime;
+use rand::Rng;
+
+use crate::manager::AgentRecord;
+
+/// Chooses one agent out of an already-capability-filtered, already-`Running`
+/// candidate set. Implementations hold their own interior-mutable state
+/// (an index, a weight table, a session map) so `select` only needs `&self`.
+pub trait RoutingStrategy: Send + Sync {
+    /// `eligible` is never empty — callers filter to `AgentState::Running`
+    /// agents matching every required capability before calling this, and
+    /// return [`crate::manager::LifecycleError::NoEligibleAgent`] themselves
+    /// when that filter leaves nothing. `session_key` is only consulted by
+    /// [`StickyStrategy`]; other strategies ignore it.
+    fn select(&self, eligible: &[&AgentRecord], session_key: Option<&str>) -> String;
+}
+
+fn runtime_cost_tier(runtime: &ClawRuntime) -> u8 {
+    match runtime {
+        ClawRuntime::NullClaw | ClawRuntime::PicoClaw | ClawRuntime::MicroClaw => 1,
+        ClawRuntime::ZeroClaw | ClawRuntime::NanoClaw | ClawRuntime::MimiClaw => 2,
+        ClawRuntime::OpenClaw | ClawRuntime::IronClaw => 3,
+    }
+}
+
+/// The original dispatch policy: group eligible agents by (task count,
+/// runtime cost tier) and cycle through the lowest-scoring group with an
+/// ever-advancing index, so repeat calls spread load across tied agents
+/// instead of always picking the first one sorts to.
+#[derive(Default)]
+pub struct RoundRobinStrategy {
+    index: AtomicUsize,
+}
+
+impl RoutingStrategy for RoundRobinStrategy {
+    fn select(&self, eligible: &[&AgentRecord], _session_key: Option<&str>) -> String {
+        let mut ranked: Vec<&AgentRecord> = eligible.to_vec();
+        ranked.sort_by_key(|agent| {
+            (agent.task_count, runtime_cost_tier(&agent.runtime), agent.id.clone())
+        });
+
+        let best_score = (ranked[0].task_count, runtime_cost_tier(&ranked[0].runtime));
+        let best_group: Vec<&AgentRecord> = ranked
+            .iter()
+            .copied()
+            .filter(|agent| (agent.task_count, runtime_cost_tier(&agent.runtime)) == best_score)
+            .collect();
+
+        let idx = self.index.fetch_add(1, Ordering::Relaxed) % best_group.len();
+        best_group[idx].id.clone()
+    }
+}
+
+/// Always the eligible agent with the fewest completed tasks, ties broken by
+/// id — simpler than [`RoundRobinStrategy`]: no runtime cost tiering, no
+/// cycling within a tied group.
+#[derive(Default)]
+pub struct LeastLoadedStrategy;
+
+impl RoutingStrategy for LeastLoadedStrategy {
+    fn select(&self, eligible: &[&AgentRecord], _session_key: Option<&str>) -> String {
+        eligible
+            .iter()
+            .min_by_key(|agent| (agent.task_count, agent.id.clone()))
+            .expect("eligible is never empty")
+            .id
+            .clone()
+    }
+}
+
+/// Picks an eligible agent at random, biased by a per-agent weight (default
+/// `1` for any agent without one set). A weight of `0` excludes an agent from
+/// selection entirely without having to unregister it.
+#[derive(Default)]
+pub struct WeightedRandomStrategy {
+    weights: Mutex<HashMap<String, u32>>,
+}
+
+impl WeightedRandomStrategy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_weight(&self, agent_id: &str, weight: u32) {
+        let mut weights = self.weights.lock().expect("routing mutex poisoned");
+        weights.insert(agent_id.to_string(), weight);
+    }
+
+    fn weight_of(&self, agent_id: &str) -> u32 {
+        let weights = self.weights.lock().expect("routing mutex poisoned");
+        weights.get(agent_id).copied().unwrap_or(1)
+    }
+}
+
+impl RoutingStrategy for WeightedRandomStrategy {
+    fn select(&self, eligible: &[&AgentRecord], _session_key: Option<&str>) -> String {
+        let mut ranked: Vec<&AgentRecord> = eligible.to_vec();
+        ranked.sort_by_key(|agent| agent.id.clone());
+
+        let total_weight: u64 = ranked.iter().map(|agent| self.weight_of(&agent.id) as u64).sum();
+        if total_weight == 0 {
+            // Every eligible agent is weighted out; fall back to the first
+            // by id rather than refusing to route at all.
+            return ranked[0].id.clone();
+        }
+
+        let mut draw = rand::thread_rng().gen_range(0..total_weight);
+        for agent in &ranked {
+            let weight = self.weight_of(&agent.id) as u64;
+            if draw < weight {
+                return agent.id.clone();
+            }
+            draw -= weight;
+        }
+        ranked[ranked.len() - 1].id.clone()
+    }
+}
+
+/// Hashes `session_key` over the eligible agents (sorted by id) so every
+/// message in a conversation keeps hitting the same runtime while that set
+/// is unchanged. When the set changes, e.g. the target is no longer
+/// `Running`, the key maps to another eligible agent, and other keys may
+/// move too. A call with no `session_key` uses [`LeastLoadedStrategy`].
+pub struct StickyStrategy {
+    fallback: LeastLoadedStrategy,
+}
+
+impl Default for StickyStrategy {
+    fn default() -> Self {
+        Self {
+            fallback: LeastLoadedStrategy,
+        }
+    }
+}
+
+impl StickyStrategy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RoutingStrategy for StickyStrategy {
+    fn select(&self, eligible: &[&AgentRecord], session_key: Option<&str>) -> String {
+        let Some(session_key) = session_key else {
+            return self.fallback.select(eligible, None);
+        };
+
+        let mut ranked: Vec<&AgentRecord> = eligible.to_vec();
+        ranked.sort_by_key(|agent| agent.id.clone());
+
+        let mut hasher = DefaultHasher::new();
+        session_key.hash(&mut hasher);
+        let index = (hasher.finish() as usize) % ranked.len();
+        ranked[index].id.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clawden_core::HealthStatus;
+    use crate::lifecycle::AgentState;
+    use crate::manager::RestartPolicy;
+
+    fn agent(id: &str, task_count: u64) -> AgentRecord {
+        AgentRecord {
+            id: id.to_string(),
+            name: id.to_string(),
+            runtime: ClawRuntime::ZeroClaw,
+            capabilities: vec![],
+            state: AgentState::Running,
+            task_count,
+            health: HealthStatus::Unknown,
+            consecutive_health_failures: 0,
+            last_health_check_unix_ms: None,
+            next_recovery_attempt_unix_ms: None,
+            restart_policy: RestartPolicy::default(),
+            restart_count: 0,
+            last_restart_unix_ms: None,
+            certificate_fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn least_loaded_picks_smallest_task_count_then_id() {
+        let a = agent("agent-2", 3);
+        let b = agent("agent-1", 1);
+        let c = agent("agent-3", 1);
+        let strategy = LeastLoadedStrategy;
+        assert_eq!(strategy.select(&[&a, &b, &c], None), "agent-1");
+    }
+
+    #[test]
+    fn sticky_returns_the_same_agent_for_the_same_key() {
+        let a = agent("agent-1", 0);
+        let b = agent("agent-2", 0);
+        let c = agent("agent-3", 0);
+        let strategy = StickyStrategy::new();
+        let first = strategy.select(&[&a, &b, &c], Some("conversation-42"));
+        let second = strategy.select(&[&a, &b, &c], Some("conversation-42"));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn sticky_falls_back_to_least_loaded_without_a_session_key() {
+        let a = agent("agent-1", 5);
+        let b = agent("agent-2", 0);
+        let strategy = StickyStrategy::new();
+        assert_eq!(strategy.select(&[&a, &b], None), "agent-2");
+    }
+
+    #[test]
+    fn weighted_random_excludes_zero_weight_agents() {
+        let a = agent("agent-1", 0);
+        let b = agent("agent-2", 0);
+        let strategy = WeightedRandomStrategy::new();
+        strategy.set_weight("agent-1", 0);
+        strategy.set_weight("agent-2", 5);
+
+        for _ in 0..20 {
+            assert_eq!(strategy.select(&[&a, &b], None), "agent-2");
+        }
+    }
+}
@@ -0,0 +1,617 @@
+//! Recurring/background jobs layered on top of [`LifecycleManager`] and
+//! [`SwarmCoordinator`].
+//!
+//! Everything in the server up to this point is request-driven: an HTTP
+//! call routes a message to an agent and the response goes straight back to
+//! the caller. [`Scheduler`] adds the other half — entries that fire on
+//! their own cadence, either a plain interval or a five-field cron
+//! expression, and route through the same `route_and_send`/`fan_out`
+//! dispatch everything else uses.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use clawden_core::{AgentResponse, SwarmCoordinator};
+use serde::{Deserialize, Serialize};
+
+use crate::manager::LifecycleManager;
+
+/// How an entry's next fire time is derived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleSpec {
+    /// Fire every `interval_ms`, starting `interval_ms` after creation.
+    Interval { interval_ms: u64 },
+    /// Fire on a 5-field `minute hour day-of-month month day-of-week` cron
+    /// expression. Each field is `*`, a literal number, or a `*/N` step
+    /// (matching values divisible by N). Evaluated against UTC.
+    Cron { expression: String },
+}
+
+impl ScheduleSpec {
+    /// The first timestamp strictly after `after_unix_ms` that this spec
+    /// fires at, or `None` for a `Cron` expression that does not parse or
+    /// has no match inside the
+    /// one-year search window (almost certainly a typo, e.g. day 31 paired
+    /// with month 2).
+    fn first_fire_after(&self, after_unix_ms: u64) -> Option<u64> {
+        match self {
+            ScheduleSpec::Interval { interval_ms } => Some(after_unix_ms + interval_ms),
+            ScheduleSpec::Cron { expression } => {
+                cron::CronSchedule::parse(expression)?.next_fire_after(after_unix_ms)
+            }
+        }
+    }
+}
+
+/// Who a fire dispatches to: a specific agent (bypassing capability
+/// routing) or a swarm team (fanned out via [`SwarmCoordinator::fan_out`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "id")]
+pub enum ScheduleTarget {
+    Agent(String),
+    Team(String),
+}
+
+/// What to do when the server was down (or just slow) long enough that an
+/// entry's `next_fire_unix_ms` has fallen multiple intervals behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUpPolicy {
+    /// Collapse every missed fire into one: `next_fire` jumps straight to
+    /// the first slot still in the future.
+    #[default]
+    Skip,
+    /// Fire once per missed interval, back-to-back, until caught up to the
+    /// present.
+    CatchUp,
+}
+
+/// What to do when an entry's previous dispatch hasn't finished by the time
+/// it's due again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    /// Skip this fire rather than run two dispatches for the same entry at
+    /// once.
+    #[default]
+    Skip,
+    /// Dispatch anyway, overlapping with the run already in flight.
+    Allow,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    pub id: String,
+    pub required_capabilities: Vec<String>,
+    pub payload: String,
+    pub spec: ScheduleSpec,
+    #[serde(default)]
+    pub target: Option<ScheduleTarget>,
+    #[serde(default)]
+    pub catch_up_policy: CatchUpPolicy,
+    #[serde(default)]
+    pub overlap_policy: OverlapPolicy,
+    pub max_runs: Option<u32>,
+    pub run_count: u32,
+    pub last_fire_unix_ms: Option<u64>,
+    pub next_fire_unix_ms: u64,
+    /// Set for the duration of a dispatch; consulted by `overlap_policy`.
+    /// Not part of the wire format — it's run-time bookkeeping, not
+    /// configuration.
+    #[serde(skip)]
+    in_flight: bool,
+}
+
+/// Outcome of one entry firing during a [`Scheduler::tick`], for callers
+/// that want to log or audit what ran.
+#[derive(Debug, Clone)]
+pub struct ScheduleFireResult {
+    pub entry_id: String,
+    pub response: Result<AgentResponse, String>,
+}
+
+/// Holds every [`ScheduleEntry`] plus a min-heap of `(next_fire_unix_ms, id)`
+/// so [`Scheduler::tick`] can find the next-due entry without scanning the
+/// whole set. The heap is allowed to carry stale entries — a removed or
+/// already-rescheduled id is filtered out lazily when popped rather than
+/// eagerly removed from the heap, since `BinaryHeap` has no `remove`.
+#[derive(Default)]
+pub struct Scheduler {
+    entries: HashMap<String, ScheduleEntry>,
+    due: BinaryHeap<Reverse<(u64, String)>>,
+    next_id: AtomicU64,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_entry(
+        &mut self,
+        required_capabilities: Vec<String>,
+        payload: String,
+        spec: ScheduleSpec,
+        target: Option<ScheduleTarget>,
+        catch_up_policy: CatchUpPolicy,
+        overlap_policy: OverlapPolicy,
+        max_runs: Option<u32>,
+        now_unix_ms: u64,
+    ) -> Result<ScheduleEntry, String> {
+        let next_fire_unix_ms = spec
+            .first_fire_after(now_unix_ms)
+            .ok_or_else(|| "cron expression never matches within one year".to_string())?;
+
+        let id = format!("sched-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let entry = ScheduleEntry {
+            id: id.clone(),
+            required_capabilities,
+            payload,
+            spec,
+            target,
+            catch_up_policy,
+            overlap_policy,
+            max_runs,
+            run_count: 0,
+            last_fire_unix_ms: None,
+            next_fire_unix_ms,
+            in_flight: false,
+        };
+        self.due
+            .push(Reverse((entry.next_fire_unix_ms, id.clone())));
+        self.entries.insert(id, entry.clone());
+        Ok(entry)
+    }
+
+    pub fn remove_entry(&mut self, id: &str) -> bool {
+        self.entries.remove(id).is_some()
+    }
+
+    pub fn list_entries(&self) -> Vec<ScheduleEntry> {
+        let mut entries: Vec<_> = self.entries.values().cloned().collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        entries
+    }
+
+    /// Fires every entry whose `next_fire_unix_ms` has passed, dispatching
+    /// each through `manager.route_and_send` (for an [`ScheduleTarget::Agent`]
+    /// target, or no target at all) or `swarm.fan_out` (for
+    /// [`ScheduleTarget::Team`]). A successful fire reschedules the entry per
+    /// its `spec` and `catch_up_policy` (or drops it once `max_runs` is
+    /// reached); a dispatch failure leaves the entry in place, rescheduled to
+    /// its next fire time, instead of dropping it. An entry whose previous
+    /// dispatch is still in flight is skipped rather than run twice when
+    /// `overlap_policy` is [`OverlapPolicy::Skip`].
+    pub async fn tick(
+        &mut self,
+        manager: &mut LifecycleManager,
+        swarm: &mut SwarmCoordinator,
+        now_unix_ms: u64,
+    ) -> Vec<ScheduleFireResult> {
+        let mut results = Vec::new();
+
+        loop {
+            let Some(Reverse((fire_at, id))) = self.due.peek().cloned() else {
+                break;
+            };
+            if fire_at > now_unix_ms {
+                break;
+            }
+            self.due.pop();
+
+            let Some(entry) = self.entries.get(&id) else {
+                continue; // removed since it was scheduled
+            };
+            if entry.next_fire_unix_ms != fire_at {
+                continue; // stale heap entry superseded by a later reschedule
+            }
+
+            if entry.in_flight && entry.overlap_policy == OverlapPolicy::Skip {
+                results.push(ScheduleFireResult {
+                    entry_id: id.clone(),
+                    response: Err("skipped: previous run still in flight".to_string()),
+                });
+                self.reschedule(&id, now_unix_ms, false);
+                continue;
+            }
+
+            let capabilities = entry.required_capabilities.clone();
+            let payload = entry.payload.clone();
+            let target = entry.target.clone();
+
+            if let Some(entry) = self.entries.get_mut(&id) {
+                entry.in_flight = true;
+            }
+
+            let send_result = match target {
+                Some(ScheduleTarget::Team(team_name)) => swarm
+                    .fan_out(&team_name, &payload, vec![payload.clone()])
+                    .map(|tasks| AgentResponse {
+                        content: serde_json::to_string(&tasks).unwrap_or_default(),
+                    })
+                    .map_err(crate::manager::LifecycleError::SendFailed),
+                Some(ScheduleTarget::Agent(agent_id)) => manager
+                    .route_and_send(&capabilities, payload, Some(agent_id), None)
+                    .await
+                    .map(|(_, response)| response),
+                None => manager
+                    .route_and_send(&capabilities, payload, None, None)
+                    .await
+                    .map(|(_, response)| response),
+            };
+
+            let Some(entry) = self.entries.get_mut(&id) else {
+                continue;
+            };
+            entry.in_flight = false;
+
+            match send_result {
+                Ok(response) => {
+                    entry.run_count += 1;
+                    entry.last_fire_unix_ms = Some(now_unix_ms);
+                    let exhausted = entry
+                        .max_runs
+                        .map(|max| entry.run_count >= max)
+                        .unwrap_or(false);
+                    results.push(ScheduleFireResult {
+                        entry_id: id.clone(),
+                        response: Ok(response),
+                    });
+
+                    if exhausted {
+                        self.entries.remove(&id);
+                        continue;
+                    }
+                    self.reschedule(&id, now_unix_ms, true);
+                }
+                Err(err) => {
+                    results.push(ScheduleFireResult {
+                        entry_id: id.clone(),
+                        response: Err(err.to_string()),
+                    });
+                    self.reschedule(&id, now_unix_ms, false);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Computes and pushes an entry's next due time. After a fire, honoring
+    /// `catch_up_policy` means choosing what "after" means: `Skip` always
+    /// advances from `now` so a long gap collapses to one future slot,
+    /// while `CatchUp` advances from the *previous* `next_fire_unix_ms` so a
+    /// backlog of missed fires stays due immediately and drains one entry
+    /// per loop iteration above. A failed dispatch always retries from
+    /// `now` regardless of policy — there's nothing to "catch up" on when
+    /// nothing ran.
+    fn reschedule(&mut self, id: &str, now_unix_ms: u64, fired: bool) {
+        let Some(entry) = self.entries.get_mut(id) else {
+            return;
+        };
+        let catching_up = fired && entry.catch_up_policy == CatchUpPolicy::CatchUp;
+        let base = if catching_up {
+            entry.next_fire_unix_ms
+        } else {
+            now_unix_ms
+        };
+        let next = entry
+            .spec
+            .first_fire_after(base)
+            .unwrap_or(now_unix_ms + 60_000);
+        // While draining a `CatchUp` backlog, `next` must be allowed to stay
+        // in the past: `tick`'s loop re-pops any entry whose fire time is
+        // `<= now_unix_ms`, so clamping it to `now` here would collapse the
+        // whole backlog into a single slot after just one fire, exactly like
+        // `Skip`. Only the non-catch-up path (`Skip`, or a failed dispatch
+        // retry) needs the floor, since `base` there is already `now`.
+        entry.next_fire_unix_ms = if catching_up { next } else { next.max(now_unix_ms) };
+        self.due.push(Reverse((entry.next_fire_unix_ms, id.to_string())));
+    }
+}
+
+/// A minimal, self-contained 5-field cron evaluator — no external calendar
+/// crate, just integer arithmetic over a UNIX-epoch civil calendar.
+mod cron {
+    const MINUTE_MS: u64 = 60_000;
+    const MAX_MINUTES_AHEAD: u64 = 366 * 24 * 60;
+
+    enum Field {
+        Any,
+        Value(u32),
+        Step(u32),
+    }
+
+    impl Field {
+        fn parse(raw: &str) -> Option<Field> {
+            if raw == "*" {
+                return Some(Field::Any);
+            }
+            if let Some(step) = raw.strip_prefix("*/") {
+                return step.parse().ok().map(Field::Step);
+            }
+            raw.parse().ok().map(Field::Value)
+        }
+
+        fn matches(&self, value: u32) -> bool {
+            match self {
+                Field::Any => true,
+                Field::Value(v) => *v == value,
+                Field::Step(n) => *n > 0 && value % n == 0,
+            }
+        }
+    }
+
+    pub struct CronSchedule {
+        minute: Field,
+        hour: Field,
+        day_of_month: Field,
+        month: Field,
+        day_of_week: Field,
+    }
+
+    impl CronSchedule {
+        pub fn parse(expression: &str) -> Option<CronSchedule> {
+            let fields: Vec<&str> = expression.split_whitespace().collect();
+            let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+                return None;
+            };
+            Some(CronSchedule {
+                minute: Field::parse(minute)?,
+                hour: Field::parse(hour)?,
+                day_of_month: Field::parse(day_of_month)?,
+                month: Field::parse(month)?,
+                day_of_week: Field::parse(day_of_week)?,
+            })
+        }
+
+        /// The smallest whole-minute timestamp strictly after
+        /// `after_unix_ms` that matches every field, scanning forward up to
+        /// a year before giving up.
+        pub fn next_fire_after(&self, after_unix_ms: u64) -> Option<u64> {
+            let mut candidate = (after_unix_ms / MINUTE_MS + 1) * MINUTE_MS;
+            for _ in 0..MAX_MINUTES_AHEAD {
+                let moment = CivilMoment::from_unix_ms(candidate);
+                if self.minute.matches(moment.minute)
+                    && self.hour.matches(moment.hour)
+                    && self.day_of_month.matches(moment.day)
+                    && self.month.matches(moment.month)
+                    && self.day_of_week.matches(moment.weekday)
+                {
+                    return Some(candidate);
+                }
+                candidate += MINUTE_MS;
+            }
+            None
+        }
+    }
+
+    struct CivilMoment {
+        minute: u32,
+        hour: u32,
+        day: u32,
+        month: u32,
+        /// 0 = Sunday, matching cron's day-of-week convention.
+        weekday: u32,
+    }
+
+    impl CivilMoment {
+        fn from_unix_ms(unix_ms: u64) -> CivilMoment {
+            let total_minutes = unix_ms / MINUTE_MS;
+            let minute = (total_minutes % 60) as u32;
+            let total_hours = total_minutes / 60;
+            let hour = (total_hours % 24) as u32;
+            let days = (total_hours / 24) as i64;
+            let weekday = ((days + 4) % 7) as u32; // epoch day 0 = Thursday
+            let (_year, month, day) = civil_from_days(days);
+            CivilMoment {
+                minute,
+                hour,
+                day,
+                month,
+                weekday,
+            }
+        }
+    }
+
+    /// Days-since-epoch -> (year, month, day), proleptic Gregorian. Howard
+    /// Hinnant's `civil_from_days` algorithm — pulled in by value rather
+    /// than a calendar crate dependency since this is the only calendar math
+    /// the scheduler needs.
+    fn civil_from_days(z: i64) -> (i64, u32, u32) {
+        let z = z + 719468;
+        let era = if z >= 0 { z } else { z - 146096 } / 146097;
+        let doe = (z - era * 146097) as u64;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let y = yoe as i64 + era * 400;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let y = if m <= 2 { y + 1 } else { y };
+        (y, m, d)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn every_five_minutes_lands_on_the_next_multiple() {
+            let schedule = CronSchedule::parse("*/5 * * * *").unwrap();
+            // 1970-01-01T00:03:00Z
+            let after = 3 * MINUTE_MS;
+            let fire_at = schedule.next_fire_after(after).unwrap();
+            assert_eq!(fire_at, 5 * MINUTE_MS);
+        }
+
+        #[test]
+        fn fixed_minute_and_hour_waits_for_the_next_day() {
+            let schedule = CronSchedule::parse("30 2 * * *").unwrap();
+            // 1970-01-01T03:00:00Z, already past today's 02:30 slot
+            let after = 3 * 60 * MINUTE_MS;
+            let fire_at = schedule.next_fire_after(after).unwrap();
+            let moment = CivilMoment::from_unix_ms(fire_at);
+            assert_eq!((moment.hour, moment.minute, moment.day), (2, 30, 2));
+        }
+
+        #[test]
+        fn rejects_a_malformed_expression() {
+            assert!(CronSchedule::parse("not a cron string").is_none());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clawden_adapters::builtin_registry;
+    use clawden_core::ClawRuntime;
+    use crate::manager::LifecycleManager;
+
+    fn interval_spec(ms: u64) -> ScheduleSpec {
+        ScheduleSpec::Interval { interval_ms: ms }
+    }
+
+    #[tokio::test]
+    async fn failed_routing_retries_instead_of_dropping_the_entry() {
+        let mut manager = LifecycleManager::new(builtin_registry());
+        manager.register_agent(
+            "alpha".to_string(),
+            ClawRuntime::ZeroClaw,
+            vec!["chat".to_string()],
+        );
+        let mut swarm = SwarmCoordinator::default();
+
+        let mut scheduler = Scheduler::new();
+        scheduler
+            .add_entry(
+                vec!["chat".to_string()],
+                "ping".to_string(),
+                interval_spec(1_000),
+                None,
+                CatchUpPolicy::default(),
+                OverlapPolicy::default(),
+                None,
+                0,
+            )
+            .expect("interval spec is always valid");
+
+        // No agent is running yet, so route_and_send fails; the entry must
+        // survive the tick rather than being dropped.
+        let results = scheduler.tick(&mut manager, &mut swarm, 0).await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].response.is_err());
+        assert_eq!(scheduler.list_entries().len(), 1);
+    }
+
+    #[test]
+    fn add_and_remove_entry() {
+        let mut scheduler = Scheduler::new();
+        let entry = scheduler
+            .add_entry(
+                vec![],
+                "hello".to_string(),
+                interval_spec(5_000),
+                None,
+                CatchUpPolicy::default(),
+                OverlapPolicy::default(),
+                Some(3),
+                0,
+            )
+            .expect("interval spec is always valid");
+        assert_eq!(scheduler.list_entries().len(), 1);
+        assert!(scheduler.remove_entry(&entry.id));
+        assert!(scheduler.list_entries().is_empty());
+    }
+
+    #[test]
+    fn invalid_cron_expression_is_rejected_up_front() {
+        let mut scheduler = Scheduler::new();
+        let result = scheduler.add_entry(
+            vec![],
+            "hello".to_string(),
+            ScheduleSpec::Cron {
+                expression: "not a cron string".to_string(),
+            },
+            None,
+            CatchUpPolicy::default(),
+            OverlapPolicy::default(),
+            None,
+            0,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn catch_up_skip_collapses_a_missed_backlog_into_one_future_slot() {
+        let mut scheduler = Scheduler::new();
+        let entry = scheduler
+            .add_entry(
+                vec![],
+                "hello".to_string(),
+                interval_spec(1_000),
+                None,
+                CatchUpPolicy::Skip,
+                OverlapPolicy::default(),
+                None,
+                0,
+            )
+            .expect("interval spec is always valid");
+
+        // Simulate a long gap: the server wakes up far past several missed
+        // intervals. `Skip` should land the next fire just after `now`,
+        // not several backlog entries behind it.
+        scheduler.reschedule(&entry.id, 50_000, true);
+        let rescheduled = scheduler.list_entries().into_iter().next().unwrap();
+        assert_eq!(rescheduled.next_fire_unix_ms, 51_000);
+    }
+
+    #[test]
+    fn catch_up_drains_one_missed_interval_per_reschedule_call() {
+        let mut scheduler = Scheduler::new();
+        let entry = scheduler
+            .add_entry(
+                vec![],
+                "hello".to_string(),
+                interval_spec(1_000),
+                None,
+                CatchUpPolicy::CatchUp,
+                OverlapPolicy::default(),
+                None,
+                0,
+            )
+            .expect("interval spec is always valid");
+        assert_eq!(
+            scheduler.list_entries().into_iter().next().unwrap().next_fire_unix_ms,
+            1_000
+        );
+
+        // Same long-gap scenario as the `Skip` test above, but `CatchUp`
+        // must fire once per missed interval (the drain loop in `tick`
+        // keeps re-popping the entry while it's still due) instead of
+        // jumping straight to "now" after a single fire.
+        let now_unix_ms = 50_000;
+        let mut fires = 0;
+        loop {
+            let next_fire_unix_ms = scheduler
+                .list_entries()
+                .into_iter()
+                .next()
+                .unwrap()
+                .next_fire_unix_ms;
+            if next_fire_unix_ms > now_unix_ms {
+                break;
+            }
+            scheduler.reschedule(&entry.id, now_unix_ms, true);
+            fires += 1;
+        }
+
+        assert_eq!(fires, 50, "expected one fire per missed 1s interval up to 50s");
+        let rescheduled = scheduler.list_entries().into_iter().next().unwrap();
+        assert_eq!(rescheduled.next_fire_unix_ms, 51_000);
+    }
+}
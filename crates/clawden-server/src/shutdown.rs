@@ -0,0 +1,61 @@
+//! Graceful shutdown coordination for `main`'s background tasks.
+//!
+//! `axum::serve(...).with_graceful_shutdown(...)` only stops accepting new
+//! connections and waits for in-flight ones to finish; it has no way to
+//! reach the health-monitor loop spawned alongside it. [`ShutdownCoordinator`]
+//! is the one signal both sides watch: axum waits on
+//! [`ShutdownCoordinator::signal`] directly, and the monitor loop polls
+//! [`ShutdownCoordinator::subscribe`] each tick via `tokio::select!` so it
+//! exits its loop instead of being killed mid-write.
+
+use tokio::sync::watch;
+
+/// Broadcasts one shutdown signal derived from Ctrl-C or `SIGTERM`.
+pub struct ShutdownCoordinator {
+    sender: watch::Sender<bool>,
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self { sender }
+    }
+
+    /// A receiver background loops can poll in a `tokio::select!` alongside
+    /// their own work, exiting once it reads `true`.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.sender.subscribe()
+    }
+
+    /// Resolves once Ctrl-C or `SIGTERM` arrives, flips the shared signal to
+    /// `true` for every subscriber, and returns — the future to hand
+    /// `axum::serve(...).with_graceful_shutdown(...)`.
+    pub async fn signal(&self) {
+        wait_for_signal().await;
+        let _ = self.sender.send(true);
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    let mut terminate = signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
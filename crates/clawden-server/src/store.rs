@@ -0,0 +1,324 @@
+//! Pluggable persistence for the fleet state that otherwise lives only in
+//! `AppState`'s in-memory `RwLock`s — agents, channel configs/bindings,
+//! swarm teams, and discovered endpoints. (`AuditEvent`s already have their
+//! own durable path via [`clawden_core::SqliteAuditStore`].) [`MemoryStore`]
+//! is the default and what tests get; [`SqliteStore`] is selected by `main`
+//! via `CLAWDEN_DB_PATH` so a restarted daemon reloads fleet state instead
+//! of starting from empty.
+//!
+//! Handlers write through after each in-memory mutation succeeds — the same
+//! "call the store from the handler, not from inside the model object"
+//! shape `append_audit` already uses — rather than `LifecycleManager`/
+//! `ChannelStore` taking a `Store` dependency themselves.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use clawden_core::{ChannelBinding, ChannelInstanceConfig, DiscoveredEndpoint};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::manager::AgentRecord;
+
+/// How long a pooled connection waits on `SQLITE_BUSY` before giving up,
+/// rather than failing immediately the instant another pooled connection
+/// holds the write lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Every entity kind [`Store`] knows how to persist, each as its own
+/// `(key TEXT PRIMARY KEY, data TEXT NOT NULL)` table in the SQL backend —
+/// simpler than a normalized schema per entity, and sufficient since nothing
+/// here is queried by field, only reloaded wholesale on boot.
+pub trait Store: Send + Sync {
+    fn save_agent(&self, agent: &AgentRecord);
+    fn delete_agent(&self, agent_id: &str);
+    fn load_agents(&self) -> Vec<AgentRecord>;
+
+    fn save_channel_config(&self, config: &ChannelInstanceConfig);
+    fn delete_channel_config(&self, instance_name: &str);
+    fn load_channel_configs(&self) -> Vec<ChannelInstanceConfig>;
+
+    fn save_channel_binding(&self, key: &str, binding: &ChannelBinding);
+    fn load_channel_bindings(&self) -> Vec<ChannelBinding>;
+
+    fn save_swarm_team(&self, name: &str, team: &serde_json::Value);
+    fn load_swarm_teams(&self) -> Vec<serde_json::Value>;
+
+    fn save_discovered_endpoint(&self, key: &str, endpoint: &DiscoveredEndpoint);
+    fn load_discovered_endpoints(&self) -> Vec<DiscoveredEndpoint>;
+
+    /// Forces any buffered writes to durable storage, mirroring
+    /// [`clawden_core::AuditStore::flush`]. The default is a no-op, used by
+    /// [`MemoryStore`]; [`SqliteStore`] commits per call and additionally
+    /// checkpoints its WAL here.
+    fn flush(&self) {}
+}
+
+/// Default, non-durable [`Store`] — what a fresh process (and every test)
+/// gets when `CLAWDEN_DB_PATH` isn't set.
+#[derive(Default)]
+pub struct MemoryStore {
+    agents: Mutex<HashMap<String, AgentRecord>>,
+    channel_configs: Mutex<HashMap<String, ChannelInstanceConfig>>,
+    channel_bindings: Mutex<HashMap<String, ChannelBinding>>,
+    swarm_teams: Mutex<HashMap<String, serde_json::Value>>,
+    discovered_endpoints: Mutex<HashMap<String, DiscoveredEndpoint>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Store for MemoryStore {
+    fn save_agent(&self, agent: &AgentRecord) {
+        if let Ok(mut agents) = self.agents.lock() {
+            agents.insert(agent.id.clone(), agent.clone());
+        }
+    }
+
+    fn delete_agent(&self, agent_id: &str) {
+        if let Ok(mut agents) = self.agents.lock() {
+            agents.remove(agent_id);
+        }
+    }
+
+    fn load_agents(&self) -> Vec<AgentRecord> {
+        self.agents
+            .lock()
+            .map_or_else(|_| Vec::new(), |guard| guard.values().cloned().collect())
+    }
+
+    fn save_channel_config(&self, config: &ChannelInstanceConfig) {
+        if let Ok(mut configs) = self.channel_configs.lock() {
+            configs.insert(config.instance_name.clone(), config.clone());
+        }
+    }
+
+    fn delete_channel_config(&self, instance_name: &str) {
+        if let Ok(mut configs) = self.channel_configs.lock() {
+            configs.remove(instance_name);
+        }
+    }
+
+    fn load_channel_configs(&self) -> Vec<ChannelInstanceConfig> {
+        self.channel_configs
+            .lock()
+            .map_or_else(|_| Vec::new(), |guard| guard.values().cloned().collect())
+    }
+
+    fn save_channel_binding(&self, key: &str, binding: &ChannelBinding) {
+        if let Ok(mut bindings) = self.channel_bindings.lock() {
+            bindings.insert(key.to_string(), binding.clone());
+        }
+    }
+
+    fn load_channel_bindings(&self) -> Vec<ChannelBinding> {
+        self.channel_bindings
+            .lock()
+            .map_or_else(|_| Vec::new(), |guard| guard.values().cloned().collect())
+    }
+
+    fn save_swarm_team(&self, name: &str, team: &serde_json::Value) {
+        if let Ok(mut teams) = self.swarm_teams.lock() {
+            teams.insert(name.to_string(), team.clone());
+        }
+    }
+
+    fn load_swarm_teams(&self) -> Vec<serde_json::Value> {
+        self.swarm_teams
+            .lock()
+            .map_or_else(|_| Vec::new(), |guard| guard.values().cloned().collect())
+    }
+
+    fn save_discovered_endpoint(&self, key: &str, endpoint: &DiscoveredEndpoint) {
+        if let Ok(mut endpoints) = self.discovered_endpoints.lock() {
+            endpoints.insert(key.to_string(), endpoint.clone());
+        }
+    }
+
+    fn load_discovered_endpoints(&self) -> Vec<DiscoveredEndpoint> {
+        self.discovered_endpoints
+            .lock()
+            .map_or_else(|_| Vec::new(), |guard| guard.values().cloned().collect())
+    }
+}
+
+/// One versioned schema change, applied in order and tracked via SQLite's
+/// `user_version` pragma so a restart only runs migrations it hasn't seen
+/// yet — the small migrator the persistent backend needs.
+struct Migration {
+    version: u32,
+    up: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        up: "CREATE TABLE agents (key TEXT PRIMARY KEY, data TEXT NOT NULL)",
+    },
+    Migration {
+        version: 2,
+        up: "CREATE TABLE channel_configs (key TEXT PRIMARY KEY, data TEXT NOT NULL)",
+    },
+    Migration {
+        version: 3,
+        up: "CREATE TABLE channel_bindings (key TEXT PRIMARY KEY, data TEXT NOT NULL)",
+    },
+    Migration {
+        version: 4,
+        up: "CREATE TABLE swarm_teams (key TEXT PRIMARY KEY, data TEXT NOT NULL)",
+    },
+    Migration {
+        version: 5,
+        up: "CREATE TABLE discovered_endpoints (key TEXT PRIMARY KEY, data TEXT NOT NULL)",
+    },
+];
+
+fn run_migrations(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let current: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for migration in MIGRATIONS {
+        if migration.version <= current {
+            continue;
+        }
+        conn.execute_batch(migration.up)?;
+        conn.pragma_update(None, "user_version", migration.version)?;
+    }
+    Ok(())
+}
+
+/// Durable [`Store`] backed by a pooled SQLite connection, selected by
+/// `main` when `CLAWDEN_DB_PATH` is set. Every entity kind gets its own
+/// `key`/`data` table (see [`MIGRATIONS`]); `data` is the entity
+/// JSON-encoded, matching [`MemoryStore`]'s "just keep the whole record"
+/// shape instead of a normalized schema.
+pub struct SqliteStore {
+    pool: Pool<SqliteConnectionManager>,
+}
+
+impl SqliteStore {
+    /// Opens (creating if needed) the database at `path`, applies any
+    /// pending migrations, and builds the connection pool handlers draw
+    /// from per-call.
+    ///
+    /// Every pooled connection gets `journal_mode=WAL` and a `busy_timeout`
+    /// set at checkout time: the default rollback-journal mode serializes
+    /// writers at the file-lock level, so two pooled connections writing at
+    /// once routinely hit `SQLITE_BUSY` instead of waiting their turn.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let manager = SqliteConnectionManager::file(path).with_init(|conn| {
+            conn.pragma_update(None, "journal_mode", "WAL")?;
+            conn.busy_timeout(BUSY_TIMEOUT)
+        });
+        let pool = Pool::new(manager).map_err(|e| e.to_string())?;
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        run_migrations(&conn).map_err(|e| e.to_string())?;
+        Ok(Self { pool })
+    }
+
+    fn put(&self, table: &str, key: &str, value: &impl Serialize) {
+        let Ok(conn) = self.pool.get() else {
+            tracing::warn!(table, key, "failed to check out pooled connection for write");
+            return;
+        };
+        let Ok(json) = serde_json::to_string(value) else {
+            tracing::warn!(table, key, "failed to serialize value for write");
+            return;
+        };
+        if let Err(error) = conn.execute(
+            &format!(
+                "INSERT INTO {table} (key, data) VALUES (?1, ?2) \
+                 ON CONFLICT(key) DO UPDATE SET data = excluded.data"
+            ),
+            rusqlite::params![key, json],
+        ) {
+            tracing::warn!(table, key, %error, "failed to persist write");
+        }
+    }
+
+    fn remove(&self, table: &str, key: &str) {
+        let Ok(conn) = self.pool.get() else {
+            tracing::warn!(table, key, "failed to check out pooled connection for delete");
+            return;
+        };
+        if let Err(error) = conn.execute(&format!("DELETE FROM {table} WHERE key = ?1"), rusqlite::params![key]) {
+            tracing::warn!(table, key, %error, "failed to persist delete");
+        }
+    }
+
+    fn load_all<T: DeserializeOwned>(&self, table: &str) -> Vec<T> {
+        let Ok(conn) = self.pool.get() else {
+            return Vec::new();
+        };
+        let Ok(mut stmt) = conn.prepare(&format!("SELECT data FROM {table}")) else {
+            return Vec::new();
+        };
+        let Ok(rows) = stmt.query_map([], |row| row.get::<_, String>(0)) else {
+            return Vec::new();
+        };
+        rows.filter_map(Result::ok)
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect()
+    }
+}
+
+impl Store for SqliteStore {
+    fn save_agent(&self, agent: &AgentRecord) {
+        self.put("agents", &agent.id, agent);
+    }
+
+    fn delete_agent(&self, agent_id: &str) {
+        self.remove("agents", agent_id);
+    }
+
+    fn load_agents(&self) -> Vec<AgentRecord> {
+        self.load_all("agents")
+    }
+
+    fn save_channel_config(&self, config: &ChannelInstanceConfig) {
+        self.put("channel_configs", &config.instance_name, config);
+    }
+
+    fn delete_channel_config(&self, instance_name: &str) {
+        self.remove("channel_configs", instance_name);
+    }
+
+    fn load_channel_configs(&self) -> Vec<ChannelInstanceConfig> {
+        self.load_all("channel_configs")
+    }
+
+    fn save_channel_binding(&self, key: &str, binding: &ChannelBinding) {
+        self.put("channel_bindings", key, binding);
+    }
+
+    fn load_channel_bindings(&self) -> Vec<ChannelBinding> {
+        self.load_all("channel_bindings")
+    }
+
+    fn save_swarm_team(&self, name: &str, team: &serde_json::Value) {
+        self.put("swarm_teams", name, team);
+    }
+
+    fn load_swarm_teams(&self) -> Vec<serde_json::Value> {
+        self.load_all("swarm_teams")
+    }
+
+    fn save_discovered_endpoint(&self, key: &str, endpoint: &DiscoveredEndpoint) {
+        self.put("discovered_endpoints", key, endpoint);
+    }
+
+    fn load_discovered_endpoints(&self) -> Vec<DiscoveredEndpoint> {
+        self.load_all("discovered_endpoints")
+    }
+
+    fn flush(&self) {
+        if let Ok(conn) = self.pool.get() {
+            let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Tracks what happens to a dispatched task beyond the inline response
+//! `send_task`/`fan_out_task` already return. Every dispatch gets a
+//! [`TaskRecord`] that moves through [`TaskState`] as routing resolves, so an
+//! operator can look up what happened — or why it failed — after the fact
+//! instead of only seeing `content` once, on the wire, the moment it arrives.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub id: String,
+    /// Filled in once routing resolves an agent — `None` while `Queued`, and
+    /// still `None` on failure if no agent was ever selected.
+    pub agent_id: Option<String>,
+    pub message: String,
+    pub state: TaskState,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub created_at_unix_ms: u64,
+    pub finished_at_unix_ms: Option<u64>,
+}
+
+/// In-memory task history, mirroring [`crate::scheduler::Scheduler`]'s
+/// `next_id`/`HashMap` shape rather than going through [`crate::store::Store`]
+/// — task history doesn't need to survive a restart the way fleet state does.
+#[derive(Default)]
+pub struct TaskStore {
+    tasks: HashMap<String, TaskRecord>,
+    next_id: AtomicU64,
+}
+
+impl TaskStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a task as `Queued` right before it's dispatched.
+    pub fn create(&mut self, message: String, now_unix_ms: u64) -> TaskRecord {
+        let id = format!("task-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let record = TaskRecord {
+            id: id.clone(),
+            agent_id: None,
+            message,
+            state: TaskState::Queued,
+            result: None,
+            error: None,
+            created_at_unix_ms: now_unix_ms,
+            finished_at_unix_ms: None,
+        };
+        self.tasks.insert(id, record.clone());
+        record
+    }
+
+    pub fn mark_running(&mut self, id: &str, agent_id: String) {
+        if let Some(record) = self.tasks.get_mut(id) {
+            record.state = TaskState::Running;
+            record.agent_id = Some(agent_id);
+        }
+    }
+
+    pub fn mark_succeeded(&mut self, id: &str, result: String, now_unix_ms: u64) {
+        if let Some(record) = self.tasks.get_mut(id) {
+            record.state = TaskState::Succeeded;
+            record.result = Some(result);
+            record.finished_at_unix_ms = Some(now_unix_ms);
+        }
+    }
+
+    pub fn mark_failed(&mut self, id: &str, error: String, now_unix_ms: u64) {
+        if let Some(record) = self.tasks.get_mut(id) {
+            record.state = TaskState::Failed;
+            record.error = Some(error);
+            record.finished_at_unix_ms = Some(now_unix_ms);
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<TaskRecord> {
+        self.tasks.get(id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<TaskRecord> {
+        let mut tasks: Vec<_> = self.tasks.values().cloned().collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        tasks
+    }
+
+    /// A single agent's task history, each entry keyed by its own `id` with
+    /// `error` set when it failed — the "errors table" an operator drills
+    /// into from `GET /agents/:id/tasks`, without a parallel log duplicating
+    /// what's already on each [`TaskRecord`].
+    pub fn list_for_agent(&self, agent_id: &str) -> Vec<TaskRecord> {
+        let mut tasks: Vec<_> = self
+            .tasks
+            .values()
+            .filter(|task| task.agent_id.as_deref() == Some(agent_id))
+            .cloned()
+            .collect();
+        tasks.sort_by(|a, b| a.id.cmp(&b.id));
+        tasks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_transitions_from_queued_to_succeeded() {
+        let mut store = TaskStore::new();
+        let record = store.create("hello".to_string(), 100);
+        assert_eq!(record.state, TaskState::Queued);
+
+        store.mark_running(&record.id, "agent-1".to_string());
+        store.mark_succeeded(&record.id, "hi back".to_string(), 150);
+
+        let updated = store.get(&record.id).expect("task should still exist");
+        assert_eq!(updated.state, TaskState::Succeeded);
+        assert_eq!(updated.agent_id.as_deref(), Some("agent-1"));
+        assert_eq!(updated.result.as_deref(), Some("hi back"));
+        assert_eq!(updated.finished_at_unix_ms, Some(150));
+    }
+
+    #[test]
+    fn failed_task_records_its_error() {
+        let mut store = TaskStore::new();
+        let record = store.create("hello".to_string(), 100);
+        store.mark_failed(&record.id, "no eligible agent".to_string(), 120);
+
+        let updated = store.get(&record.id).expect("task should still exist");
+        assert_eq!(updated.state, TaskState::Failed);
+        assert_eq!(updated.error.as_deref(), Some("no eligible agent"));
+        assert_eq!(updated.finished_at_unix_ms, Some(120));
+    }
+
+    #[test]
+    fn list_for_agent_only_returns_that_agents_tasks() {
+        let mut store = TaskStore::new();
+        let a = store.create("for a".to_string(), 100);
+        let b = store.create("for b".to_string(), 100);
+        store.mark_running(&a.id, "agent-a".to_string());
+        store.mark_running(&b.id, "agent-b".to_string());
+
+        let for_a = store.list_for_agent("agent-a");
+        assert_eq!(for_a.len(), 1);
+        assert_eq!(for_a[0].id, a.id);
+    }
+}
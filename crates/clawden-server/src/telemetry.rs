@@ -0,0 +1,223 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// How many past log lines / metric samples each agent keeps around for a
+/// reconnecting client's replay. Old entries are dropped once a channel
+/// exceeds this.
+const BACKLOG_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub sequence: u64,
+    pub timestamp_unix_ms: u64,
+    pub level: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricPoint {
+    pub sequence: u64,
+    pub timestamp_unix_ms: u64,
+    pub cpu_percent: f32,
+    pub memory_mb: f32,
+    pub queue_depth: u32,
+}
+
+/// One agent's log and metric history plus the broadcast channels that feed
+/// live WebSocket tails. Replay is served from `backlog`; live updates come
+/// from `sender` — a subscriber joins by cloning a receiver, so a slow or
+/// absent client never blocks publishing.
+struct AgentChannel<T> {
+    backlog: VecDeque<T>,
+    next_sequence: u64,
+    sender: broadcast::Sender<T>,
+}
+
+impl<T: Clone> AgentChannel<T> {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(BACKLOG_CAPACITY);
+        Self {
+            backlog: VecDeque::with_capacity(BACKLOG_CAPACITY),
+            next_sequence: 0,
+            sender,
+        }
+    }
+
+    fn push(&mut self, build: impl FnOnce(u64) -> T) -> T {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        let item = build(sequence);
+        if self.backlog.len() >= BACKLOG_CAPACITY {
+            self.backlog.pop_front();
+        }
+        self.backlog.push_back(item.clone());
+        let _ = self.sender.send(item.clone());
+        item
+    }
+
+    fn since(&self, since_sequence: u64) -> (Vec<T>, broadcast::Receiver<T>)
+    where
+        T: SequencedBy,
+    {
+        let replay = self
+            .backlog
+            .iter()
+            .filter(|item| item.sequence() >= since_sequence)
+            .cloned()
+            .collect();
+        (replay, self.sender.subscribe())
+    }
+}
+
+trait SequencedBy {
+    fn sequence(&self) -> u64;
+}
+
+impl SequencedBy for LogLine {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+impl SequencedBy for MetricPoint {
+    fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+#[derive(Default)]
+struct AgentTelemetry {
+    logs: AgentChannel<LogLine>,
+    metrics: AgentChannel<MetricPoint>,
+}
+
+impl Default for AgentChannel<LogLine> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for AgentChannel<MetricPoint> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-agent log/metric streaming state, keyed by agent id. `agent_logs` and
+/// `agent_metrics_history` used to be one-shot stubs; this is what lets the
+/// new `/logs/stream` and `/metrics/stream` WebSocket routes replay backlog
+/// then tail live updates instead.
+#[derive(Default)]
+pub struct TelemetryRegistry {
+    agents: Mutex<HashMap<String, AgentTelemetry>>,
+}
+
+impl TelemetryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_log(&self, agent_id: &str, level: &str, message: &str, now_unix_ms: u64) {
+        let mut agents = self.agents.lock().expect("telemetry mutex poisoned");
+        let entry = agents.entry(agent_id.to_string()).or_default();
+        entry.logs.push(|sequence| LogLine {
+            sequence,
+            timestamp_unix_ms: now_unix_ms,
+            level: level.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    pub fn record_metrics(
+        &self,
+        agent_id: &str,
+        metrics: clawden_core::AgentMetrics,
+        now_unix_ms: u64,
+    ) {
+        let mut agents = self.agents.lock().expect("telemetry mutex poisoned");
+        let entry = agents.entry(agent_id.to_string()).or_default();
+        entry.metrics.push(|sequence| MetricPoint {
+            sequence,
+            timestamp_unix_ms: now_unix_ms,
+            cpu_percent: metrics.cpu_percent,
+            memory_mb: metrics.memory_mb,
+            queue_depth: metrics.queue_depth,
+        });
+    }
+
+    /// Backlog from `since_sequence` onward plus a receiver for subsequent
+    /// live log lines. An agent with no history yet still gets a receiver,
+    /// so a client that connects before the first line is written still
+    /// tails live.
+    pub fn subscribe_logs(
+        &self,
+        agent_id: &str,
+        since_sequence: u64,
+    ) -> (Vec<LogLine>, broadcast::Receiver<LogLine>) {
+        let mut agents = self.agents.lock().expect("telemetry mutex poisoned");
+        let entry = agents.entry(agent_id.to_string()).or_default();
+        entry.logs.since(since_sequence)
+    }
+
+    pub fn subscribe_metrics(
+        &self,
+        agent_id: &str,
+        since_sequence: u64,
+    ) -> (Vec<MetricPoint>, broadcast::Receiver<MetricPoint>) {
+        let mut agents = self.agents.lock().expect("telemetry mutex poisoned");
+        let entry = agents.entry(agent_id.to_string()).or_default();
+        entry.metrics.since(since_sequence)
+    }
+
+    /// Drops an agent's telemetry state entirely, called when it stops so a
+    /// future restart starts its sequence numbers fresh instead of
+    /// continuing an unrelated prior run's backlog.
+    pub fn remove(&self, agent_id: &str) {
+        let mut agents = self.agents.lock().expect("telemetry mutex poisoned");
+        agents.remove(agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_includes_only_entries_from_offset_onward() {
+        let registry = TelemetryRegistry::new();
+        registry.record_log("agent-1", "info", "first", 0);
+        registry.record_log("agent-1", "info", "second", 1);
+        registry.record_log("agent-1", "info", "third", 2);
+
+        let (replay, _receiver) = registry.subscribe_logs("agent-1", 1);
+        assert_eq!(replay.len(), 2);
+        assert_eq!(replay[0].message, "second");
+        assert_eq!(replay[1].message, "third");
+    }
+
+    #[test]
+    fn live_updates_arrive_on_the_subscribed_receiver() {
+        let registry = TelemetryRegistry::new();
+        let (replay, mut receiver) = registry.subscribe_logs("agent-1", 0);
+        assert!(replay.is_empty());
+
+        registry.record_log("agent-1", "warn", "disk usage high", 10);
+        let line = receiver.try_recv().expect("line should be delivered");
+        assert_eq!(line.message, "disk usage high");
+    }
+
+    #[test]
+    fn removing_an_agent_resets_its_sequence_numbers() {
+        let registry = TelemetryRegistry::new();
+        registry.record_log("agent-1", "info", "first", 0);
+        registry.remove("agent-1");
+
+        registry.record_log("agent-1", "info", "after restart", 0);
+        let (replay, _receiver) = registry.subscribe_logs("agent-1", 0);
+        assert_eq!(replay.len(), 1);
+        assert_eq!(replay[0].sequence, 0);
+    }
+}
@@ -0,0 +1,162 @@
+//! mTLS enrollment for the control channel between the server and its
+//! agents. `route_and_send`/`start_agent`/the proxy-status path previously
+//! trusted whatever presented an `agent_id`; [`CertificateAuthority`] issues
+//! each enrolled agent a fingerprinted client certificate. The `send_task`
+//! handler refuses a named agent whose certificate doesn't match what was
+//! issued; `route_and_send`, `start_agent` and the proxy-status path do not
+//! check it.
+//!
+//! There's no real socket-level TLS handshake here — agents are adapter
+//! trait objects called in-process, not peers over a network — so this
+//! models the PKI half (CA, issuance, fingerprint verification) that a real
+//! transport would sit on top of, the same way [`crate::auth`] models SCRAM
+//! exchanges without an actual SASL-speaking socket.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+/// Server-side TLS posture: whether agents connecting to the control channel
+/// must present a certificate the CA issued, versus being allowed to connect
+/// unauthenticated (e.g. in a dev deployment with `CLAWDEN_REQUIRE_CLIENT_CERT`
+/// unset).
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub require_client_cert: bool,
+}
+
+impl TlsConfig {
+    pub fn from_env() -> Self {
+        let require_client_cert = std::env::var("CLAWDEN_REQUIRE_CLIENT_CERT")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        Self {
+            require_client_cert,
+        }
+    }
+}
+
+/// A client certificate issued to one agent: a PEM-shaped artifact carrying
+/// the agent's id as its subject, plus the fingerprint
+/// [`CertificateAuthority::verify_fingerprint`] checks presented certs
+/// against.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct IssuedCertificate {
+    pub agent_id: String,
+    pub fingerprint: String,
+    pub cert_pem: String,
+}
+
+fn pem_block(label: &str, subject: &str, fingerprint: &str) -> String {
+    let body = BASE64.encode(format!("subject={subject};fingerprint={fingerprint}"));
+    format!("-----BEGIN {label}-----\n{body}\n-----END {label}-----\n")
+}
+
+fn fingerprint_of(material: &[u8]) -> String {
+    let digest = Sha256::digest(material);
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// The server's own self-signed root, plus a record of which fingerprint
+/// each enrolled agent was issued — an in-memory CA, matching how
+/// [`clawden_core::AuditLog`] and [`crate::telemetry::TelemetryRegistry`]
+/// stand in for a durable store elsewhere in this crate.
+pub struct CertificateAuthority {
+    ca_fingerprint: String,
+    ca_cert_pem: String,
+    enrolled: Mutex<HashMap<String, String>>,
+}
+
+impl CertificateAuthority {
+    pub fn new() -> Self {
+        let ca_fingerprint = fingerprint_of(b"clawden-server-ca-root");
+        let ca_cert_pem = pem_block("CERTIFICATE", "clawden-server-ca", &ca_fingerprint);
+        Self {
+            ca_fingerprint,
+            ca_cert_pem,
+            enrolled: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ca_certificate_pem(&self) -> &str {
+        &self.ca_cert_pem
+    }
+
+    /// Issues `agent_id` a client certificate, overwriting any certificate it
+    /// previously held — re-enrolling an agent revokes its old fingerprint
+    /// implicitly, since [`Self::verify_fingerprint`] only ever checks the
+    /// latest one on file.
+    pub fn enroll(&self, agent_id: &str) -> IssuedCertificate {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let mut material = format!("{agent_id}:{}:", self.ca_fingerprint).into_bytes();
+        material.extend_from_slice(&nonce);
+        let fingerprint = fingerprint_of(&material);
+        let cert_pem = pem_block("CERTIFICATE", agent_id, &fingerprint);
+
+        let mut enrolled = self.enrolled.lock().expect("ca mutex poisoned");
+        enrolled.insert(agent_id.to_string(), fingerprint.clone());
+
+        IssuedCertificate {
+            agent_id: agent_id.to_string(),
+            fingerprint,
+            cert_pem,
+        }
+    }
+
+    /// `true` if `agent_id` was enrolled and `presented_fingerprint` matches
+    /// what it was issued. An agent never enrolled has nothing to match
+    /// against and fails closed.
+    ///
+    /// Compared in constant time, the same as the SASL client proof in
+    /// [`clawden_core::sasl::verify_client_proof`]: this fingerprint is a
+    /// network-presented credential, so a byte-at-a-time `==` would let a
+    /// timing attacker narrow it down one byte at a time.
+    pub fn verify_fingerprint(&self, agent_id: &str, presented_fingerprint: &str) -> bool {
+        let enrolled = self.enrolled.lock().expect("ca mutex poisoned");
+        enrolled.get(agent_id).is_some_and(|fingerprint| {
+            fingerprint
+                .as_bytes()
+                .ct_eq(presented_fingerprint.as_bytes())
+                .unwrap_u8()
+                == 1
+        })
+    }
+}
+
+impl Default for CertificateAuthority {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enrolled_fingerprint_verifies_and_unknown_ones_fail() {
+        let ca = CertificateAuthority::new();
+        let issued = ca.enroll("agent-1");
+
+        assert!(ca.verify_fingerprint("agent-1", &issued.fingerprint));
+        assert!(!ca.verify_fingerprint("agent-1", "not-the-real-fingerprint"));
+        assert!(!ca.verify_fingerprint("agent-2", &issued.fingerprint));
+    }
+
+    #[test]
+    fn re_enrolling_revokes_the_previous_fingerprint() {
+        let ca = CertificateAuthority::new();
+        let first = ca.enroll("agent-1");
+        let second = ca.enroll("agent-1");
+
+        assert_ne!(first.fingerprint, second.fingerprint);
+        assert!(!ca.verify_fingerprint("agent-1", &first.fingerprint));
+        assert!(ca.verify_fingerprint("agent-1", &second.fingerprint));
+    }
+}
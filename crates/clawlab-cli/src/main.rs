@@ -75,6 +75,10 @@ enum TaskCommand {
         agent_id: Option<String>,
         #[arg(long = "capability")]
         required_capabilities: Vec<String>,
+        /// Print incremental output as it arrives instead of waiting for
+        /// the full response.
+        #[arg(long)]
+        stream: bool,
     },
 }
 
@@ -87,7 +91,28 @@ enum SkillCommand {
 
 #[derive(Debug, Subcommand)]
 enum ConfigCommand {
-    Set { key: String, value: String },
+    Set {
+        key: String,
+        value: String,
+        /// Identifies this operator in the op's accept-stamp, so concurrent
+        /// writers resolve deterministically instead of racing.
+        #[arg(long, default_value = "cli")]
+        writer_id: String,
+        /// Require the key to be absent for this write to take effect.
+        #[arg(long)]
+        expect_absent: bool,
+        /// Require the key to currently hold this value (with
+        /// --expected-version) for this write to take effect.
+        #[arg(long)]
+        expected_value: Option<String>,
+        #[arg(long)]
+        expected_version: Option<u64>,
+        /// Value to write instead when the dependency check fails, so the
+        /// conflict is visible rather than silently overwritten
+        /// (last-writer-wins is the default fallback).
+        #[arg(long)]
+        conflict_marker: Option<String>,
+    },
     Diff,
 }
 
@@ -139,6 +164,17 @@ struct FleetStatus {
     degraded_agents: usize,
 }
 
+#[derive(Debug, Serialize)]
+struct ConfigSetRequest {
+    key: String,
+    value: String,
+    writer_id: String,
+    expect_absent: bool,
+    expected_value: Option<String>,
+    expected_version: Option<u64>,
+    conflict_marker: Option<String>,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
     let client = Client::new();
@@ -214,23 +250,103 @@ fn main() -> Result<()> {
                 message,
                 agent_id,
                 required_capabilities,
+                stream,
             } => {
                 let body = SendTaskRequest {
                     message,
                     required_capabilities,
                     agent_id,
                 };
+                if stream {
+                    let response = client
+                        .post(format!("{base}/task/send/stream"))
+                        .json(&body)
+                        .send()?
+                        .error_for_status()?;
+                    print_sse_stream(response)?;
+                } else {
+                    let response = client
+                        .post(format!("{base}/task/send"))
+                        .json(&body)
+                        .send()?
+                        .error_for_status()?;
+                    println!("{}", response.text()?);
+                }
+            }
+        },
+        Commands::Skill { command } => println!("skill command: {command:?}"),
+        Commands::Config { command } => match command {
+            ConfigCommand::Set {
+                key,
+                value,
+                writer_id,
+                expect_absent,
+                expected_value,
+                expected_version,
+                conflict_marker,
+            } => {
+                let body = ConfigSetRequest {
+                    key,
+                    value,
+                    writer_id,
+                    expect_absent,
+                    expected_value,
+                    expected_version,
+                    conflict_marker,
+                };
                 let response = client
-                    .post(format!("{base}/task/send"))
+                    .post(format!("{base}/config"))
                     .json(&body)
                     .send()?
                     .error_for_status()?;
                 println!("{}", response.text()?);
             }
+            ConfigCommand::Diff => {
+                let response = client
+                    .get(format!("{base}/config/diff"))
+                    .send()?
+                    .error_for_status()?;
+                println!("{}", response.text()?);
+            }
         },
-        Commands::Skill { command } => println!("skill command: {command:?}"),
-        Commands::Config { command } => println!("config command: {command:?}"),
     }
 
     Ok(())
 }
+
+/// Reads a Server-Sent-Events body line by line and prints each event as it
+/// arrives, rather than buffering the whole response like the non-streaming
+/// path. Recognizes the `queued`/`assigned`/`token`/`done`/`error` events
+/// `/task/send/stream` emits.
+fn print_sse_stream(response: reqwest::blocking::Response) -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let reader = BufReader::new(response);
+    let mut current_event = String::from("message");
+
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(event) = line.strip_prefix("event:") {
+            current_event = event.trim().to_string();
+            continue;
+        }
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let data = data.trim();
+
+        match current_event.as_str() {
+            "queued" => println!("[queued]"),
+            "assigned" => println!("[assigned] {data}"),
+            "token" => print!("{data}"),
+            "done" => {
+                println!();
+                println!("[done]");
+            }
+            "error" => println!("\n[error] {data}"),
+            _ => println!("{data}"),
+        }
+        std::io::stdout().flush()?;
+    }
+    Ok(())
+}
@@ -1,20 +1,58 @@
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::response::sse::{Event, Sse};
+use axum::response::IntoResponse;
 use axum::Json;
 use clawlab_core::ClawRuntime;
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use tokio_stream::wrappers::ReceiverStream;
 
 use crate::audit::AuditLog;
+use crate::batch::{BatchItem, OneOrMany};
+use crate::config::{
+    current_unix_ms, ConfigDiffEntry, ConfigStore, DependencyCheck, MergeProcedure,
+};
+use crate::jobs::{JobRecord, JobStore};
 use crate::lifecycle::AgentState;
-use crate::manager::{append_audit, AgentRecord, LifecycleManager};
+use crate::manager::{append_audit, AgentRecord, ClawdenError, LifecycleManager};
+use crate::scheduler::{ScheduleEntry, ScheduledAction, Scheduler};
+
+/// Maps each [`ClawdenError`] variant to the HTTP status a client should
+/// treat as stable/machine-readable, and serializes the body as
+/// `{ "error": <code>, "message": <detail> }` — `error` is safe to branch
+/// on, `message` is `Display`'s human-readable wording for logs/debugging.
+impl IntoResponse for ClawdenError {
+    fn into_response(self) -> axum::response::Response {
+        let status = match &self {
+            ClawdenError::AgentNotFound(_) => StatusCode::NOT_FOUND,
+            ClawdenError::InvalidTransition { .. }
+            | ClawdenError::NotRunning(_)
+            | ClawdenError::NoHandle(_) => StatusCode::CONFLICT,
+            ClawdenError::NoAdapter(_) => StatusCode::NOT_IMPLEMENTED,
+            ClawdenError::NoEligibleAgent => StatusCode::SERVICE_UNAVAILABLE,
+            ClawdenError::AdapterFailure(_) => StatusCode::BAD_GATEWAY,
+        };
+        let body = Json(serde_json::json!({
+            "error": self.code(),
+            "message": self.to_string(),
+        }));
+        (status, body).into_response()
+    }
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub manager: Arc<RwLock<LifecycleManager>>,
     pub audit: Arc<AuditLog>,
+    pub config: Arc<ConfigStore>,
+    pub scheduler: Arc<Scheduler>,
+    pub jobs: Arc<RwLock<JobStore>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -61,15 +99,34 @@ pub async fn list_agents(State(state): State<AppState>) -> Json<Vec<AgentRecord>
     Json(manager.list_agents())
 }
 
+/// Batch counterpart to [`register_agent`]: accepts either a single
+/// registration object or an array of them and registers each in turn
+/// under one write-lock acquisition, so bringing up a whole fleet is one
+/// HTTP round trip instead of one per agent. `register_agent` itself never
+/// fails, so every entry comes back `ok`.
+pub async fn register_agents_batch(
+    State(state): State<AppState>,
+    Json(request): Json<OneOrMany<RegisterAgentRequest>>,
+) -> (StatusCode, Json<Vec<BatchItem<AgentRecord>>>) {
+    let mut manager = state.manager.write().await;
+    let results = request
+        .into_vec()
+        .into_iter()
+        .map(|item| {
+            let record = manager.register_agent(item.name, item.runtime, item.capabilities);
+            append_audit(&state.audit, "agent.register", &record.id);
+            BatchItem::ok(record)
+        })
+        .collect();
+    (StatusCode::CREATED, Json(results))
+}
+
 pub async fn start_agent(
     State(state): State<AppState>,
     Path(agent_id): Path<String>,
-) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+) -> Result<Json<AgentRecord>, ClawdenError> {
     let mut manager = state.manager.write().await;
-    let record = manager
-        .start_agent(&agent_id)
-        .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let record = manager.start_agent(&agent_id).await?;
     append_audit(&state.audit, "agent.start", &agent_id);
     Ok(Json(record))
 }
@@ -77,12 +134,9 @@ pub async fn start_agent(
 pub async fn stop_agent(
     State(state): State<AppState>,
     Path(agent_id): Path<String>,
-) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+) -> Result<Json<AgentRecord>, ClawdenError> {
     let mut manager = state.manager.write().await;
-    let record = manager
-        .stop_agent(&agent_id)
-        .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let record = manager.stop_agent(&agent_id).await?;
     append_audit(&state.audit, "agent.stop", &agent_id);
     Ok(Json(record))
 }
@@ -112,7 +166,7 @@ pub async fn fleet_status(State(state): State<AppState>) -> Json<FleetStatusResp
 pub async fn send_task(
     State(state): State<AppState>,
     Json(request): Json<SendTaskRequest>,
-) -> Result<Json<TaskSendResponse>, (StatusCode, String)> {
+) -> Result<Json<TaskSendResponse>, ClawdenError> {
     let mut manager = state.manager.write().await;
     let (agent, response) = manager
         .route_and_send(
@@ -120,8 +174,7 @@ pub async fn send_task(
             request.message,
             request.agent_id.clone(),
         )
-        .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+        .await?;
 
     append_audit(&state.audit, "task.send", &agent.id);
 
@@ -131,6 +184,455 @@ pub async fn send_task(
     }))
 }
 
+/// Batch counterpart to [`send_task`]: accepts either a single task or an
+/// array of them. Agent selection shares mutable round-robin state, so
+/// every item is selected one at a time, in request order, under a single
+/// write-lock acquisition *before* any dispatch starts; each selected
+/// item's adapter call then runs concurrently with the others via
+/// [`futures::future::join_all`] — the write lock is only reacquired
+/// briefly per item to hand off the dispatch, not held for the adapter
+/// call itself, so the items' adapter calls can actually overlap instead of
+/// serializing behind one shared guard — and one item's failure is
+/// reported in its own slot rather than aborting the rest.
+pub async fn send_tasks_batch(
+    State(state): State<AppState>,
+    Json(request): Json<OneOrMany<SendTaskRequest>>,
+) -> Json<Vec<BatchItem<TaskSendResponse>>> {
+    let mut selections = Vec::new();
+    {
+        let mut manager = state.manager.write().await;
+        for item in request.into_vec() {
+            let selected = match &item.agent_id {
+                Some(id) => Ok(id.clone()),
+                None => manager.select_agent(&item.required_capabilities),
+            };
+            selections.push((item.message, selected));
+        }
+    }
+
+    let dispatches = selections.into_iter().map(|(message, selected)| {
+        let state = state.clone();
+        async move {
+            let agent_id = selected?;
+            let pending = state
+                .manager
+                .write()
+                .await
+                .dispatch_to_agent(&agent_id, message)?;
+            let response = pending.await?;
+            let agent = state
+                .manager
+                .read()
+                .await
+                .list_agents()
+                .into_iter()
+                .find(|a| a.id == agent_id)
+                .ok_or_else(|| ClawdenError::AgentNotFound(agent_id.clone()))?;
+            append_audit(&state.audit, "task.send", &agent_id);
+            Ok(TaskSendResponse {
+                agent,
+                content: response.content,
+            })
+        }
+    });
+
+    let results = futures::future::join_all(dispatches)
+        .await
+        .into_iter()
+        .map(|result: Result<TaskSendResponse, ClawdenError>| match result {
+            Ok(value) => BatchItem::ok(value),
+            Err(error) => BatchItem::err(error.to_string()),
+        })
+        .collect();
+
+    Json(results)
+}
+
+/// Streaming counterpart to [`send_task`]: emits `queued`, `assigned`,
+/// `token` (chunked), `done`/`error` as Server-Sent-Events instead of
+/// buffering the whole response. The lifecycle manager itself still drives
+/// the task as a single `route_and_send` call, so the token chunks are the
+/// final response split for incremental display rather than true
+/// per-token generation — this is the seam a real streaming adapter would
+/// plug into later.
+pub async fn send_task_stream(
+    State(state): State<AppState>,
+    Json(request): Json<SendTaskRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Event>(16);
+
+    tokio::spawn(async move {
+        let _ = tx.send(Event::default().event("queued").data("{}")).await;
+
+        let mut manager = state.manager.write().await;
+        let result = manager
+            .route_and_send(
+                &request.required_capabilities,
+                request.message,
+                request.agent_id.clone(),
+            )
+            .await;
+        drop(manager);
+
+        match result {
+            Ok((agent, response)) => {
+                append_audit(&state.audit, "task.send", &agent.id);
+                let _ = tx
+                    .send(
+                        Event::default()
+                            .event("assigned")
+                            .json_data(serde_json::json!({ "agent_id": agent.id }))
+                            .unwrap_or_else(|_| Event::default().event("error")),
+                    )
+                    .await;
+
+                for chunk in chunk_for_streaming(&response.content) {
+                    let _ = tx
+                        .send(Event::default().event("token").data(chunk))
+                        .await;
+                }
+                let _ = tx.send(Event::default().event("done").data("{}")).await;
+            }
+            Err(e) => {
+                let _ = tx
+                    .send(Event::default().event("error").data(e.to_string()))
+                    .await;
+            }
+        }
+    });
+
+    Sse::new(ReceiverStream::new(rx)).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    )
+}
+
+/// Splits `content` into terminal-friendly chunks for the `token` SSE
+/// events; whole words so a client printing each chunk as it arrives
+/// doesn't split a word across two lines.
+fn chunk_for_streaming(content: &str) -> Vec<String> {
+    let words: Vec<&str> = content.split_whitespace().collect();
+    if words.is_empty() {
+        return vec![content.to_string()];
+    }
+    words
+        .chunks(4)
+        .map(|group| format!("{} ", group.join(" ")))
+        .collect()
+}
+
 pub async fn audit_log(State(state): State<AppState>) -> Json<Vec<crate::audit::AuditEvent>> {
     Json(state.audit.list())
 }
+
+// --- Config endpoints (Bayou-style optimistic replication) ---
+
+/// `POST /config` request body. `expected_value`/`expected_version` given
+/// together assert `DependencyCheck::Equals`; `expect_absent` alone asserts
+/// `DependencyCheck::Absent`; neither asserts `DependencyCheck::Any`, which
+/// always holds, so the update always applies and `conflict_marker` is never
+/// used. With `Equals` or `Absent`, `conflict_marker` is written in place of
+/// the value when the check fails on replay; without it the value is written
+/// anyway (last writer wins).
+#[derive(Debug, Deserialize)]
+pub struct ConfigSetRequest {
+    pub key: String,
+    pub value: String,
+    pub writer_id: String,
+    #[serde(default)]
+    pub expect_absent: bool,
+    #[serde(default)]
+    pub expected_value: Option<String>,
+    #[serde(default)]
+    pub expected_version: Option<u64>,
+    #[serde(default)]
+    pub conflict_marker: Option<String>,
+}
+
+impl ConfigSetRequest {
+    fn dependency_check(&self) -> DependencyCheck {
+        match (&self.expected_value, self.expected_version) {
+            (Some(value), Some(version)) => DependencyCheck::Equals {
+                value: value.clone(),
+                version,
+            },
+            _ if self.expect_absent => DependencyCheck::Absent,
+            _ => DependencyCheck::Any,
+        }
+    }
+
+    fn merge_procedure(&self) -> MergeProcedure {
+        match &self.conflict_marker {
+            Some(marker) => MergeProcedure::ConflictMarker {
+                marker: marker.clone(),
+            },
+            None => MergeProcedure::LastWriterWins,
+        }
+    }
+}
+
+pub async fn config_set(
+    State(state): State<AppState>,
+    Json(request): Json<ConfigSetRequest>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let check = request.dependency_check();
+    let merge = request.merge_procedure();
+    let op = state.config.propose(
+        request.key.clone(),
+        request.value,
+        check,
+        merge,
+        request.writer_id,
+        current_unix_ms(),
+    );
+    append_audit(&state.audit, "config.set", &request.key);
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::to_value(op).unwrap_or_default()),
+    )
+}
+
+pub async fn config_diff(State(state): State<AppState>) -> Json<Vec<ConfigDiffEntry>> {
+    Json(state.config.diff(current_unix_ms()))
+}
+
+// --- Scheduler endpoints ---
+
+#[derive(Debug, Deserialize)]
+pub struct CreateScheduleRequest {
+    pub interval_ms: u64,
+    pub action: ScheduledAction,
+}
+
+pub async fn create_schedule(
+    State(state): State<AppState>,
+    Json(request): Json<CreateScheduleRequest>,
+) -> (StatusCode, Json<ScheduleEntry>) {
+    let entry = state
+        .scheduler
+        .add(request.interval_ms, request.action, current_unix_ms())
+        .await;
+    append_audit(&state.audit, "schedule.create", &entry.id);
+    (StatusCode::CREATED, Json(entry))
+}
+
+pub async fn list_schedule(State(state): State<AppState>) -> Json<Vec<ScheduleEntry>> {
+    Json(state.scheduler.list().await)
+}
+
+pub async fn delete_schedule(
+    State(state): State<AppState>,
+    Path(schedule_id): Path<String>,
+) -> StatusCode {
+    if state.scheduler.remove(&schedule_id).await {
+        append_audit(&state.audit, "schedule.delete", &schedule_id);
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+// --- Asynchronous job endpoints ---
+
+/// Selects (and so reserves) an agent synchronously, records the job as
+/// `Queued`, and returns `202 Accepted` immediately — the adapter call
+/// itself runs on a spawned background task via [`run_job`], so a
+/// long-running agent call no longer ties up the HTTP request the way
+/// `send_task` does.
+pub async fn submit_task(
+    State(state): State<AppState>,
+    Json(request): Json<SendTaskRequest>,
+) -> Result<(StatusCode, Json<JobRecord>), ClawdenError> {
+    let agent_id = match request.agent_id.clone() {
+        Some(id) => id,
+        None => {
+            state
+                .manager
+                .write()
+                .await
+                .select_agent(&request.required_capabilities)?
+        }
+    };
+
+    let job = state
+        .jobs
+        .write()
+        .await
+        .create(agent_id, request.message, current_unix_ms());
+    append_audit(&state.audit, "task.submit", &job.id);
+
+    tokio::spawn(run_job(
+        job.id.clone(),
+        state.manager.clone(),
+        state.jobs.clone(),
+        state.audit.clone(),
+    ));
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Background worker behind [`submit_task`]: transitions the job to
+/// `Running` (incrementing the agent's `task_count`, via
+/// `LifecycleManager::dispatch_to_agent`), then records `Finished` or
+/// `Failed` once the adapter call resolves.
+async fn run_job(
+    job_id: String,
+    manager: Arc<RwLock<LifecycleManager>>,
+    jobs: Arc<RwLock<JobStore>>,
+    audit: Arc<AuditLog>,
+) {
+    let Some(job) = jobs.read().await.get(&job_id) else {
+        return;
+    };
+
+    jobs.write().await.mark_running(&job_id);
+    append_audit(&audit, "task.running", &job_id);
+
+    // Acquire the write lock only for the synchronous record/handle/adapter
+    // lookup `dispatch_to_agent` does, then drop it before awaiting the
+    // returned future — so one job's (potentially slow) adapter call
+    // doesn't block every other fleet operation for its duration.
+    let pending = manager
+        .write()
+        .await
+        .dispatch_to_agent(&job.agent_id, job.message);
+    let result = match pending {
+        Ok(future) => future.await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(response) => {
+            jobs.write()
+                .await
+                .mark_finished(&job_id, response, current_unix_ms());
+            append_audit(&audit, "task.finished", &job_id);
+        }
+        Err(error) => {
+            jobs.write()
+                .await
+                .mark_failed(&job_id, error.to_string(), current_unix_ms());
+            append_audit(&audit, "task.failed", &job_id);
+        }
+    }
+}
+
+pub async fn get_task(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobRecord>, StatusCode> {
+    state
+        .jobs
+        .read()
+        .await
+        .get(&job_id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn list_tasks(State(state): State<AppState>) -> Json<Vec<JobRecord>> {
+    Json(state.jobs.read().await.list())
+}
+
+/// Cancels a still-`Queued` job; once [`run_job`] has picked it up this
+/// returns `409 Conflict` rather than attempting to un-dispatch it.
+pub async fn cancel_task(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> StatusCode {
+    if state.jobs.write().await.cancel_if_queued(&job_id) {
+        append_audit(&state.audit, "task.cancel", &job_id);
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::CONFLICT
+    }
+}
+
+// --- Live event subscription (SSE) ---
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeEventsParams {
+    #[serde(default = "default_subscribed_event")]
+    pub event: String,
+}
+
+fn default_subscribed_event() -> String {
+    "*".to_string()
+}
+
+/// Opens `agent_id`'s adapter event subscription and forwards each yielded
+/// value as an SSE `data:` frame until the client disconnects, the agent
+/// stops, or the adapter stream ends — at which point a final `event: end`
+/// frame closes things out cleanly. Shares `send_task_stream`'s
+/// channel-and-spawn shape, but pumps items off a long-lived adapter stream
+/// instead of a single `route_and_send` result.
+pub async fn agent_events_stream(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(params): Query<SubscribeEventsParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ClawdenError> {
+    let manager = state.manager.read().await;
+    let events = manager.subscribe_events(&agent_id, &params.event).await?;
+    drop(manager);
+
+    append_audit(&state.audit, "agent.subscribe", &agent_id);
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Event>(16);
+    tokio::spawn(forward_agent_events(state, agent_id, events, tx));
+
+    Ok(Sse::new(ReceiverStream::new(rx)).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
+
+/// Drives `events` to completion on behalf of [`agent_events_stream`],
+/// stopping early if the agent transitions out of `Running` (the adapter
+/// stream itself has no way to know that) or if the client has already
+/// hung up on `tx`.
+async fn forward_agent_events(
+    state: AppState,
+    agent_id: String,
+    mut events: clawlab_core::EventStream,
+    tx: tokio::sync::mpsc::Sender<Event>,
+) {
+    loop {
+        if !agent_is_running(&state, &agent_id).await {
+            break;
+        }
+        tokio::select! {
+            item = events.next() => match item {
+                Some(Ok(value)) => {
+                    let event = Event::default()
+                        .json_data(value)
+                        .unwrap_or_else(|_| Event::default().event("error"));
+                    if tx.send(event).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Err(e)) => {
+                    let _ = tx
+                        .send(Event::default().event("error").data(e.to_string()))
+                        .await;
+                    break;
+                }
+                None => break,
+            },
+            _ = tx.closed() => return,
+        }
+    }
+    let _ = tx.send(Event::default().event("end").data("{}")).await;
+}
+
+/// Whether `agent_id` is still tracked and `Running` — checked between
+/// each forwarded event so a stopped agent's subscription closes instead of
+/// idling on a stream nothing will ever push to again.
+async fn agent_is_running(state: &AppState, agent_id: &str) -> bool {
+    let manager = state.manager.read().await;
+    manager
+        .list_agents()
+        .iter()
+        .any(|a| a.id == agent_id && a.state == AgentState::Running)
+}
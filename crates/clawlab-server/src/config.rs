@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Precondition a [`ConfigOp`] asserts against the running state before its
+/// update is allowed to apply.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DependencyCheck {
+    /// The key must not currently exist.
+    Absent,
+    /// The key must currently hold `value` at `version`.
+    Equals { value: String, version: u64 },
+    /// No precondition — the update always applies.
+    Any,
+}
+
+impl DependencyCheck {
+    fn holds(&self, current: Option<&VersionedValue>) -> bool {
+        match self {
+            DependencyCheck::Absent => current.is_none(),
+            DependencyCheck::Equals { value, version } => current
+                .map(|v| &v.value == value && v.version == *version)
+                .unwrap_or(false),
+            DependencyCheck::Any => true,
+        }
+    }
+}
+
+/// Fallback applied in place of the update when an op's [`DependencyCheck`]
+/// fails against the running state.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MergeProcedure {
+    /// Apply the op's update anyway, overwriting whatever is there.
+    LastWriterWins,
+    /// Overwrite the key with a conflict marker instead of the op's value,
+    /// so operators can see the precondition failed rather than silently
+    /// losing a write.
+    ConflictMarker { marker: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VersionedValue {
+    pub value: String,
+    pub version: u64,
+}
+
+/// One entry of the append-only Bayou operation log: a dependency check, the
+/// update it guards, and the merge procedure to run instead when the check
+/// fails. Ops are ordered by `accept_stamp` — `(timestamp_unix_ms,
+/// writer_id)` — not by arrival order, so a late-arriving op with an older
+/// timestamp is inserted into the middle of the log rather than appended.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigOp {
+    pub accept_stamp: (u64, String),
+    pub key: String,
+    pub value: String,
+    pub check: DependencyCheck,
+    pub merge: MergeProcedure,
+}
+
+fn apply_op(view: &mut HashMap<String, VersionedValue>, op: &ConfigOp) {
+    let current = view.get(&op.key);
+    let next_version = current.map(|v| v.version + 1).unwrap_or(0);
+    let resolved = if op.check.holds(current) {
+        VersionedValue {
+            value: op.value.clone(),
+            version: next_version,
+        }
+    } else {
+        match &op.merge {
+            MergeProcedure::LastWriterWins => VersionedValue {
+                value: op.value.clone(),
+                version: next_version,
+            },
+            MergeProcedure::ConflictMarker { marker } => VersionedValue {
+                value: marker.clone(),
+                version: next_version,
+            },
+        }
+    };
+    view.insert(op.key.clone(), resolved);
+}
+
+/// Delta between the committed and tentative views of a single key.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiffEntry {
+    pub key: String,
+    pub committed: Option<VersionedValue>,
+    pub tentative: Option<VersionedValue>,
+}
+
+/// Bayou-style optimistic-replication config store. Writes never mutate
+/// state directly; they're recorded as [`ConfigOp`]s in an append-only log
+/// ordered by accept-stamp. Two views are derived from the log on demand:
+///
+/// - *committed*: the stable prefix — ops old enough (`commit_horizon_ms`)
+///   that no earlier-stamped op can plausibly still arrive and reorder them.
+/// - *tentative*: committed plus the remaining ops, replayed in
+///   accept-stamp order against the running state.
+///
+/// A late op lands in the log ahead of existing tentative ops; since the
+/// tentative view is always recomputed by replaying from the committed
+/// prefix, this naturally rolls back and re-executes every tentative op in
+/// the corrected order rather than patching the old result in place.
+pub struct ConfigStore {
+    log: Mutex<Vec<ConfigOp>>,
+    commit_horizon_ms: u64,
+}
+
+impl ConfigStore {
+    pub fn new(commit_horizon_ms: u64) -> Self {
+        Self {
+            log: Mutex::new(Vec::new()),
+            commit_horizon_ms,
+        }
+    }
+
+    /// Records a new op at `(now_ms, writer_id)`, inserting it in
+    /// accept-stamp order rather than simply appending, so a late op takes
+    /// its rightful place among already-tentative ops.
+    pub fn propose(
+        &self,
+        key: String,
+        value: String,
+        check: DependencyCheck,
+        merge: MergeProcedure,
+        writer_id: String,
+        now_ms: u64,
+    ) -> ConfigOp {
+        let op = ConfigOp {
+            accept_stamp: (now_ms, writer_id),
+            key,
+            value,
+            check,
+            merge,
+        };
+        let mut log = self.log.lock().expect("config log mutex poisoned");
+        let insert_at = log.partition_point(|existing| existing.accept_stamp < op.accept_stamp);
+        log.insert(insert_at, op.clone());
+        op
+    }
+
+    fn committed_split(log: &[ConfigOp], now_ms: u64, commit_horizon_ms: u64) -> usize {
+        let horizon = now_ms.saturating_sub(commit_horizon_ms);
+        log.partition_point(|op| op.accept_stamp.0 <= horizon)
+    }
+
+    pub fn committed_view(&self, now_ms: u64) -> HashMap<String, VersionedValue> {
+        let log = self.log.lock().expect("config log mutex poisoned");
+        let split = Self::committed_split(&log, now_ms, self.commit_horizon_ms);
+        let mut view = HashMap::new();
+        for op in &log[..split] {
+            apply_op(&mut view, op);
+        }
+        view
+    }
+
+    pub fn tentative_view(&self, now_ms: u64) -> HashMap<String, VersionedValue> {
+        let log = self.log.lock().expect("config log mutex poisoned");
+        let mut view = HashMap::new();
+        for op in log.iter() {
+            apply_op(&mut view, op);
+        }
+        view
+    }
+
+    /// Returns every key whose committed and tentative values disagree —
+    /// the still-pending, not-yet-stable changes.
+    pub fn diff(&self, now_ms: u64) -> Vec<ConfigDiffEntry> {
+        let committed = self.committed_view(now_ms);
+        let tentative = self.tentative_view(now_ms);
+
+        let mut keys: Vec<&String> = committed.keys().chain(tentative.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let committed_value = committed.get(key).cloned();
+                let tentative_value = tentative.get(key).cloned();
+                if committed_value == tentative_value {
+                    None
+                } else {
+                    Some(ConfigDiffEntry {
+                        key: key.clone(),
+                        committed: committed_value,
+                        tentative: tentative_value,
+                    })
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for ConfigStore {
+    fn default() -> Self {
+        Self::new(5_000)
+    }
+}
+
+pub fn current_unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absent_check_succeeds_on_first_write() {
+        let store = ConfigStore::new(5_000);
+        store.propose(
+            "theme".to_string(),
+            "dark".to_string(),
+            DependencyCheck::Absent,
+            MergeProcedure::LastWriterWins,
+            "writer-a".to_string(),
+            1_000,
+        );
+        let tentative = store.tentative_view(1_000);
+        assert_eq!(tentative["theme"].value, "dark");
+        assert_eq!(tentative["theme"].version, 0);
+    }
+
+    #[test]
+    fn failed_dependency_check_runs_merge_procedure() {
+        let store = ConfigStore::new(5_000);
+        store.propose(
+            "theme".to_string(),
+            "dark".to_string(),
+            DependencyCheck::Absent,
+            MergeProcedure::LastWriterWins,
+            "writer-a".to_string(),
+            1_000,
+        );
+        store.propose(
+            "theme".to_string(),
+            "light".to_string(),
+            DependencyCheck::Absent,
+            MergeProcedure::ConflictMarker {
+                marker: "!conflict!".to_string(),
+            },
+            "writer-b".to_string(),
+            2_000,
+        );
+        let tentative = store.tentative_view(2_000);
+        assert_eq!(tentative["theme"].value, "!conflict!");
+    }
+
+    #[test]
+    fn late_op_reorders_tentative_replay() {
+        let store = ConfigStore::new(5_000);
+        store.propose(
+            "theme".to_string(),
+            "dark".to_string(),
+            DependencyCheck::Absent,
+            MergeProcedure::LastWriterWins,
+            "writer-a".to_string(),
+            2_000,
+        );
+        // A late op stamped *before* the one above arrives after it.
+        store.propose(
+            "theme".to_string(),
+            "light".to_string(),
+            DependencyCheck::Absent,
+            MergeProcedure::ConflictMarker {
+                marker: "!conflict!".to_string(),
+            },
+            "writer-b".to_string(),
+            1_000,
+        );
+        // Replayed in accept-stamp order: the 1_000 op sees "theme" absent
+        // and wins; the 2_000 op then sees it present and its own Absent
+        // check fails, so it falls back to last-writer-wins anyway.
+        let tentative = store.tentative_view(2_000);
+        assert_eq!(tentative["theme"].value, "dark");
+        assert_eq!(tentative["theme"].version, 1);
+    }
+
+    #[test]
+    fn diff_reports_only_pending_keys() {
+        let store = ConfigStore::new(5_000);
+        store.propose(
+            "theme".to_string(),
+            "dark".to_string(),
+            DependencyCheck::Absent,
+            MergeProcedure::LastWriterWins,
+            "writer-a".to_string(),
+            1_000,
+        );
+        // Still within the commit horizon at now_ms=1_500, so nothing is
+        // committed yet and the key shows up as pending.
+        let pending = store.diff(1_500);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].key, "theme");
+        assert!(pending[0].committed.is_none());
+
+        // Past the commit horizon, the op has stabilized into committed.
+        let settled = store.diff(10_000);
+        assert!(settled.is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+//! Asynchronous task/job tracking for `POST /tasks`. Unlike `send_task`,
+//! which blocks the HTTP request until `adapter.send` returns, submitting a
+//! job reserves an agent up front and returns immediately — a background
+//! worker advances the job through [`JobState`] as the adapter call
+//! resolves, and a caller polls `GET /tasks/:id` for the result.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use clawlab_core::AgentResponse;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: String,
+    pub agent_id: String,
+    pub message: String,
+    pub state: JobState,
+    pub result: Option<AgentResponse>,
+    pub error: Option<String>,
+    pub created_unix_ms: u64,
+    pub finished_unix_ms: Option<u64>,
+}
+
+/// In-memory job history, mirroring `crate::manager::LifecycleManager`'s
+/// `next_id`/`HashMap` shape — job history doesn't need to survive a
+/// restart any more than the fleet state it's dispatched against does.
+#[derive(Default)]
+pub struct JobStore {
+    jobs: HashMap<String, JobRecord>,
+    next_id: AtomicU64,
+}
+
+impl JobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a job as `Queued` against `agent_id`, which the caller must
+    /// already have selected (via `LifecycleManager::select_agent`) before
+    /// calling this, so capacity is reserved at submit time rather than
+    /// whenever the background worker happens to run.
+    pub fn create(&mut self, agent_id: String, message: String, now_unix_ms: u64) -> JobRecord {
+        let id = format!("job-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let record = JobRecord {
+            id: id.clone(),
+            agent_id,
+            message,
+            state: JobState::Queued,
+            result: None,
+            error: None,
+            created_unix_ms: now_unix_ms,
+            finished_unix_ms: None,
+        };
+        self.jobs.insert(id, record.clone());
+        record
+    }
+
+    pub fn mark_running(&mut self, id: &str) {
+        if let Some(record) = self.jobs.get_mut(id) {
+            record.state = JobState::Running;
+        }
+    }
+
+    pub fn mark_finished(&mut self, id: &str, result: AgentResponse, now_unix_ms: u64) {
+        if let Some(record) = self.jobs.get_mut(id) {
+            record.state = JobState::Finished;
+            record.result = Some(result);
+            record.finished_unix_ms = Some(now_unix_ms);
+        }
+    }
+
+    pub fn mark_failed(&mut self, id: &str, error: String, now_unix_ms: u64) {
+        if let Some(record) = self.jobs.get_mut(id) {
+            record.state = JobState::Failed;
+            record.error = Some(error);
+            record.finished_unix_ms = Some(now_unix_ms);
+        }
+    }
+
+    /// Cancels a still-`Queued` job, returning `false` if it had already
+    /// started (or doesn't exist) — once the background worker has picked
+    /// a job up there's no way to un-dispatch it.
+    pub fn cancel_if_queued(&mut self, id: &str) -> bool {
+        match self.jobs.get(id) {
+            Some(record) if record.state == JobState::Queued => {
+                self.jobs.remove(id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobRecord> {
+        self.jobs.get(id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<JobRecord> {
+        let mut jobs: Vec<_> = self.jobs.values().cloned().collect();
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        jobs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_transitions_from_queued_to_finished() {
+        let mut store = JobStore::new();
+        let job = store.create("agent-1".to_string(), "hello".to_string(), 100);
+        assert_eq!(job.state, JobState::Queued);
+
+        store.mark_running(&job.id);
+        store.mark_finished(
+            &job.id,
+            AgentResponse {
+                content: "hi back".to_string(),
+            },
+            150,
+        );
+
+        let updated = store.get(&job.id).expect("job should still exist");
+        assert_eq!(updated.state, JobState::Finished);
+        assert_eq!(updated.finished_unix_ms, Some(150));
+    }
+
+    #[test]
+    fn cancel_if_queued_refuses_once_running() {
+        let mut store = JobStore::new();
+        let job = store.create("agent-1".to_string(), "hello".to_string(), 100);
+        store.mark_running(&job.id);
+
+        assert!(!store.cancel_if_queued(&job.id));
+        assert!(store.get(&job.id).is_some());
+    }
+}
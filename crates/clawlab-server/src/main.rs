@@ -1,15 +1,25 @@
 mod api;
 mod audit;
+mod batch;
+mod config;
+mod jobs;
 mod lifecycle;
 mod manager;
+mod otel;
+mod scheduler;
 
 use crate::api::{
-    audit_log, fleet_status, health_summary, list_agents, register_agent, send_task, start_agent,
-    stop_agent, AppState,
+    agent_events_stream, audit_log, cancel_task, config_diff, config_set, create_schedule,
+    delete_schedule, fleet_status, get_task, health_summary, list_agents, list_schedule,
+    list_tasks, register_agent, register_agents_batch, send_task, send_task_stream,
+    send_tasks_batch, start_agent, stop_agent, submit_task, AppState,
 };
 use crate::audit::{AuditEvent, AuditLog};
+use crate::config::ConfigStore;
+use crate::jobs::JobStore;
 use crate::lifecycle::AgentState;
 use crate::manager::{append_audit, LifecycleManager};
+use crate::scheduler::Scheduler;
 use axum::{routing::get, Json, Router};
 use serde::Serialize;
 use std::net::SocketAddr;
@@ -40,22 +50,46 @@ async fn main() {
         .init();
 
     let audit_store = Arc::new(AuditLog::default());
-    let manager = LifecycleManager::new(clawlab_adapters::builtin_registry());
+    let manager = Arc::new(RwLock::new(LifecycleManager::new(
+        clawlab_adapters::builtin_registry(),
+    )));
+    let scheduler = Arc::new(Scheduler::new());
     let shared_state = AppState {
-        manager: Arc::new(RwLock::new(manager)),
+        manager: manager.clone(),
         audit: audit_store.clone(),
+        config: Arc::new(ConfigStore::default()),
+        scheduler: scheduler.clone(),
+        jobs: Arc::new(RwLock::new(JobStore::new())),
     };
 
+    Scheduler::spawn_tick_loop(scheduler, manager, audit_store.clone());
+
     let app = Router::new()
         .route("/health", get(health))
         .route("/agents", get(list_agents))
         .route("/agents/register", axum::routing::post(register_agent))
+        .route("/agents:batch", axum::routing::post(register_agents_batch))
         .route("/agents/{agent_id}/start", axum::routing::post(start_agent))
         .route("/agents/{agent_id}/stop", axum::routing::post(stop_agent))
+        .route("/agents/{agent_id}/events", get(agent_events_stream))
         .route("/agents/health", get(health_summary))
         .route("/fleet/status", get(fleet_status))
         .route("/task/send", axum::routing::post(send_task))
+        .route("/tasks:batch", axum::routing::post(send_tasks_batch))
+        .route("/task/send/stream", axum::routing::post(send_task_stream))
         .route("/audit", get(audit_log))
+        .route("/config", axum::routing::post(config_set))
+        .route("/config/diff", get(config_diff))
+        .route(
+            "/schedule",
+            axum::routing::post(create_schedule).get(list_schedule),
+        )
+        .route("/schedule/{schedule_id}", axum::routing::delete(delete_schedule))
+        .route("/tasks", axum::routing::post(submit_task).get(list_tasks))
+        .route(
+            "/tasks/{job_id}",
+            get(get_task).delete(cancel_task),
+        )
         .with_state(shared_state);
     let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
 
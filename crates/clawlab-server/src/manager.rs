@@ -4,12 +4,59 @@ use std::sync::Arc;
 
 use clawlab_adapters::AdapterRegistry;
 use clawlab_core::{
-    AgentConfig, AgentHandle, AgentMessage, AgentResponse, ClawRuntime, HealthStatus,
+    AgentConfig, AgentHandle, AgentMessage, AgentMetrics, AgentResponse, ClawRuntime, HealthStatus,
 };
 use serde::Serialize;
+use thiserror::Error;
+use tracing::Instrument;
 
 use crate::audit::{AuditEvent, AuditLog};
 use crate::lifecycle::AgentState;
+#[cfg(feature = "otel")]
+use crate::otel;
+
+/// Structured errors for [`LifecycleManager`]'s agent-facing methods.
+/// Replaces the `Result<_, String>` these used to return so handlers can
+/// map an error variant to an HTTP status (via `ClawdenError`'s
+/// `IntoResponse` impl in `api.rs`) instead of string-matching a message.
+/// `Display` keeps the plain strings these methods returned before for the
+/// not-found, no-adapter, not-running, no-handle and no-eligible-agent
+/// cases. `InvalidTransition` prints the target state's `Debug` name
+/// (`Running`), and `AdapterFailure` replaces the old start/stop/send
+/// prefixes with `adapter call failed:`.
+#[derive(Debug, Error)]
+pub enum ClawdenError {
+    #[error("agent {0} not found")]
+    AgentNotFound(String),
+    #[error("no adapter registered for runtime {0:?}")]
+    NoAdapter(ClawRuntime),
+    #[error("invalid state transition from {from:?} to {to:?}")]
+    InvalidTransition { from: AgentState, to: AgentState },
+    #[error("agent {0} is not running")]
+    NotRunning(String),
+    #[error("agent {0} has no active handle")]
+    NoHandle(String),
+    #[error("no running agent matches required capabilities")]
+    NoEligibleAgent,
+    #[error("adapter call failed: {0}")]
+    AdapterFailure(String),
+}
+
+impl ClawdenError {
+    /// Stable, machine-readable code a client can branch on, independent of
+    /// `Display`'s human-readable wording.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClawdenError::AgentNotFound(_) => "agent_not_found",
+            ClawdenError::NoAdapter(_) => "no_adapter",
+            ClawdenError::InvalidTransition { .. } => "invalid_transition",
+            ClawdenError::NotRunning(_) => "not_running",
+            ClawdenError::NoHandle(_) => "no_handle",
+            ClawdenError::NoEligibleAgent => "no_eligible_agent",
+            ClawdenError::AdapterFailure(_) => "adapter_failure",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct AgentRecord {
@@ -20,6 +67,7 @@ pub struct AgentRecord {
     pub state: AgentState,
     pub task_count: u64,
     pub health: HealthStatus,
+    pub consecutive_health_failures: u32,
 }
 
 pub struct LifecycleManager {
@@ -51,6 +99,7 @@ impl LifecycleManager {
             state: AgentState::Registered,
             task_count: 0,
             health: HealthStatus::Unknown,
+            consecutive_health_failures: 0,
         };
         self.agents.insert(id, record.clone());
         record
@@ -62,22 +111,24 @@ impl LifecycleManager {
         agents
     }
 
-    pub async fn start_agent(&mut self, agent_id: &str) -> Result<AgentRecord, String> {
+    #[tracing::instrument(skip(self), fields(agent_id = %agent_id, runtime = tracing::field::Empty))]
+    pub async fn start_agent(&mut self, agent_id: &str) -> Result<AgentRecord, ClawdenError> {
         let Some(record) = self.agents.get_mut(agent_id) else {
-            return Err(format!("agent {agent_id} not found"));
+            return Err(ClawdenError::AgentNotFound(agent_id.to_string()));
         };
+        tracing::Span::current().record("runtime", tracing::field::debug(&record.runtime));
 
         let Some(adapter) = self.adapters.get(&record.runtime) else {
-            return Err(format!("no adapter registered for runtime {:?}", record.runtime));
+            return Err(ClawdenError::NoAdapter(record.runtime.clone()));
         };
 
         if !record.state.can_transition_to(AgentState::Running)
             && record.state != AgentState::Registered
         {
-            return Err(format!(
-                "invalid state transition from {:?} to running",
-                record.state
-            ));
+            return Err(ClawdenError::InvalidTransition {
+                from: record.state,
+                to: AgentState::Running,
+            });
         }
 
         let config = AgentConfig {
@@ -89,7 +140,7 @@ impl LifecycleManager {
         let handle = adapter
             .start(&config)
             .await
-            .map_err(|e| format!("failed to start agent: {e}"))?;
+            .map_err(|e| ClawdenError::AdapterFailure(e.to_string()))?;
 
         record.state = AgentState::Running;
         record.health = HealthStatus::Unknown;
@@ -97,10 +148,12 @@ impl LifecycleManager {
         Ok(record.clone())
     }
 
-    pub async fn stop_agent(&mut self, agent_id: &str) -> Result<AgentRecord, String> {
+    #[tracing::instrument(skip(self), fields(agent_id = %agent_id, runtime = tracing::field::Empty))]
+    pub async fn stop_agent(&mut self, agent_id: &str) -> Result<AgentRecord, ClawdenError> {
         let Some(record) = self.agents.get_mut(agent_id) else {
-            return Err(format!("agent {agent_id} not found"));
+            return Err(ClawdenError::AgentNotFound(agent_id.to_string()));
         };
+        tracing::Span::current().record("runtime", tracing::field::debug(&record.runtime));
 
         let Some(handle) = self.handles.get(agent_id) else {
             if record.state.can_transition_to(AgentState::Stopped) {
@@ -110,13 +163,13 @@ impl LifecycleManager {
         };
 
         let Some(adapter) = self.adapters.get(&record.runtime) else {
-            return Err(format!("no adapter registered for runtime {:?}", record.runtime));
+            return Err(ClawdenError::NoAdapter(record.runtime.clone()));
         };
 
         adapter
             .stop(handle)
             .await
-            .map_err(|e| format!("failed to stop agent: {e}"))?;
+            .map_err(|e| ClawdenError::AdapterFailure(e.to_string()))?;
 
         self.handles.remove(agent_id);
         if record.state.can_transition_to(AgentState::Stopped) {
@@ -140,63 +193,227 @@ impl LifecycleManager {
                 continue;
             };
             match adapter.health(handle).await {
-                Ok(health) => record.health = health,
+                Ok(health) => {
+                    record.health = health;
+                    record.consecutive_health_failures = 0;
+                }
                 Err(_) => {
                     record.health = HealthStatus::Degraded;
+                    record.consecutive_health_failures =
+                        record.consecutive_health_failures.saturating_add(1);
                     if record.state.can_transition_to(AgentState::Degraded) {
                         record.state = AgentState::Degraded;
                     }
                 }
             }
+
+            #[cfg(feature = "otel")]
+            if let Ok(AgentMetrics {
+                cpu_percent,
+                memory_mb,
+                queue_depth,
+            }) = adapter.metrics(handle).await
+            {
+                otel::record_agent_metrics(
+                    &id,
+                    &format!("{:?}", record.runtime),
+                    cpu_percent,
+                    memory_mb,
+                    queue_depth,
+                );
+            }
+        }
+
+        let agents = self.list_agents();
+
+        #[cfg(feature = "otel")]
+        {
+            let running = agents
+                .iter()
+                .filter(|agent| agent.state == AgentState::Running)
+                .count() as u64;
+            let degraded = agents
+                .iter()
+                .filter(|agent| agent.state == AgentState::Degraded)
+                .count() as u64;
+            otel::record_fleet_counts(running, degraded);
+        }
+
+        agents
+    }
+
+    /// Attempts to bring every `Degraded` agent back to `Running` by
+    /// re-running its health check, promoting it back on success — unlike
+    /// [`Self::refresh_health`], which only records whatever health state
+    /// it observes, this is the recovery half scheduled callers want fired
+    /// on a timer.
+    pub async fn recover_degraded(&mut self) -> Vec<AgentRecord> {
+        let ids: Vec<String> = self
+            .agents
+            .values()
+            .filter(|agent| agent.state == AgentState::Degraded)
+            .map(|agent| agent.id.clone())
+            .collect();
+
+        for id in ids {
+            let Some(record) = self.agents.get(&id) else {
+                continue;
+            };
+            let Some(handle) = self.handles.get(&id) else {
+                continue;
+            };
+            let Some(adapter) = self.adapters.get(&record.runtime) else {
+                continue;
+            };
+
+            let span = tracing::info_span!(
+                "recover_degraded",
+                agent_id = %id,
+                runtime = ?record.runtime,
+                consecutive_health_failures = record.consecutive_health_failures,
+            );
+            let health = adapter.health(handle).instrument(span).await;
+
+            if let Ok(HealthStatus::Healthy) = health {
+                if let Some(record) = self.agents.get_mut(&id) {
+                    record.health = HealthStatus::Healthy;
+                    record.consecutive_health_failures = 0;
+                    if record.state.can_transition_to(AgentState::Running) {
+                        record.state = AgentState::Running;
+                    }
+                }
+            }
         }
 
         self.list_agents()
     }
 
+    #[tracing::instrument(
+        skip(self, required_capabilities, message, target_agent_id),
+        fields(agent_id = tracing::field::Empty, runtime = tracing::field::Empty)
+    )]
     pub async fn route_and_send(
         &mut self,
         required_capabilities: &[String],
         message: String,
         target_agent_id: Option<String>,
-    ) -> Result<(AgentRecord, AgentResponse), String> {
+    ) -> Result<(AgentRecord, AgentResponse), ClawdenError> {
         let selected_id = if let Some(id) = target_agent_id {
             id
         } else {
             self.select_agent(required_capabilities)?
         };
 
-        let Some(record) = self.agents.get_mut(&selected_id) else {
-            return Err(format!("agent {selected_id} not found"));
+        let span = tracing::Span::current();
+        span.record("agent_id", selected_id.as_str());
+        if let Some(record) = self.agents.get(&selected_id) {
+            span.record("runtime", tracing::field::debug(&record.runtime));
+        }
+
+        let response = self.dispatch_to_agent(&selected_id, message)?.await?;
+        let record = self
+            .agents
+            .get(&selected_id)
+            .cloned()
+            .ok_or(ClawdenError::AgentNotFound(selected_id))?;
+        Ok((record, response))
+    }
+
+    /// Validates and records a dispatch to an already-selected,
+    /// already-`Running` agent, then hands back the (potentially slow)
+    /// adapter call as a future the caller awaits separately. Shared by
+    /// [`Self::route_and_send`] (which selects the agent inline) and the
+    /// `/tasks` job worker (which selects the agent at submit time via
+    /// [`Self::select_agent`] and dispatches later, in the background) —
+    /// `task_count` increments here, at dispatch, not at submission.
+    ///
+    /// Deliberately *not* an `async fn`: the record/handle/adapter lookups
+    /// and the `task_count` bump are the only part that needs `&mut self`,
+    /// and they're synchronous, so this method returns as soon as they're
+    /// done. The returned future owns clones of `handle`/`adapter` instead
+    /// of borrowing `self`, so a caller holding `self` behind a shared
+    /// `RwLock` can drop the write guard before awaiting it — otherwise
+    /// (as this used to work) the guard would stay held for the adapter
+    /// call's full round-trip, serializing every other fleet operation
+    /// behind one in-flight dispatch.
+    pub fn dispatch_to_agent(
+        &mut self,
+        agent_id: &str,
+        message: String,
+    ) -> Result<impl std::future::Future<Output = Result<AgentResponse, ClawdenError>>, ClawdenError>
+    {
+        let Some(record) = self.agents.get_mut(agent_id) else {
+            return Err(ClawdenError::AgentNotFound(agent_id.to_string()));
         };
 
         if record.state != AgentState::Running {
-            return Err(format!("agent {} is not running", record.id));
+            return Err(ClawdenError::NotRunning(record.id.clone()));
         }
 
-        let Some(handle) = self.handles.get(&selected_id) else {
-            return Err(format!("agent {} has no active handle", record.id));
+        let Some(handle) = self.handles.get(agent_id).cloned() else {
+            return Err(ClawdenError::NoHandle(record.id.clone()));
         };
 
         let Some(adapter) = self.adapters.get(&record.runtime) else {
-            return Err(format!("no adapter registered for runtime {:?}", record.runtime));
+            return Err(ClawdenError::NoAdapter(record.runtime.clone()));
         };
 
-        let response = adapter
-            .send(
-                handle,
-                &AgentMessage {
-                    role: "user".to_string(),
-                    content: message,
-                },
-            )
-            .await
-            .map_err(|e| format!("send failed: {e}"))?;
-
         record.task_count += 1;
-        Ok((record.clone(), response))
+
+        Ok(async move {
+            adapter
+                .send(
+                    &handle,
+                    &AgentMessage {
+                        role: "user".to_string(),
+                        content: message,
+                    },
+                )
+                .await
+                .map_err(|e| ClawdenError::AdapterFailure(e.to_string()))
+        })
+    }
+
+    /// Opens a live event subscription against `agent_id`'s adapter and
+    /// hands back the resulting stream directly, rather than the
+    /// `(handle, adapter)` pair `dispatch_to_agent` works from — the SSE
+    /// handler behind `/agents/:id/events` holds this stream for the whole
+    /// connection, long after the read lock guarding this lookup is gone.
+    pub async fn subscribe_events(
+        &self,
+        agent_id: &str,
+        event: &str,
+    ) -> Result<clawlab_core::EventStream, ClawdenError> {
+        let Some(record) = self.agents.get(agent_id) else {
+            return Err(ClawdenError::AgentNotFound(agent_id.to_string()));
+        };
+
+        if record.state != AgentState::Running {
+            return Err(ClawdenError::NotRunning(record.id.clone()));
+        }
+
+        let Some(handle) = self.handles.get(agent_id) else {
+            return Err(ClawdenError::NoHandle(record.id.clone()));
+        };
+
+        let Some(adapter) = self.adapters.get(&record.runtime) else {
+            return Err(ClawdenError::NoAdapter(record.runtime.clone()));
+        };
+
+        adapter
+            .subscribe(handle, event)
+            .await
+            .map_err(|e| ClawdenError::AdapterFailure(e.to_string()))
     }
 
-    fn select_agent(&mut self, required_capabilities: &[String]) -> Result<String, String> {
+    /// Picks the next eligible agent by round-robin, reserving its turn in
+    /// the rotation immediately — used both by [`Self::route_and_send`] and
+    /// by `POST /tasks` to select (and so reserve) an agent at submit time,
+    /// before the job actually runs.
+    pub(crate) fn select_agent(
+        &mut self,
+        required_capabilities: &[String],
+    ) -> Result<String, ClawdenError> {
         let eligible: Vec<&AgentRecord> = self
             .agents
             .values()
@@ -209,7 +426,7 @@ impl LifecycleManager {
             .collect();
 
         if eligible.is_empty() {
-            return Err("no running agent matches required capabilities".to_string());
+            return Err(ClawdenError::NoEligibleAgent);
         }
 
         let idx = self.round_robin_index % eligible.len();
@@ -224,12 +441,17 @@ pub fn append_audit(audit: &Arc<AuditLog>, action: &str, target: &str) {
         .expect("system clock before UNIX_EPOCH")
         .as_millis() as u64;
 
-    audit.append(AuditEvent {
+    let event = AuditEvent {
         actor: "api".to_string(),
         action: action.to_string(),
         target: target.to_string(),
         timestamp_unix_ms: now,
-    });
+    };
+
+    #[cfg(feature = "otel")]
+    otel::record_audit_event(&event);
+
+    audit.append(event);
 }
 
 #[cfg(test)]
@@ -0,0 +1,81 @@
+//! OpenTelemetry export for fleet metrics and audit events, entirely
+//! behind the `otel` feature — every call here compiles away to nothing
+//! (and costs nothing) when the feature isn't enabled. Mirrors `clawden_config::otel_metrics`'s `OnceLock`-cached
+//! instrument pattern, scaled to this crate's fleet-level gauges rather
+//! than a single drift counter.
+#![cfg(feature = "otel")]
+
+use std::sync::OnceLock;
+
+use opentelemetry::metrics::{Gauge, Meter};
+use opentelemetry::KeyValue;
+
+use crate::audit::AuditEvent;
+
+fn meter() -> &'static Meter {
+    static METER: OnceLock<Meter> = OnceLock::new();
+    METER.get_or_init(|| opentelemetry::global::meter("clawlab-server"))
+}
+
+fn agent_cpu_percent() -> &'static Gauge<f64> {
+    static GAUGE: OnceLock<Gauge<f64>> = OnceLock::new();
+    GAUGE.get_or_init(|| meter().f64_gauge("clawden_agent_cpu_percent").build())
+}
+
+fn agent_memory_mb() -> &'static Gauge<f64> {
+    static GAUGE: OnceLock<Gauge<f64>> = OnceLock::new();
+    GAUGE.get_or_init(|| meter().f64_gauge("clawden_agent_memory_mb").build())
+}
+
+fn agent_queue_depth() -> &'static Gauge<u64> {
+    static GAUGE: OnceLock<Gauge<u64>> = OnceLock::new();
+    GAUGE.get_or_init(|| meter().u64_gauge("clawden_agent_queue_depth").build())
+}
+
+fn fleet_running_agents() -> &'static Gauge<u64> {
+    static GAUGE: OnceLock<Gauge<u64>> = OnceLock::new();
+    GAUGE.get_or_init(|| meter().u64_gauge("clawden_fleet_running_agents").build())
+}
+
+fn fleet_degraded_agents() -> &'static Gauge<u64> {
+    static GAUGE: OnceLock<Gauge<u64>> = OnceLock::new();
+    GAUGE.get_or_init(|| meter().u64_gauge("clawden_fleet_degraded_agents").build())
+}
+
+/// Records one agent's latest CPU/memory/queue-depth sample, tagged with
+/// `agent.id` and `runtime` so a dashboard can break fleet-wide gauges down
+/// per agent. Called from `LifecycleManager::refresh_health` once per
+/// agent with a handle and adapter whose `metrics` call succeeds, whatever
+/// its health check returned.
+pub(crate) fn record_agent_metrics(agent_id: &str, runtime: &str, cpu_percent: f32, memory_mb: f32, queue_depth: u32) {
+    let attrs = [
+        KeyValue::new("agent.id", agent_id.to_string()),
+        KeyValue::new("runtime", runtime.to_string()),
+    ];
+    agent_cpu_percent().record(cpu_percent as f64, &attrs);
+    agent_memory_mb().record(memory_mb as f64, &attrs);
+    agent_queue_depth().record(queue_depth as u64, &attrs);
+}
+
+/// Records the fleet-wide running/degraded counts observed at the end of a
+/// `refresh_health` sweep.
+pub(crate) fn record_fleet_counts(running: u64, degraded: u64) {
+    fleet_running_agents().record(running, &[]);
+    fleet_degraded_agents().record(degraded, &[]);
+}
+
+/// Emits `event` as a structured log record (`actor`/`action`/`target`
+/// attributes) for whatever log exporter the process's OTLP pipeline is
+/// configured with — piggybacks on `tracing`, the same way `main.rs`
+/// already logs the startup audit event, rather than standing up a second
+/// logging path just for this.
+pub(crate) fn record_audit_event(event: &AuditEvent) {
+    tracing::info!(
+        target: "clawlab.audit",
+        actor = %event.actor,
+        action = %event.action,
+        target = %event.target,
+        timestamp_unix_ms = event.timestamp_unix_ms,
+        "audit event recorded"
+    );
+}
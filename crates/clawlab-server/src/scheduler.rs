@@ -0,0 +1,176 @@
+//! Timer-driven execution of recurring lifecycle actions — health refresh,
+//! degraded-agent recovery, and scheduled task sends — against the shared
+//! `LifecycleManager`, so these stop depending on an operator hitting the
+//! equivalent REST endpoint by hand.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+
+use crate::audit::AuditLog;
+use crate::manager::{append_audit, LifecycleManager};
+
+/// What a [`ScheduleEntry`] does when it fires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    RefreshHealth,
+    RecoverDegraded,
+    SendTask {
+        #[serde(default)]
+        required_capabilities: Vec<String>,
+        message: String,
+    },
+}
+
+impl ScheduledAction {
+    fn audit_action_name(&self) -> &'static str {
+        match self {
+            ScheduledAction::RefreshHealth => "schedule.refresh-health",
+            ScheduledAction::RecoverDegraded => "schedule.recover-degraded",
+            ScheduledAction::SendTask { .. } => "schedule.send-task",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleEntry {
+    pub id: String,
+    pub interval_ms: u64,
+    pub next_run_unix_ms: u64,
+    pub action: ScheduledAction,
+    /// Guards against overlapping fires of the same entry: a tick that
+    /// finds this already set skips the entry entirely rather than queuing
+    /// a second concurrent run behind the one still in flight.
+    #[serde(skip)]
+    running: Arc<AtomicBool>,
+}
+
+/// Holds every registered [`ScheduleEntry`] and drives them on a fixed
+/// polling cadence via [`Self::tick`].
+pub struct Scheduler {
+    entries: RwLock<Vec<ScheduleEntry>>,
+    next_id: AtomicU64,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn add(&self, interval_ms: u64, action: ScheduledAction, now_unix_ms: u64) -> ScheduleEntry {
+        let id = format!("schedule-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let entry = ScheduleEntry {
+            id,
+            interval_ms,
+            next_run_unix_ms: now_unix_ms + interval_ms,
+            action,
+            running: Arc::new(AtomicBool::new(false)),
+        };
+        self.entries.write().await.push(entry.clone());
+        entry
+    }
+
+    pub async fn list(&self) -> Vec<ScheduleEntry> {
+        self.entries.read().await.clone()
+    }
+
+    pub async fn remove(&self, id: &str) -> bool {
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|entry| entry.id != id);
+        entries.len() != before
+    }
+
+    /// Runs every entry whose `next_run_unix_ms` has passed, against
+    /// `manager`, recording an audit event per fired action. A failed
+    /// action still reschedules rather than dropping the entry — a
+    /// transient adapter failure shouldn't silently disable recovery.
+    pub async fn tick(
+        &self,
+        manager: &Arc<RwLock<LifecycleManager>>,
+        audit: &Arc<AuditLog>,
+        now_unix_ms: u64,
+    ) {
+        let due: Vec<ScheduleEntry> = {
+            let mut entries = self.entries.write().await;
+            let mut due = Vec::new();
+            for entry in entries.iter_mut() {
+                if entry.next_run_unix_ms > now_unix_ms {
+                    continue;
+                }
+                if entry.running.swap(true, Ordering::AcqRel) {
+                    // Prior run for this entry hasn't finished; skip this
+                    // tick rather than queue a second concurrent fire.
+                    continue;
+                }
+                entry.next_run_unix_ms = now_unix_ms + entry.interval_ms;
+                due.push(entry.clone());
+            }
+            due
+        };
+
+        for entry in due {
+            Self::fire(&entry, manager, audit).await;
+            entry.running.store(false, Ordering::Release);
+        }
+    }
+
+    async fn fire(entry: &ScheduleEntry, manager: &Arc<RwLock<LifecycleManager>>, audit: &Arc<AuditLog>) {
+        match &entry.action {
+            ScheduledAction::RefreshHealth => {
+                manager.write().await.refresh_health().await;
+            }
+            ScheduledAction::RecoverDegraded => {
+                manager.write().await.recover_degraded().await;
+            }
+            ScheduledAction::SendTask {
+                required_capabilities,
+                message,
+            } => {
+                let _ = manager
+                    .write()
+                    .await
+                    .route_and_send(required_capabilities, message.clone(), None)
+                    .await;
+            }
+        }
+        append_audit(audit, entry.action.audit_action_name(), &entry.id);
+    }
+
+    /// Spawns the background tick loop at a fixed 1-second polling
+    /// cadence — fine-grained enough for sub-minute `interval_ms` entries
+    /// without busy-looping.
+    pub fn spawn_tick_loop(
+        scheduler: Arc<Scheduler>,
+        manager: Arc<RwLock<LifecycleManager>>,
+        audit: Arc<AuditLog>,
+    ) {
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(1));
+            loop {
+                ticker.tick().await;
+                scheduler.tick(&manager, &audit, current_unix_ms()).await;
+            }
+        });
+    }
+}
+
+fn current_unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before UNIX_EPOCH")
+        .as_millis() as u64
+}